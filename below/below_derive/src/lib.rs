@@ -85,6 +85,11 @@ pub fn enum_iter_derive(input: TokenStream) -> TokenStream {
 ///     Name used for generating enum variant instead of the original one. Must
 ///     be a valid field name for struct (not quoted).
 ///
+/// #[queriable(alias = age)]
+///     Also create a variant for the field under another name, querying the
+///     same value. Used to render one value in two ways. Not supported for
+///     subquery fields.
+///
 /// Example:
 ///
 /// #[derive(::below_derive::Queriable)]
@@ -32,6 +32,7 @@ mod kw {
     custom_keyword!(ignore);
     custom_keyword!(subquery);
     custom_keyword!(preferred_name);
+    custom_keyword!(alias);
 }
 
 pub enum StructMeta {
@@ -67,6 +68,10 @@ pub enum FieldMeta {
         kw: kw::preferred_name,
         value: Ident,
     },
+    Alias {
+        kw: kw::alias,
+        value: Ident,
+    },
 }
 
 impl Parse for FieldMeta {
@@ -81,6 +86,11 @@ impl Parse for FieldMeta {
             let _: Token![=] = input.parse()?;
             let value = input.parse()?;
             Ok(FieldMeta::PreferredName { kw, value })
+        } else if lookahead.peek(kw::alias) {
+            let kw = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            let value = input.parse()?;
+            Ok(FieldMeta::Alias { kw, value })
         } else {
             Err(lookahead.error())
         }
@@ -93,6 +103,7 @@ impl Spanned for FieldMeta {
             FieldMeta::Ignore(kw) => kw.span,
             FieldMeta::Subquery(kw) => kw.span,
             FieldMeta::PreferredName { kw, .. } => kw.span,
+            FieldMeta::Alias { kw, .. } => kw.span,
         }
     }
 }
@@ -132,6 +143,7 @@ struct QueriableFieldProps {
     pub preferred_name: Ident,
     pub ident: Ident,
     pub variant_name: Ident,
+    pub alias_variant_name: Option<Ident>,
     pub option_type: Option<syn::Type>,
 }
 
@@ -142,6 +154,8 @@ fn get_queriable_field_props(field: &Field) -> syn::Result<QueriableFieldProps>
     let mut ignore_kw = None;
     let mut subquery_kw = None;
     let mut preferred_name_kw = None;
+    let mut alias = None;
+    let mut alias_kw = None;
     let option_type = parse_option(&field.ty);
     for meta in get_metadata("queriable", &field.attrs)? {
         match meta {
@@ -171,20 +185,35 @@ fn get_queriable_field_props(field: &Field) -> syn::Result<QueriableFieldProps>
                 preferred_name_kw = Some(kw);
                 preferred_name = Some(value.clone());
             }
+            FieldMeta::Alias { value, kw } => {
+                if let Some(fst_kw) = alias_kw {
+                    return Err(occurrence_error(fst_kw, kw, "alias"));
+                }
+                alias_kw = Some(kw);
+                alias = Some(value.clone());
+            }
         }
     }
+    if let (Some(kw), Some(_)) = (alias_kw, &subquery) {
+        return Err(syn::Error::new(
+            kw.span,
+            "queriable(alias) is not supported for subquery fields",
+        ));
+    }
     let ident = field
         .ident
         .clone()
         .ok_or_else(|| syn::Error::new(field.span(), "This macro only support named fields"))?;
     let preferred_name = preferred_name.unwrap_or_else(|| ident.clone());
     let variant_name = to_camelcase(&preferred_name);
+    let alias_variant_name = alias.as_ref().map(to_camelcase);
     Ok(QueriableFieldProps {
         ignore,
         subquery,
         preferred_name,
         ident,
         variant_name,
+        alias_variant_name,
         option_type,
     })
 }
@@ -217,11 +246,15 @@ pub fn queriable_derive_impl(ast: &DeriveInput) -> syn::Result<TokenStream> {
 
     let field_id_variants = all_field_props.iter().map(|field_props| {
         let variant_name = &field_props.variant_name;
-        match &field_props.subquery {
-            Some(subquery_field_id_type) => quote! {
+        match (&field_props.subquery, &field_props.alias_variant_name) {
+            (Some(subquery_field_id_type), _) => quote! {
                 #variant_name(#subquery_field_id_type),
             },
-            None => quote! {
+            (None, Some(alias_variant_name)) => quote! {
+                #variant_name,
+                #alias_variant_name,
+            },
+            (None, None) => quote! {
                 #variant_name,
             },
         }
@@ -243,7 +276,12 @@ pub fn queriable_derive_impl(ast: &DeriveInput) -> syn::Result<TokenStream> {
             } else {
                 quote! { std::option::Option::Some(Field::from(&self.#field_ident)) }
             };
-            quote! { Self::FieldId::#variant_name => #query, }
+            match &field_props.alias_variant_name {
+                Some(alias_variant_name) => quote! {
+                    Self::FieldId::#variant_name | Self::FieldId::#alias_variant_name => #query,
+                },
+                None => quote! { Self::FieldId::#variant_name => #query, },
+            }
         }
     });
 
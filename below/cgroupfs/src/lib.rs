@@ -130,11 +130,11 @@ impl CgroupReader {
         self.read_singleline_stat_file("memory.current")
     }
 
-    /// Read memory.high - returning memory.high consumption in bytes
-    /// Will return -1 if the content is max
-    /// Will return None if the file is missing
-    pub fn read_memory_high(&self) -> Result<Option<i64>> {
-        match self.read_singleline_stat_file("memory.high") {
+    /// Read a memory limit file like memory.high in bytes. Will return -1 if
+    /// the content is max and None if the file is missing, e.g. in the root
+    /// cgroup
+    fn read_memory_limit(&self, file_name: &str) -> Result<Option<i64>> {
+        match self.read_singleline_stat_file(file_name) {
             Ok(v) => Ok(Some(v as i64)),
             Err(Error::IoError(_, e)) if e.kind() == ErrorKind::NotFound => Ok(None),
             Err(Error::UnexpectedLine(_, line)) if line.starts_with("max") => Ok(Some(-1)),
@@ -142,12 +142,103 @@ impl CgroupReader {
         }
     }
 
+    /// Read memory.high - returning memory.high consumption in bytes
+    /// Will return -1 if the content is max
+    /// Will return None if the file is missing
+    pub fn read_memory_high(&self) -> Result<Option<i64>> {
+        self.read_memory_limit("memory.high")
+    }
+
+    /// Read memory.max - returning the hard memory limit in bytes
+    /// Will return -1 if the content is max
+    /// Will return None if the file is missing
+    pub fn read_memory_max(&self) -> Result<Option<i64>> {
+        self.read_memory_limit("memory.max")
+    }
+
     /// Read memory.swap.current - returning current cgroup memory
     /// swap consumption in bytes
     pub fn read_memory_swap_current(&self) -> Result<u64> {
         self.read_singleline_stat_file("memory.swap.current")
     }
 
+    /// Read cgroup.controllers - returning the controllers enabled for
+    /// this cgroup, i.e. those in the parent's cgroup.subtree_control
+    pub fn read_cgroup_controllers(&self) -> Result<Vec<String>> {
+        let file_name = "cgroup.controllers";
+        let file = self
+            .dir
+            .open_file(file_name)
+            .map_err(|e| self.io_error(file_name, e))?;
+        let buf_reader = BufReader::new(file);
+        match buf_reader.lines().next() {
+            Some(line) => {
+                let line = line.map_err(|e| self.io_error(file_name, e))?;
+                Ok(line.split_whitespace().map(|c| c.to_owned()).collect())
+            }
+            // No controller enabled
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Read cpu.weight - returning the relative share of cpu time
+    pub fn read_cpu_weight(&self) -> Result<u32> {
+        let file_name = "cpu.weight";
+        let weight = self.read_singleline_stat_file(file_name)?;
+        u32::try_from(weight).map_err(|_| self.unexpected_line(file_name, weight.to_string()))
+    }
+
+    /// Read io.weight - returning the default weight and any per device
+    /// overrides
+    pub fn read_io_weight(&self) -> Result<IoWeight> {
+        let file_name = "io.weight";
+        let file = self
+            .dir
+            .open_file(file_name)
+            .map_err(|e| self.io_error(file_name, e))?;
+        let buf_reader = BufReader::new(file);
+        let mut io_weight = IoWeight::default();
+        for line in buf_reader.lines() {
+            let line = line.map_err(|e| self.io_error(file_name, e))?;
+            // io.weight looks like:
+            // default 100
+            // 8:16 200
+            let (key, weight) = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [key, weight] => match weight.parse::<u32>() {
+                    Ok(weight) => (key.to_owned(), weight),
+                    Err(_) => return Err(self.unexpected_line(file_name, line)),
+                },
+                _ => return Err(self.unexpected_line(file_name, line)),
+            };
+            if key == "default" {
+                io_weight.default = Some(weight);
+            } else {
+                io_weight.devices.insert(key, weight);
+            }
+        }
+        if io_weight == IoWeight::default() {
+            Err(self.invalid_file_format(file_name))
+        } else {
+            Ok(io_weight)
+        }
+    }
+
+    /// Read cgroup.freeze - returning whether the cgroup is requested to be
+    /// frozen
+    pub fn read_freeze(&self) -> Result<bool> {
+        let file_name = "cgroup.freeze";
+        match self.read_singleline_stat_file(file_name)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            v => Err(self.unexpected_line(file_name, v.to_string())),
+        }
+    }
+
+    /// Read cgroup.stat - returning the number of descendant cgroups
+    pub fn read_cgroup_stat(&self) -> Result<CgroupStat> {
+        CgroupStat::read(self)
+    }
+
     /// Read cpu.stat - returning assorted cpu consumption statistics
     pub fn read_cpu_stat(&self) -> Result<CpuStat> {
         CpuStat::read(&self)
@@ -338,12 +429,22 @@ key_values_format!(MemoryStat; memory.stat; [
     pgrefill,
     pgscan,
     pgsteal,
+    pgscan_kswapd,
+    pgscan_direct,
+    pgsteal_kswapd,
+    pgsteal_direct,
     pgactivate,
     pgdeactivate,
     pglazyfree,
     pglazyfreed,
     thp_fault_alloc,
-    thp_collapse_alloc
+    thp_collapse_alloc,
+    zswap
+]);
+
+key_values_format!(CgroupStat; cgroup.stat; [
+    nr_descendants,
+    nr_dying_descendants
 ]);
 
 key_values_format!(MemoryEvents; memory.events; [
@@ -380,7 +481,8 @@ macro_rules! name_key_equal_value_format {
                         if kv.len() != 2 {
                             return Err(r.invalid_file_format(file_name));
                         }
-                        let key = kv[0];
+                        // Dotted keys like cost.usage are read into cost_usage
+                        let key = kv[0].replace('.', "_");
                         match key.as_ref() {
                             $(stringify!($field) => s.$field = Some(
                                 kv[1].parse().map_err(|_| r.unexpected_line(file_name.clone(), line.clone()))?
@@ -410,6 +512,9 @@ name_key_equal_value_format!(IoStat; true; [
     wios,
     dbytes,
     dios,
+    cost_vrate,
+    cost_usage,
+    cost_indebt,
 ]);
 
 name_key_equal_value_format!(PressureMetrics; false; [
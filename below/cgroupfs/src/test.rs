@@ -134,6 +134,30 @@ fn test_memory_swap_current_success() {
     assert_eq!(val, 1234);
 }
 
+#[test]
+fn test_cgroup_controllers_success() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content("cgroup.controllers", b"cpuset cpu io memory pids\n");
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_cgroup_controllers()
+        .expect("Failed to read cgroup.controllers");
+    assert_eq!(val, vec!["cpuset", "cpu", "io", "memory", "pids"]);
+}
+
+#[test]
+fn test_cgroup_controllers_empty() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content("cgroup.controllers", b"");
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_cgroup_controllers()
+        .expect("Failed to read cgroup.controllers");
+    assert!(val.is_empty());
+}
+
 #[test]
 fn test_memory_high_success() {
     let cgroup = TestCgroup::new();
@@ -160,6 +184,33 @@ fn test_memory_high_max_success() {
     assert_eq!(val, -1);
 }
 
+#[test]
+fn test_memory_max() {
+    let cgroup = TestCgroup::new();
+    let cgroup_reader = cgroup.get_reader();
+    assert_eq!(
+        cgroup_reader
+            .read_memory_max()
+            .expect("Failed to read memory.max"),
+        None
+    );
+
+    cgroup.create_file_with_content("memory.max", b"1073741824\n");
+    assert_eq!(
+        cgroup_reader
+            .read_memory_max()
+            .expect("Failed to read memory.max"),
+        Some(1073741824)
+    );
+    cgroup.create_file_with_content("memory.max", b"max\n");
+    assert_eq!(
+        cgroup_reader
+            .read_memory_max()
+            .expect("Failed to read memory.max"),
+        Some(-1)
+    );
+}
+
 #[test]
 fn test_memory_high_failure() {
     let cgroup = TestCgroup::new();
@@ -174,6 +225,76 @@ fn test_memory_high_failure() {
     assert!(val.is_err());
 }
 
+#[test]
+fn test_cpu_weight() {
+    let cgroup = TestCgroup::new();
+    let cgroup_reader = cgroup.get_reader();
+    assert!(cgroup_reader.read_cpu_weight().is_err());
+
+    cgroup.create_file_with_content("cpu.weight", b"250\n");
+    let val = cgroup_reader
+        .read_cpu_weight()
+        .expect("Failed to read cpu.weight");
+    assert_eq!(val, 250);
+}
+
+#[test]
+fn test_io_weight() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content("io.weight", b"default 100\n8:16 200\n8:0 50\n");
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_io_weight()
+        .expect("Failed to read io.weight");
+    assert_eq!(val.default, Some(100));
+    assert_eq!(val.devices.len(), 2);
+    assert_eq!(val.devices.get("8:16"), Some(&200));
+    assert_eq!(val.devices.get("8:0"), Some(&50));
+
+    cgroup.create_file_with_content("io.weight", b"default\n");
+    assert!(cgroup_reader.read_io_weight().is_err());
+    cgroup.create_file_with_content("io.weight", b"");
+    assert!(cgroup_reader.read_io_weight().is_err());
+}
+
+#[test]
+fn test_freeze() {
+    let cgroup = TestCgroup::new();
+    let cgroup_reader = cgroup.get_reader();
+    assert!(cgroup_reader.read_freeze().is_err());
+
+    cgroup.create_file_with_content("cgroup.freeze", b"1\n");
+    assert!(cgroup_reader
+        .read_freeze()
+        .expect("Failed to read cgroup.freeze"));
+    cgroup.create_file_with_content("cgroup.freeze", b"0\n");
+    assert!(!cgroup_reader
+        .read_freeze()
+        .expect("Failed to read cgroup.freeze"));
+    cgroup.create_file_with_content("cgroup.freeze", b"2\n");
+    assert!(cgroup_reader.read_freeze().is_err());
+}
+
+#[test]
+fn test_cgroup_stat() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content(
+        "cgroup.stat",
+        b"nr_descendants 12\nnr_dying_descendants 3\n",
+    );
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_cgroup_stat()
+        .expect("Failed to read cgroup.stat");
+    assert_eq!(val.nr_descendants, Some(12));
+    assert_eq!(val.nr_dying_descendants, Some(3));
+
+    cgroup.create_file_with_content("cgroup.stat", b"");
+    assert!(cgroup_reader.read_cgroup_stat().is_err());
+}
+
 #[test]
 fn test_memory_stat_success() {
     let cgroup = TestCgroup::new();
@@ -184,6 +305,51 @@ fn test_memory_stat_success() {
         .read_memory_stat()
         .expect("Failed to read memory.stat");
     assert_eq!(val.slab.expect("Failed to populate slab field"), 1234);
+    // Only present with zswap enabled kernels
+    assert_eq!(val.zswap, None);
+}
+
+#[test]
+fn test_memory_stat_zswap() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content(
+        "memory.stat",
+        b"slab 1234
+zswap 5678
+zswapped 9012
+",
+    );
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_memory_stat()
+        .expect("Failed to read memory.stat");
+    assert_eq!(val.zswap, Some(5678));
+}
+
+#[test]
+fn test_memory_stat_reclaim() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content(
+        "memory.stat",
+        b"pgscan 300
+pgsteal 200
+pgscan_kswapd 250
+pgscan_direct 50
+pgsteal_kswapd 180
+pgsteal_direct 20
+",
+    );
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_memory_stat()
+        .expect("Failed to read memory.stat");
+    assert_eq!(val.pgscan, Some(300));
+    assert_eq!(val.pgscan_kswapd, Some(250));
+    assert_eq!(val.pgscan_direct, Some(50));
+    assert_eq!(val.pgsteal_kswapd, Some(180));
+    assert_eq!(val.pgsteal_direct, Some(20));
 }
 
 #[test]
@@ -297,6 +463,23 @@ fn test_io_stat_success() {
     );
 }
 
+#[test]
+fn test_io_stat_cost() {
+    let cgroup = TestCgroup::new();
+    cgroup.create_file_with_content("io.stat", b"253:0 rbytes=531 wbytes=162379 rios=61 wios=81 dbytes=0 dios=0 cost.vrate=87.50 cost.usage=1234 cost.wait=56 cost.indebt=78 cost.indelay=9\n13:0 rbytes=135 wbytes=162379 rios=61 wios=81 dbytes=0 dios=0\n");
+
+    let cgroup_reader = cgroup.get_reader();
+    let val = cgroup_reader
+        .read_io_stat()
+        .expect("Failed to read io.stat");
+    assert_eq!(val["253:0"].cost_vrate, Some(87.5));
+    assert_eq!(val["253:0"].cost_usage, Some(1234));
+    assert_eq!(val["253:0"].cost_indebt, Some(78));
+    // Devices without io.cost have none of the keys
+    assert_eq!(val["13:0"].cost_vrate, None);
+    assert_eq!(val["13:0"].cost_usage, None);
+}
+
 #[test]
 fn test_io_stat_parse_failure() {
     let cgroup = TestCgroup::new();
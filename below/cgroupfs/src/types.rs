@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -32,6 +34,12 @@ pub struct IoStat {
     pub wios: Option<u64>,
     pub dbytes: Option<u64>,
     pub dios: Option<u64>,
+    /// io.cost keys, only present when the io.cost controller is enabled
+    /// for the device. cost.vrate is only reported for the root cgroup and
+    /// cost.indebt only with debug stats.
+    pub cost_vrate: Option<f64>,
+    pub cost_usage: Option<u64>,
+    pub cost_indebt: Option<u64>,
 }
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -61,12 +69,17 @@ pub struct MemoryStat {
     pub pgrefill: Option<u64>,
     pub pgscan: Option<u64>,
     pub pgsteal: Option<u64>,
+    pub pgscan_kswapd: Option<u64>,
+    pub pgscan_direct: Option<u64>,
+    pub pgsteal_kswapd: Option<u64>,
+    pub pgsteal_direct: Option<u64>,
     pub pgactivate: Option<u64>,
     pub pgdeactivate: Option<u64>,
     pub pglazyfree: Option<u64>,
     pub pglazyfreed: Option<u64>,
     pub thp_fault_alloc: Option<u64>,
     pub thp_collapse_alloc: Option<u64>,
+    pub zswap: Option<u64>,
 }
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -101,6 +114,22 @@ pub struct Pressure {
     pub memory: MemoryPressure,
 }
 
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CgroupStat {
+    pub nr_descendants: Option<u64>,
+    /// Descendants removed but still held by the kernel, e.g. by page cache
+    /// charged to them
+    pub nr_dying_descendants: Option<u64>,
+}
+
+/// Weights of io.weight. `default` applies to devices without an override.
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct IoWeight {
+    pub default: Option<u32>,
+    /// Per device overrides keyed by "<major>:<minor>"
+    pub devices: BTreeMap<String, u32>,
+}
+
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct MemoryEvents {
     pub low: Option<u64>,
@@ -22,9 +22,15 @@ const MISSING_SAMPLE_WARN_DURATION_S: u64 = 60;
 
 /// Convert from date to `SystemTime`
 pub fn system_time_from_date(date: &str) -> Result<SystemTime> {
+    system_time_from_date_at(date, dateutil::HgTime::now())
+}
+
+/// Convert from date to `SystemTime`, with keywords and relative dates
+/// resolved against `now`.
+pub fn system_time_from_date_at(date: &str, now: dateutil::HgTime) -> Result<SystemTime> {
     Ok(UNIX_EPOCH
     + Duration::from_secs(
-        dateutil::HgTime::parse(&date)
+        dateutil::HgTime::parse_at(date, now)
             .ok_or_else(|| {
                 anyhow!(
                     "Unrecognized timestamp format\n\
@@ -34,7 +40,7 @@ pub fn system_time_from_date(date: &str) -> Result<SystemTime> {
                 Relative: \"{{humantime}} ago\", e.g. 2 days 3 hr 15m 10sec ago\n\t\
                 Relative short: Mixed {{time_digit}}{{time_unit_char}}. E.g. 10m, 3d2H, 5h30s, 10m5h\n\t\
                 Absolute: \"Jan 01 23:59\", \"01/01/1970 11:59PM\", \"1970-01-01 23:59:59\"\n\t\
-                Unix Epoch: 1589808367",
+                Unix Epoch: 1589808367, in milliseconds 1589808367000 or ms:1589808367000",
                     &date
                 )
             })?
@@ -42,14 +48,8 @@ pub fn system_time_from_date(date: &str) -> Result<SystemTime> {
     ))
 }
 
-/// Convert from date and an optional days adjuster to `SystemTime`. Days
-/// adjuster is of form y[y...] to `SystemTime`. Each "y" will deduct 1 day
-/// from the resulting time.
-pub fn system_time_from_date_and_adjuster(
-    date: &str,
-    days_adjuster: Option<&str>,
-) -> Result<SystemTime> {
-    let mut time = system_time_from_date(date)?;
+/// Deduct one day from `time` for each "y" of a days adjuster.
+fn adjust_days(mut time: SystemTime, days_adjuster: Option<&str>) -> Result<SystemTime> {
     if let Some(days) = days_adjuster {
         if days.is_empty() || days.find(|c: char| c != 'y').is_some() {
             bail!("Unrecognized days adjuster format: {}", days);
@@ -60,22 +60,59 @@ pub fn system_time_from_date_and_adjuster(
     Ok(time)
 }
 
+/// Convert from date and an optional days adjuster to `SystemTime`. Days
+/// adjuster is of form y[y...] to `SystemTime`. Each "y" will deduct 1 day
+/// from the resulting time.
+pub fn system_time_from_date_and_adjuster(
+    date: &str,
+    days_adjuster: Option<&str>,
+) -> Result<SystemTime> {
+    adjust_days(system_time_from_date(date)?, days_adjuster)
+}
+
 /// Convert from date range and an optional days adjuster to a start and end
 /// `SystemTime`. Days adjuster is of form y[y...]. Each "y" will deduct 1 day
-/// from the resulting time.
+/// from the resulting time. Both dates are resolved against the same now, so
+/// e.g. "-b 1h -e now" is exactly an hour long.
 pub fn system_time_range_from_date_and_adjuster(
     start_date: &str,
     end_date: Option<&str>,
     days_adjuster: Option<&str>,
 ) -> Result<(SystemTime, SystemTime)> {
-    let start = system_time_from_date_and_adjuster(start_date, days_adjuster)?;
+    let now = dateutil::HgTime::now();
+    let start = adjust_days(system_time_from_date_at(start_date, now)?, days_adjuster)?;
     let end = match end_date {
-        Some(t) => system_time_from_date_and_adjuster(t, days_adjuster)?,
+        Some(t) => adjust_days(system_time_from_date_at(t, now)?, days_adjuster)?,
         None => SystemTime::now(),
     };
     Ok((start, end))
 }
 
+/// Snap a time range outwards to multiples of `interval` since the epoch, i.e.
+/// floor the start and ceil the end, so that ranges from different hosts line
+/// up on the same boundaries.
+pub fn align_time_range(
+    start: SystemTime,
+    end: SystemTime,
+    interval: Duration,
+) -> Result<(SystemTime, SystemTime)> {
+    let interval = interval.as_secs();
+    if interval == 0 {
+        bail!("Alignment interval must be at least 1 second");
+    }
+    let start = util::get_unix_timestamp(start);
+    let end = util::get_unix_timestamp(end);
+    let aligned_start = start - start % interval;
+    let aligned_end = match end % interval {
+        0 => end,
+        rem => end + interval - rem,
+    };
+    Ok((
+        util::get_system_time(aligned_start),
+        util::get_system_time(aligned_end),
+    ))
+}
+
 /// Check that initial sample time is within `MISSING_SAMPLE_WARN_DURATION_S`
 /// seconds of the requested start time.
 pub fn check_initial_sample_time_with_requested_time(
@@ -184,6 +221,42 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_system_time_range_from_keywords() {
+        let range = |start, end| system_time_range_from_date_and_adjuster(start, Some(end), None);
+        let (start, end) = range("now", "now").unwrap();
+        assert_eq!(start, end);
+        let (start, end) = range("1h ago", "now").unwrap();
+        assert_eq!(
+            end.duration_since(start).unwrap(),
+            Duration::from_secs(3600)
+        );
+        let (start, end) = range("yesterday", "now").unwrap();
+        assert!(end.duration_since(start).unwrap() >= Duration::from_secs(86400 - 3600));
+    }
+
+    #[test]
+    fn test_align_time_range() {
+        let align = |start, end, interval| {
+            align_time_range(t(start), t(end), Duration::from_secs(interval)).unwrap()
+        };
+        assert_eq!(
+            align("2006-02-01 13:02:30 UTC", "2006-02-01 13:07:10 UTC", 300),
+            (t("2006-02-01 13:00:00 UTC"), t("2006-02-01 13:10:00 UTC"))
+        );
+        // Already aligned times are kept
+        assert_eq!(
+            align("2006-02-01 13:00:00 UTC", "2006-02-01 14:00:00 UTC", 3600),
+            (t("2006-02-01 13:00:00 UTC"), t("2006-02-01 14:00:00 UTC"))
+        );
+        assert!(align_time_range(
+            SystemTime::now(),
+            SystemTime::now(),
+            Duration::from_millis(500)
+        )
+        .is_err());
+    }
+
     /// Convert date to `SystemTime`
     fn t(h: &str) -> SystemTime {
         system_time_from_date(h).unwrap()
@@ -115,19 +115,28 @@ impl HgTime {
     /// some additional forms like `2 days ago`.
     /// It can also handle future forms like "ten hours from now" and "+10h"
     pub fn parse(date: &str) -> Option<Self> {
+        Self::parse_at(date, Self::now())
+    }
+
+    /// Parse a date string like `parse`, but with keywords like "now" and
+    /// "today" and relative forms like "2h ago" resolved against `now`
+    /// instead of the current time. Dates parsed against the same `now` do
+    /// not drift apart while parsing.
+    pub fn parse_at(date: &str, now: Self) -> Option<Self> {
+        let today = Local.timestamp(now.unixtime as i64, 0).date();
         match date {
-            "now" => Some(Self::now()),
-            "today" => Some(Self::from(Local::today().and_hms(0, 0, 0)).use_default_offset()),
+            "now" => Some(now),
+            "today" => Some(Self::from(today.and_hms(0, 0, 0)).use_default_offset()),
             "yesterday" => Some(
-                Self::from(Local::today().and_hms(0, 0, 0) - Duration::days(1))
+                Self::from(today.and_hms(0, 0, 0) - Duration::days(1))
                     .use_default_offset(),
             ),
             "tomorrow" => Some(
-                Self::from(Local::today().and_hms(0, 0, 0) + Duration::days(1))
+                Self::from(today.and_hms(0, 0, 0) + Duration::days(1))
                     .use_default_offset(),
             ),
             "day after tomorrow" | "the day after tomorrow" | "overmorrow" => Some(
-                Self::from(Local::today().and_hms(0, 0, 0) + Duration::days(2))
+                Self::from(today.and_hms(0, 0, 0) + Duration::days(2))
                     .use_default_offset(),
             ),
             // Match all string ends with [dhms] or ago but not ends with pm and am. Case insensitive.
@@ -164,12 +173,12 @@ impl HgTime {
                     duration_str
                         .parse::<humantime::Duration>()
                         .ok()
-                        .map(|duration| Self::now() + duration.as_secs())
+                        .map(|duration| now + duration.as_secs())
                 } else {
                     duration_str
                         .parse::<humantime::Duration>()
                         .ok()
-                        .map(|duration| Self::now() - duration.as_secs())
+                        .map(|duration| now - duration.as_secs())
                 }
             }
             date if match Regex::new(r"^\d{10}$") {
@@ -188,6 +197,24 @@ impl HgTime {
                     _ => None,
                 }
             }
+            // Epoch in milliseconds, 13 digits or any number with a "ms:"
+            // prefix. Shorter bare numbers are years, e.g. "2020".
+            date if match Regex::new(r"^(\d{13}|ms:\d+)$") {
+                Ok(millis_re) => millis_re.is_match(date),
+                _ => false,
+            } =>
+            {
+                match date.trim_start_matches("ms:").parse::<u64>() {
+                    Ok(d) => Some(
+                        Self {
+                            unixtime: d / 1000,
+                            offset: 0,
+                        }
+                        .use_default_offset(),
+                    ),
+                    _ => None,
+                }
+            }
             _ => Self::parse_absolute(date, default_date_lower),
         }
     }
@@ -569,6 +596,14 @@ mod tests {
         assert_eq!(t("1000000000 -16200"), "1000000000 -16200");
         assert_eq!(t("2006-02-01 1:00:30PM +0000"), "1138798830 0");
 
+        // Unix epoch, in seconds or milliseconds
+        assert_eq!(t("1589808367"), "1589808367 7200");
+        assert_eq!(t("1589808367123"), "1589808367 7200");
+        assert_eq!(t("ms:1589808367999"), "1589808367 7200");
+        assert_eq!(t("ms:123"), "0 7200");
+        assert_eq!(t("ms:"), "fail");
+        assert_eq!(t("158980836712"), "fail");
+
         assert_eq!(d("1:00:30PM +0000", Duration::days(1)), "0");
         assert_eq!(d("02/01", Duration::weeks(52)), "0");
         assert_eq!(d("today", Duration::days(1)), "0");
@@ -640,6 +675,25 @@ mod tests {
         assert_eq!(t("9999999 years ago"), "0 7200");
     }
 
+    #[test]
+    fn test_parse_at() {
+        set_default_offset(7200);
+        let now = HgTime {
+            unixtime: 1600000000,
+            offset: 7200,
+        };
+        let at = |date| HgTime::parse_at(date, now).map(|time| time.unixtime);
+        assert_eq!(at("now"), Some(1600000000));
+        assert_eq!(at("10m ago"), Some(1600000000 - 600));
+        assert_eq!(at("+1h"), Some(1600000000 + 3600));
+        let today = at("today").unwrap();
+        assert!(today <= 1600000000 && 1600000000 - today < 86400);
+        let yesterday = at("yesterday").unwrap();
+        assert!(today - yesterday >= 23 * 3600 && today - yesterday <= 25 * 3600);
+        // Absolute dates don't depend on now
+        assert_eq!(at("2006-02-01 13:00:30Z"), Some(1138798830));
+    }
+
     #[test]
     fn test_parse_from_now() {
         set_default_offset(7200);
@@ -112,6 +112,28 @@ pub fn timestamp_to_datetime(timestamp: &i64) -> String {
         .to_string()
 }
 
+/// Convert `secs` into a human friendly duration with its two most
+/// significant units, e.g. "3d 4h", "5m 2s" or "42s".
+pub fn convert_duration(secs: u64) -> String {
+    let units = [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)];
+    let mut parts = Vec::new();
+    let mut remain = secs;
+    for (unit, unit_secs) in units.iter() {
+        let value = remain / unit_secs;
+        remain %= unit_secs;
+        if value > 0 || !parts.is_empty() {
+            parts.push(format!("{}{}", value, unit));
+        }
+        if parts.len() == 2 {
+            break;
+        }
+    }
+    if parts.is_empty() {
+        return "0s".to_owned();
+    }
+    parts.join(" ")
+}
+
 /// Convert system time to human readable datetime.
 pub fn systemtime_to_datetime(system_time: SystemTime) -> String {
     timestamp_to_datetime(&(get_unix_timestamp(system_time) as i64))
@@ -164,4 +186,12 @@ mod test {
         assert_eq!(v1, vec![0, 2, 4, 6, 8]);
         assert_eq!(v2, vec![0, 3, 6, 9]);
     }
+
+    #[test]
+    fn test_convert_duration() {
+        assert_eq!(convert_duration(0), "0s");
+        assert_eq!(convert_duration(42), "42s");
+        assert_eq!(convert_duration(3600), "1h 0m");
+        assert_eq!(convert_duration(4000000), "46d 7h");
+    }
 }
@@ -0,0 +1,206 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::*;
+
+use diff::{format_number, number};
+use quantize::{Agg, Quantize};
+
+/// A dumped field by its name, as given to --fields, and its title, the key
+/// of its value in json output.
+struct NamedField {
+    name: String,
+    title: String,
+}
+
+/// Change of the --anomalies field of one row since the last time slice.
+struct Change {
+    label: String,
+    before: f64,
+    after: f64,
+}
+
+impl Change {
+    fn delta(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+/// Dumper for --anomalies. The wrapped dumper must dump raw json, which is
+/// read back for each time slice. Rows are matched to the last time slice by
+/// the same fields that tell rows apart for --quantize, e.g. pid or cgroup
+/// path, and the rows whose field went up and down the most are written as
+/// one line each. Changes smaller than `threshold` are left out.
+pub struct Anomalies {
+    dumper: Box<dyn Dumper>,
+    field: NamedField,
+    keys: Vec<NamedField>,
+    threshold: f64,
+    /// Value of the field in the last time slice, by row key
+    last: RefCell<HashMap<String, f64>>,
+}
+
+impl Anomalies {
+    pub fn new(dumper: Box<dyn Dumper>, field: &str, threshold: Option<f64>) -> Result<Self> {
+        let fields = dumper
+            .field_names()
+            .into_iter()
+            .zip(dumper.quantize_fields()?)
+            .map(|(name, field)| {
+                (
+                    NamedField {
+                        name,
+                        title: field.title,
+                    },
+                    field.agg,
+                )
+            })
+            .collect::<Vec<_>>();
+        if fields.is_empty() {
+            bail!("--anomalies is not supported by this command");
+        }
+        let threshold = threshold.unwrap_or_default();
+        if threshold < 0.0 {
+            bail!("--anomaly-threshold must not be negative");
+        }
+        let mut keys = Vec::new();
+        let mut anomaly_field = None;
+        for (named, agg) in fields {
+            if named.name == field {
+                if agg == Agg::Key {
+                    bail!("--anomalies {}: this field tells rows apart", field);
+                }
+                anomaly_field = Some(named);
+            } else if agg == Agg::Key {
+                keys.push(named);
+            }
+        }
+        Ok(Self {
+            dumper,
+            field: anomaly_field
+                .ok_or_else(|| Error::msg(format!("--anomalies {}: not a dumped field", field)))?,
+            keys,
+            threshold,
+            last: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Changes of the field in all rows dumped for one time slice. Rows
+    /// missing from the last time slice or without a value are left out.
+    fn get_changes(&self, value: &Value) -> Vec<Change> {
+        let mut rows = Vec::new();
+        Quantize::collect_rows(value, &mut rows);
+        let mut last = self.last.borrow_mut();
+        let mut curr = HashMap::new();
+        let mut changes = Vec::new();
+        for row in rows {
+            let key = self
+                .keys
+                .iter()
+                .map(|key| row.get(&key.title).unwrap_or(&Value::Null).to_string())
+                .collect::<Vec<_>>()
+                .join("\0");
+            let after = match number(row.get(&self.field.title)) {
+                Some(after) => after,
+                None => continue,
+            };
+            if let Some(before) = last.get(&key) {
+                let label = self
+                    .keys
+                    .iter()
+                    .map(|key| {
+                        let value = match row.get(&key.title) {
+                            Some(Value::String(value)) => value.clone(),
+                            _ => "?".to_owned(),
+                        };
+                        format!("{}={} ", key.name, value)
+                    })
+                    .collect::<String>();
+                changes.push(Change {
+                    label,
+                    before: *before,
+                    after,
+                });
+            }
+            curr.insert(key, after);
+        }
+        *last = curr;
+        changes
+    }
+}
+
+impl Dumper for Anomalies {
+    fn dump_model(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+        output: &mut dyn Write,
+        round: &mut usize,
+        _comma_flag: bool,
+    ) -> Result<IterExecResult> {
+        let mut content: Vec<u8> = Vec::new();
+        let mut inner_round = 0;
+        if self
+            .dumper
+            .dump_model(ctx, model, &mut content, &mut inner_round, false)?
+            != IterExecResult::Success
+        {
+            self.last.borrow_mut().clear();
+            return Ok(IterExecResult::Skip);
+        }
+        let value: Value = serde_json::from_slice(&content)?;
+        let changes = self.get_changes(&value);
+        let cmp = |lhs: &&Change, rhs: &&Change| {
+            lhs.delta()
+                .partial_cmp(&rhs.delta())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        };
+        let up = changes
+            .iter()
+            .filter(|change| change.delta() > 0.0)
+            .max_by(cmp);
+        let down = changes
+            .iter()
+            .filter(|change| change.delta() < 0.0)
+            .min_by(cmp);
+        let mut written = 0;
+        for (change, verb) in [(up, "jumped"), (down, "dropped")] {
+            let change = match change {
+                Some(change) if change.delta().abs() >= self.threshold => change,
+                _ => continue,
+            };
+            writeln!(
+                output,
+                "at {}: {}{} {} {}{} ({} -> {})",
+                timestamp_to_datetime(&ctx.timestamp),
+                change.label,
+                self.field.name,
+                verb,
+                if change.delta() > 0.0 { "+" } else { "" },
+                format_number(change.delta()),
+                format_number(change.before),
+                format_number(change.after),
+            )?;
+            written += 1;
+        }
+        if written == 0 {
+            return Ok(IterExecResult::Skip);
+        }
+        *round += written;
+        Ok(IterExecResult::Success)
+    }
+}
@@ -22,15 +22,19 @@ impl HasRenderConfigForDump for model::SingleCgroupModel {
         use common::util::get_prefix;
         use model::CgroupCpuModelFieldId::ThrottledPct;
         use model::CgroupIoModelFieldId::{
-            DbytesPerSec, DiosPerSec, RbytesPerSec, RiosPerSec, RwbytesPerSec, WbytesPerSec,
-            WiosPerSec,
+            CostIndebtPct, CostUsagePct, CostVrate, DbytesPerSec, DiosPerSec, RbytesPerSec,
+            RiosPerSec, RwbytesPerSec, WbytesPerSec, WiosPerSec,
         };
         use model::CgroupMemoryModelFieldId::{
             Anon, File, Pgactivate, Pgdeactivate, Pgfault, Pglazyfree, Pglazyfreed, Pgmajfault,
-            Pgrefill, Pgscan, Pgsteal, Shmem, Slab, Sock, Swap, ThpCollapseAlloc, ThpFaultAlloc,
-            Total, WorkingsetActivate, WorkingsetNodereclaim, WorkingsetRefault,
+            Pgrefill, Pgscan, PgscanDirect, PgscanKswapd, Pgsteal, PgstealDirect, PgstealKswapd,
+            Shmem, Slab, Sock, Swap, ThpCollapseAlloc, ThpFaultAlloc, Total, WorkingsetActivate,
+            WorkingsetNodereclaim, WorkingsetRefault, Zswap,
+        };
+        use model::CgroupPressureModelFieldId::{
+            MemoryFullAvg10, MemoryFullAvg300, MemoryFullAvg60, MemoryFullPct, MemorySomeAvg10,
+            MemorySomeAvg300, MemorySomeAvg60, MemorySomePct,
         };
-        use model::CgroupPressureModelFieldId::{MemoryFullPct, MemorySomePct};
         use model::SingleCgroupModelFieldId::{Cpu, Io, Mem, Name, Pressure};
         use render::HasRenderConfig;
 
@@ -45,8 +49,12 @@ impl HasRenderConfigForDump for model::SingleCgroupModel {
             Io(WiosPerSec) => rc.title("W I/O"),
             Io(DiosPerSec) => rc.title("D I/O"),
             Io(RwbytesPerSec) => rc.title("RW Total"),
+            Io(CostUsagePct) => rc.title("IO Cost Usage"),
+            Io(CostVrate) => rc.title("IO Cost Vrate"),
+            Io(CostIndebtPct) => rc.title("IO Cost Debt"),
             Mem(Total) => rc.title("Mem Total"),
             Mem(Swap) => rc.title("Mem Swap"),
+            Mem(Zswap) => rc.title("Mem Zswap"),
             Mem(Anon) => rc.title("Mem Anon"),
             Mem(File) => rc.title("Mem File"),
             Mem(Slab) => rc.title("Mem Slab"),
@@ -60,6 +68,10 @@ impl HasRenderConfigForDump for model::SingleCgroupModel {
             Mem(Pgrefill) => rc.title("Pgrefill"),
             Mem(Pgscan) => rc.title("Pgscan"),
             Mem(Pgsteal) => rc.title("Pgsteal"),
+            Mem(PgscanKswapd) => rc.title("Pgscan Kswapd"),
+            Mem(PgscanDirect) => rc.title("Pgscan Direct"),
+            Mem(PgstealKswapd) => rc.title("Pgsteal Kswapd"),
+            Mem(PgstealDirect) => rc.title("Pgsteal Direct"),
             Mem(Pgactivate) => rc.title("Pgactivate"),
             Mem(Pgdeactivate) => rc.title("Pgdeactivate"),
             Mem(Pglazyfree) => rc.title("Pglazyfree"),
@@ -68,30 +80,216 @@ impl HasRenderConfigForDump for model::SingleCgroupModel {
             Mem(ThpCollapseAlloc) => rc.title("THP Collapse Alloc"),
             Pressure(MemorySomePct) => rc.title("Memory Some Pressure"),
             Pressure(MemoryFullPct) => rc.title("Memory Pressure"),
+            Pressure(MemorySomeAvg10) => rc.title("Memory Some Pressure Avg10"),
+            Pressure(MemorySomeAvg60) => rc.title("Memory Some Pressure Avg60"),
+            Pressure(MemorySomeAvg300) => rc.title("Memory Some Pressure Avg300"),
+            Pressure(MemoryFullAvg10) => rc.title("Memory Full Pressure Avg10"),
+            Pressure(MemoryFullAvg60) => rc.title("Memory Full Pressure Avg60"),
+            Pressure(MemoryFullAvg300) => rc.title("Memory Full Pressure Avg300"),
             _ => rc,
         }
         .get()
     }
 }
 
+/// Name and full path of the --with-total row.
+pub const TOTAL_NAME: &str = "<total>";
+
+/// Sum the dumped cgroups into the --with-total row. Settings like the
+/// weights and the pressure, which don't add up, are left empty.
+pub fn get_total(cgroups: &[&model::SingleCgroupModel]) -> model::SingleCgroupModel {
+    let mut total = model::SingleCgroupModel {
+        name: TOTAL_NAME.to_owned(),
+        full_path: TOTAL_NAME.to_owned(),
+        ..Default::default()
+    };
+    for cgroup in cgroups {
+        total.cpu = model::opt_add(total.cpu.take(), cgroup.cpu.clone());
+        total.memory = model::opt_add(total.memory.take(), cgroup.memory.clone());
+        total.io_total = match (total.io_total.take(), cgroup.io_total.as_ref()) {
+            (Some(acc), Some(io)) => Some(acc + io),
+            (acc, io) => acc.or_else(|| io.cloned()),
+        };
+        if let Some(io) = cgroup.io.as_ref() {
+            let devices = total.io.get_or_insert_with(Default::default);
+            for (device, io) in io {
+                let acc = devices.remove(device).unwrap_or_default();
+                devices.insert(device.clone(), acc + io);
+            }
+        }
+    }
+    total
+}
+
+/// Graphite metric path prefix of a cgroup row, e.g.
+/// below.cgroup.system_slice.foo_service, with the device of --per-device
+/// rows appended. The root cgroup is below.cgroup.root.
+fn graphite_prefix(cgroup: &model::SingleCgroupModel) -> String {
+    let mut prefix = format!("{}.cgroup", print::GRAPHITE_ROOT);
+    let mut components = cgroup
+        .full_path
+        .split('/')
+        .filter(|component| !component.is_empty())
+        .peekable();
+    if components.peek().is_none() {
+        prefix.push_str(".root");
+    }
+    for component in components {
+        prefix.push('.');
+        prefix.push_str(&print::graphite_component(component));
+    }
+    if let Some(device) = cgroup.device.as_ref() {
+        prefix.push('.');
+        prefix.push_str(&print::graphite_component(device));
+    }
+    prefix
+}
+
+/// Rows of a cgroup for --per-device, one per device in io.stat with the io
+/// fields of that device. Cgroups without io.stat, e.g. without the io
+/// controller, keep a single row without a device.
+pub fn get_device_rows(cgroup: &model::SingleCgroupModel) -> Vec<model::SingleCgroupModel> {
+    match cgroup.io.as_ref() {
+        Some(devices) if !devices.is_empty() => devices
+            .iter()
+            .map(|(device, io)| model::SingleCgroupModel {
+                device: Some(device.clone()),
+                io_total: Some(io.clone()),
+                ..cgroup.clone()
+            })
+            .collect(),
+        _ => vec![cgroup.clone()],
+    }
+}
+
 pub struct Cgroup {
     opts: GeneralOpt,
     select: Option<SingleCgroupModelFieldId>,
+    // Field --filter applies to, --select unless --filter-field is given
+    filter_field: Option<SingleCgroupModelFieldId>,
+    with_total: bool,
+    per_device: bool,
     fields: Vec<CgroupField>,
+    // Values of the last time slice for --sort-by delta
+    last_values: delta::LastValues,
 }
 
 impl Cgroup {
     pub fn new(
         opts: &GeneralOpt,
         select: Option<SingleCgroupModelFieldId>,
+        filter_field: Option<SingleCgroupModelFieldId>,
+        with_total: bool,
+        per_device: bool,
         fields: Vec<CgroupField>,
     ) -> Self {
         Self {
             opts: opts.to_owned(),
+            filter_field: filter_field.or_else(|| select.clone()),
             select,
+            with_total,
+            per_device,
             fields,
+            last_values: Default::default(),
         }
     }
+
+    /// Write one row, except for json where the row is returned instead
+    /// for the caller to nest.
+    fn dump_row(
+        &self,
+        ctx: &CommonFieldContext,
+        cgroup: &model::SingleCgroupModel,
+        output: &mut dyn Write,
+        round: usize,
+    ) -> Result<Option<Value>> {
+        match self.opts.output_format {
+            Some(OutputFormat::Raw) | None => write!(
+                output,
+                "{}",
+                print::dump_raw_indented(
+                    &self.fields,
+                    ctx,
+                    cgroup,
+                    round,
+                    &print::RawOptions::new(&self.opts),
+                )
+            )?,
+            Some(OutputFormat::Csv) => write!(
+                output,
+                "{}",
+                print::dump_csv(
+                    &self.fields,
+                    ctx,
+                    cgroup,
+                    round,
+                    self.opts.disable_title,
+                    self.opts.raw,
+                    self.opts.na_string.as_deref(),
+                )
+            )?,
+            Some(OutputFormat::KeyVal) => write!(
+                output,
+                "{}",
+                print::dump_kv(
+                    &self.fields,
+                    ctx,
+                    cgroup,
+                    self.opts.raw,
+                    self.opts.na_string.as_deref()
+                )
+            )?,
+            Some(OutputFormat::Graphite) => write!(
+                output,
+                "{}",
+                print::dump_graphite(&self.fields, ctx, cgroup, &graphite_prefix(cgroup))
+            )?,
+            Some(OutputFormat::Json) => {
+                return Ok(Some(print::dump_json(
+                    &self.fields,
+                    ctx,
+                    cgroup,
+                    self.opts.raw,
+                )));
+            }
+        };
+        Ok(None)
+    }
+
+    /// Write the rows of a cgroup, one per device with --per-device. For
+    /// json the row is returned instead, with the device rows nested under
+    /// "devices".
+    fn dump_rows(
+        &self,
+        ctx: &CommonFieldContext,
+        cgroup: &model::SingleCgroupModel,
+        output: &mut dyn Write,
+        round: &mut usize,
+    ) -> Result<Option<Value>> {
+        if !self.per_device {
+            let row = self.dump_row(ctx, cgroup, output, *round)?;
+            *round += 1;
+            return Ok(row);
+        }
+        let device_rows = get_device_rows(cgroup);
+        if self.opts.output_format == Some(OutputFormat::Json) {
+            let mut row = print::dump_json(&self.fields, ctx, cgroup, self.opts.raw);
+            row["devices"] = device_rows
+                .iter()
+                .map(|device_row| print::dump_json(&self.fields, ctx, device_row, self.opts.raw))
+                .collect();
+            *round += 1;
+            return Ok(Some(row));
+        }
+        for device_row in &device_rows {
+            if matches!(self.opts.limit_rows, Some(limit) if *round >= limit) {
+                break;
+            }
+            self.dump_row(ctx, device_row, output, *round)?;
+            *round += 1;
+        }
+        Ok(None)
+    }
 }
 
 impl Dumper for Cgroup {
@@ -103,75 +301,46 @@ impl Dumper for Cgroup {
         round: &mut usize,
         comma_flag: bool,
     ) -> Result<IterExecResult> {
-        fn output_cgroup(
+        fn output_cgroup<'a>(
             handle: &Cgroup,
             ctx: &CommonFieldContext,
-            model: &model::CgroupModel,
+            model: &'a model::CgroupModel,
             output: &mut dyn Write,
             round: &mut usize,
-            json: bool,
             jval: &mut Value,
+            dumped: &mut Vec<&'a model::SingleCgroupModel>,
         ) -> Result<()> {
             let cgroup = &model.data;
             //filter
-            let should_print = match (handle.select.as_ref(), handle.opts.filter.as_ref()) {
+            let should_print = match (handle.filter_field.as_ref(), handle.opts.filter.as_ref()) {
                 (Some(field_id), Some(filter)) => filter.is_match(
                     &cgroup
                         .query(&field_id)
                         .map_or("?".to_owned(), |v| v.to_string()),
                 ),
                 _ => true,
-            };
+            } && !matches!(handle.opts.limit_rows, Some(limit) if *round >= limit);
 
             if should_print {
-                match handle.opts.output_format {
-                    Some(OutputFormat::Raw) | None => write!(
-                        output,
-                        "{}",
-                        print::dump_raw_indented(
-                            &handle.fields,
-                            ctx,
-                            cgroup,
-                            *round,
-                            handle.opts.repeat_title,
-                            handle.opts.disable_title,
-                            handle.opts.raw,
-                        )
-                    )?,
-                    Some(OutputFormat::Csv) => write!(
-                        output,
-                        "{}",
-                        print::dump_csv(
-                            &handle.fields,
-                            ctx,
-                            cgroup,
-                            *round,
-                            handle.opts.disable_title,
-                            handle.opts.raw,
-                        )
-                    )?,
-                    Some(OutputFormat::KeyVal) => write!(
-                        output,
-                        "{}",
-                        print::dump_kv(&handle.fields, ctx, cgroup, handle.opts.raw)
-                    )?,
-                    Some(OutputFormat::Json) => {
-                        *jval = print::dump_json(&handle.fields, ctx, cgroup, handle.opts.raw);
-                        jval["children"] = json!([]);
-                    }
-                };
-                *round += 1;
+                if let Some(row) = handle.dump_rows(ctx, cgroup, output, round)? {
+                    *jval = row;
+                    jval["children"] = json!([]);
+                }
+                dumped.push(cgroup);
             }
 
             let mut children = Vec::from_iter(&model.children);
             //sort
             if let Some(field_id) = &handle.select {
-                if handle.opts.sort {
-                    model::sort_queriables(&mut children, &field_id.to_owned().into(), false);
-                }
-
-                if handle.opts.rsort {
-                    model::sort_queriables(&mut children, &field_id.to_owned().into(), true);
+                if handle.opts.sort || handle.opts.rsort {
+                    delta::sort_queriables_by(
+                        &mut children,
+                        &field_id.to_owned().into(),
+                        handle.opts.rsort,
+                        handle.opts.sort_by,
+                        |cgroup| cgroup.data.full_path.clone(),
+                        &handle.last_values,
+                    );
                 }
 
                 if (handle.opts.sort || handle.opts.rsort) && handle.opts.top != 0 {
@@ -179,10 +348,21 @@ impl Dumper for Cgroup {
                 }
             }
 
-            for child_cgroup in &children {
+            let ranked = handle.select.is_some() && (handle.opts.sort || handle.opts.rsort);
+            for (idx, child_cgroup) in children.iter().enumerate() {
                 let mut child = json!({});
-                output_cgroup(handle, ctx, child_cgroup, output, round, json, &mut child)?;
-                if json && child["children"].is_array() {
+                output_cgroup(
+                    handle,
+                    &ctx.with_rank(ranked.then(|| idx + 1)),
+                    child_cgroup,
+                    output,
+                    round,
+                    &mut child,
+                    dumped,
+                )?;
+                if handle.opts.output_format == Some(OutputFormat::Json)
+                    && child["children"].is_array()
+                {
                     // Parent does not match, but child does, we should also render parent.
                     if !jval["children"].is_array() {
                         *jval = print::dump_json(&handle.fields, ctx, cgroup, handle.opts.raw);
@@ -194,15 +374,55 @@ impl Dumper for Cgroup {
 
             Ok(())
         }
+        // Children are sorted level by level, all against the last slice
+        self.last_values.next_slice();
         let json = self.opts.output_format == Some(OutputFormat::Json);
         let mut jval = json!({});
-        output_cgroup(&self, ctx, &model.cgroup, output, round, json, &mut jval)?;
+        let mut dumped = Vec::new();
+        output_cgroup(
+            self,
+            ctx,
+            &model.cgroup,
+            output,
+            round,
+            &mut jval,
+            &mut dumped,
+        )?;
         match (json, comma_flag) {
             (true, true) => write!(output, ",{}", jval)?,
             (true, false) => write!(output, "{}", jval)?,
             _ => {}
         };
+        // The total is a row of its own, also in json after the tree, where
+        // "total" tells it apart from a cgroup
+        let limit_reached = matches!(self.opts.limit_rows, Some(limit) if *round >= limit);
+        if self.with_total && !limit_reached {
+            let total = get_total(&dumped);
+            if let Some(mut row) = self.dump_rows(ctx, &total, output, round)? {
+                row["total"] = json!(true);
+                write!(output, ",{}", row)?;
+            }
+        }
 
         Ok(IterExecResult::Success)
     }
+
+    fn field_names(&self) -> Vec<String> {
+        self.fields.iter().map(ToString::to_string).collect()
+    }
+
+    fn quantize_fields(&self) -> Result<Vec<quantize::QuantizeField>> {
+        quantize::quantize_fields(
+            &self.fields,
+            &[
+                model::SingleCgroupModelFieldId::FullPath,
+                model::SingleCgroupModelFieldId::Name,
+                model::SingleCgroupModelFieldId::Device,
+            ],
+        )
+    }
+
+    fn nonzero_columns(&self) -> Vec<nonzero::Column> {
+        nonzero::columns(&self.fields)
+    }
 }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::expr::ExprColumn;
 use crate::{CommonField, DumpField};
 use model::EnumIter;
 use model::{
@@ -23,24 +24,58 @@ use anyhow::{bail, Error, Result};
 use clap::Parser;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 /// Field that represents a group of related FieldIds of a Queriable.
 /// Shorthand for specifying fields to dump.
 pub trait AggField<F: FieldId> {
     fn expand(&self, detail: bool) -> Vec<F>;
+
+    /// The FieldId used when this aggregated field is given to --select, if
+    /// the group has an obvious sortable representative.
+    fn select_field(&self) -> Option<F> {
+        None
+    }
+}
+
+/// Used by Clap to parse --select. Besides FieldIds, aggregated fields are
+/// accepted and mapped to their representative FieldId.
+pub fn parse_select<F, A>(s: &str) -> Result<F>
+where
+    F: FieldId + FromStr,
+    A: AggField<F> + FromStr,
+{
+    if let Ok(field_id) = F::from_str(s) {
+        Ok(field_id)
+    } else if let Ok(agg) = A::from_str(s) {
+        match agg.select_field() {
+            Some(field_id) => Ok(field_id),
+            None => bail!(
+                "Aggregated field {} has no sortable representative, select one of its fields instead",
+                s
+            ),
+        }
+    } else {
+        bail!("Variant not found: {}", s);
+    }
 }
 
 /// Generic representation of fields accepted by different dump subcommands.
 /// Each DumpOptionField is either an aggregation of multiple FieldIds, or a
-/// "unit" field which could be either a CommonField or a FieldId.
+/// "unit" field which could be either a CommonField or a FieldId. `Glob` is
+/// a pattern like `mem.pg*` with the fields it matches, and `All` stands for
+/// every field, same as --everything.
 #[derive(Clone, Debug, PartialEq)]
 pub enum DumpOptionField<F: FieldId, A: AggField<F>> {
     Unit(DumpField<F>),
     Agg(A),
+    Glob(String, Vec<DumpField<F>>),
+    All,
 }
 
-/// Expand the Agg fields and collect them with other Unit fields.
+/// Expand the Agg fields and collect them with other Unit fields. `All` must
+/// be resolved by the caller, see `has_all_field`.
 pub fn expand_fields<F: FieldId + Clone, A: AggField<F>>(
     fields: &[DumpOptionField<F, A>],
     detail: bool,
@@ -52,19 +87,83 @@ pub fn expand_fields<F: FieldId + Clone, A: AggField<F>>(
             DumpOptionField::Agg(agg) => {
                 res.extend(agg.expand(detail).into_iter().map(DumpField::FieldId))
             }
+            DumpOptionField::Glob(_, fields) => res.extend(fields.iter().cloned()),
+            DumpOptionField::All => {}
         }
     }
     res
 }
 
+/// Check if `all` is given in --fields, which means --everything.
+pub fn has_all_field<F: FieldId, A: AggField<F>>(fields: Option<&[DumpOptionField<F, A>]>) -> bool {
+    matches!(fields, Some(fields) if fields.iter().any(|field| matches!(field, DumpOptionField::All)))
+}
+
+/// Match a field name against a --fields glob, where `*` matches any
+/// sequence of characters.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let parts = pattern.split('*').collect::<Vec<_>>();
+    let (first, last) = match parts[..] {
+        [exact] => return exact == name,
+        [first, .., last] => (first, last),
+        [] => return false,
+    };
+    if name.len() < first.len() + last.len() || !name.starts_with(first) || !name.ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// All fields a subcommand knows that match a --fields glob, sorted by name.
+/// These are the fields listed in the help, i.e. CommonFields, FieldIds and
+/// every field of the aggregated fields.
+fn glob_fields<F, A>(pattern: &str) -> Vec<DumpField<F>>
+where
+    F: FieldId + EnumIter + ToString + PartialEq,
+    A: AggField<F> + EnumIter,
+{
+    let mut fields = CommonField::unit_variant_iter()
+        .map(DumpField::Common)
+        .chain(F::unit_variant_iter().map(DumpField::FieldId))
+        .chain(
+            A::unit_variant_iter()
+                .flat_map(|agg| agg.expand(true))
+                .map(DumpField::FieldId),
+        )
+        .filter(|field| glob_match(pattern, &field.to_string()))
+        .collect::<Vec<_>>();
+    fields.sort_by_key(|field| field.to_string());
+    fields.dedup();
+    fields
+}
+
 /// Used by Clap to parse user provided --fields.
-impl<F: FieldId + FromStr, A: AggField<F> + FromStr> FromStr for DumpOptionField<F, A> {
+impl<F, A> FromStr for DumpOptionField<F, A>
+where
+    F: FieldId + FromStr + EnumIter + ToString + PartialEq,
+    A: AggField<F> + FromStr + EnumIter,
+{
     type Err = Error;
 
     /// When parsing command line options into DumpOptionField, priority order
-    /// is CommonField, AggField, and then FieldId.
+    /// is `all`, globs, CommonField, AggField, and then FieldId.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(common) = CommonField::from_str(s) {
+        if s == "all" {
+            Ok(Self::All)
+        } else if s.contains('*') {
+            let fields = glob_fields::<F, A>(s);
+            if fields.is_empty() {
+                bail!("No field matches: {}", s);
+            }
+            Ok(Self::Glob(s.to_owned(), fields))
+        } else if let Ok(common) = CommonField::from_str(s) {
             Ok(Self::Unit(DumpField::Common(common)))
         } else if let Ok(agg) = A::from_str(s) {
             Ok(Self::Agg(agg))
@@ -80,9 +179,10 @@ impl<F: FieldId + FromStr, A: AggField<F> + FromStr> FromStr for DumpOptionField
 impl<F: FieldId + ToString, A: AggField<F> + ToString> ToString for DumpOptionField<F, A> {
     fn to_string(&self) -> String {
         match self {
-            Self::Unit(DumpField::Common(common)) => common.to_string(),
-            Self::Unit(DumpField::FieldId(field_id)) => field_id.to_string(),
+            Self::Unit(field) => field.to_string(),
             Self::Agg(agg) => agg.to_string(),
+            Self::Glob(pattern, _) => pattern.clone(),
+            Self::All => "all".to_owned(),
         }
     }
 }
@@ -96,6 +196,28 @@ fn join(iter: impl IntoIterator<Item = impl ToString>) -> String {
         .join(", ")
 }
 
+/// Join the --select mapping of aggregated fields. Used for generating help
+/// string.
+fn join_select_fields<F: FieldId + ToString, A: AggField<F> + ToString>(aggs: &[A]) -> String {
+    join(aggs.iter().map(|agg| match agg.select_field() {
+        Some(field_id) => format!("{} -> {}", agg.to_string(), field_id.to_string()),
+        None => format!("{} -> none", agg.to_string()),
+    }))
+}
+
+/// A named, curated set of fields for --fields-preset, in --fields syntax.
+pub type FieldsPreset = (&'static str, &'static [&'static str]);
+
+/// Describe the --fields-preset of a subcommand. Used for generating help
+/// string.
+fn join_presets(presets: &[FieldsPreset]) -> String {
+    presets
+        .iter()
+        .map(|(name, fields)| format!("* --fields-preset {}: [{}].", name, fields.join(", ")))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 // make_option macro will build a enum of tags that map to string values by
 // implementing the FromStr trait.
 // This is useful when are trying to processing or display fields base on
@@ -127,14 +249,23 @@ macro_rules! make_option {
                 }
             }
         }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                match self {
+                    $($name::$enum_field => f.write_str($str_field),)*
+                }
+            }
+        }
     }
 }
 
-/// Represents the four sub-model of SystemModel.
+/// Represents the sub-models of SystemModel.
 #[derive(
     Clone,
     Debug,
     PartialEq,
+    below_derive::EnumIter,
     below_derive::EnumFromStr,
     below_derive::EnumToString
 )]
@@ -143,11 +274,13 @@ pub enum SystemAggField {
     Mem,
     Vm,
     Stat,
+    Perf,
 }
 
 impl AggField<SystemModelFieldId> for SystemAggField {
     fn expand(&self, detail: bool) -> Vec<SystemModelFieldId> {
         use model::MemoryModelFieldId as Mem;
+        use model::PerfModelFieldId as Perf;
         use model::ProcStatModelFieldId as Stat;
         use model::SingleCpuModelFieldId as Cpu;
         use model::SystemModelFieldId as FieldId;
@@ -163,6 +296,7 @@ impl AggField<SystemModelFieldId> for SystemAggField {
                 Self::Mem => Mem::unit_variant_iter().map(FieldId::Mem).collect(),
                 Self::Vm => Vm::unit_variant_iter().map(FieldId::Vm).collect(),
                 Self::Stat => Stat::unit_variant_iter().map(FieldId::Stat).collect(),
+                Self::Perf => Perf::unit_variant_iter().map(FieldId::Perf).collect(),
             }
         } else {
             // Default fields for each group
@@ -177,6 +311,7 @@ impl AggField<SystemModelFieldId> for SystemAggField {
                     .collect(),
                 Self::Vm => Vm::unit_variant_iter().map(FieldId::Vm).collect(),
                 Self::Stat => Stat::unit_variant_iter().map(FieldId::Stat).collect(),
+                Self::Perf => Perf::unit_variant_iter().map(FieldId::Perf).collect(),
             }
         }
     }
@@ -196,6 +331,27 @@ pub static DEFAULT_SYSTEM_FIELDS: &[SystemOptionField] = &[
     DumpOptionField::Unit(DumpField::Common(CommonField::Timestamp)),
 ];
 
+/// Curated sets of fields for --fields-preset.
+pub static SYSTEM_PRESETS: &[FieldsPreset] = &[(
+    "mem-debug",
+    &[
+        "datetime",
+        "mem.total",
+        "mem.free",
+        "mem.available",
+        "mem.anon",
+        "mem.file",
+        "mem.slab",
+        "mem.swap_total",
+        "mem.swap_free",
+        "vm.pswpin_per_sec",
+        "vm.pswpout_per_sec",
+        "vm.pgscan_kswapd",
+        "vm.pgscan_direct",
+        "vm.oom_kill",
+    ],
+)];
+
 const SYSTEM_ABOUT: &str = "Dump system stats";
 
 /// Generated about message for System dump so supported fields are up-to-date.
@@ -218,6 +374,8 @@ cpus.N.<cpu_field> for individual CPU data. N is individual CPU index and
 
 {all_stat_fields}
 
+{all_perf_fields}
+
 ********************** Aggregated fields **********************
 
 * cpu: includes [{agg_cpu_fields}].
@@ -228,12 +386,20 @@ cpus.N.<cpu_field> for individual CPU data. N is individual CPU index and
 
 * stat: includes [{agg_stat_fields}].
 
+* perf: includes [{agg_perf_fields}]. Only recorded by
+  `below record --perf` on hosts with hardware perf counters, otherwise
+  empty. Not included in --default or --everything.
+
 * --detail: includes [<agg_field>.*] for each given aggregated field.
 
 * --default: includes [{default_fields}].
 
 * --everything: includes everything (equivalent to --default --detail).
 
+* all: same as --everything when given in --fields.
+
+{presets}
+
 ********************** Example Commands **********************
 
 $ below dump system -b "08:30:00" -e "08:30:30" -f datetime vm hostname -O csv
@@ -246,11 +412,14 @@ $ below dump system -b "08:30:00" -e "08:30:30" -f datetime vm hostname -O csv
         all_memory_fields = join(SystemAggField::Mem.expand(true)),
         all_vm_fields = join(SystemAggField::Vm.expand(true)),
         all_stat_fields = join(SystemAggField::Stat.expand(true)),
+        all_perf_fields = join(SystemAggField::Perf.expand(true)),
         agg_cpu_fields = join(SystemAggField::Cpu.expand(false)),
         agg_memory_fields = join(SystemAggField::Mem.expand(false)),
         agg_vm_fields = join(SystemAggField::Vm.expand(false)),
         agg_stat_fields = join(SystemAggField::Stat.expand(false)),
+        agg_perf_fields = join(SystemAggField::Perf.expand(false)),
         default_fields = join(DEFAULT_SYSTEM_FIELDS.to_owned()),
+        presets = join_presets(SYSTEM_PRESETS),
     )
 });
 
@@ -258,6 +427,7 @@ $ below dump system -b "08:30:00" -e "08:30:30" -f datetime vm hostname -O csv
     Clone,
     Debug,
     PartialEq,
+    below_derive::EnumIter,
     below_derive::EnumFromStr,
     below_derive::EnumToString
 )]
@@ -297,6 +467,17 @@ impl AggField<SingleDiskModelFieldId> for DiskAggField {
             Self::FsInfo => vec![DiskUsage, PartitionSize, FilesystemType],
         }
     }
+
+    fn select_field(&self) -> Option<SingleDiskModelFieldId> {
+        use model::SingleDiskModelFieldId::*;
+
+        match self {
+            Self::Read => Some(ReadBytesPerSec),
+            Self::Write => Some(WriteBytesPerSec),
+            Self::Discard => Some(DiscardBytesPerSec),
+            Self::FsInfo => Some(DiskUsage),
+        }
+    }
 }
 
 pub type DiskOptionField = DumpOptionField<SingleDiskModelFieldId, DiskAggField>;
@@ -316,6 +497,23 @@ pub static DEFAULT_DISK_FIELDS: &[DiskOptionField] = &[
     DumpOptionField::Unit(DumpField::Common(CommonField::Timestamp)),
 ];
 
+/// Curated sets of fields for --fields-preset.
+pub static DISK_PRESETS: &[FieldsPreset] = &[(
+    "io-debug",
+    &[
+        "datetime",
+        "name",
+        "read_bytes_per_sec",
+        "write_bytes_per_sec",
+        "discard_bytes_per_sec",
+        "read_completed",
+        "write_completed",
+        "time_spend_read_ms",
+        "time_spend_write_ms",
+        "disk_usage",
+    ],
+)];
+
 const DISK_ABOUT: &str = "Dump disk stats";
 
 /// Generated about message for System dump so supported fields are up-to-date.
@@ -337,12 +535,18 @@ static DISK_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 
 * fs_info: includes [{agg_fsinfo_fields}].
 
+* --select also takes an aggregated field: [{select_fields}].
+
 * --detail: no effect.
 
 * --default: includes [{default_fields}].
 
 * --everything: includes everything (equivalent to --default --detail).
 
+* all: same as --everything when given in --fields.
+
+{presets}
+
 ********************** Example Commands **********************
 
 Simple example:
@@ -359,12 +563,19 @@ $ below dump disk -b "08:30:00" -e "08:30:30" -s read_bytes_per_sec --rsort --to
 
 "#,
         about = DISK_ABOUT,
+        select_fields = join_select_fields(&[
+            DiskAggField::Read,
+            DiskAggField::Write,
+            DiskAggField::Discard,
+            DiskAggField::FsInfo
+        ]),
         common_fields = join(CommonField::unit_variant_iter()),
         agg_read_fields = join(DiskAggField::Read.expand(false)),
         agg_write_fields = join(DiskAggField::Write.expand(false)),
         agg_discard_fields = join(DiskAggField::Discard.expand(false)),
         agg_fsinfo_fields = join(DiskAggField::FsInfo.expand(false)),
         default_fields = join(DEFAULT_DISK_FIELDS.to_owned()),
+        presets = join_presets(DISK_PRESETS),
     )
 });
 
@@ -373,6 +584,7 @@ $ below dump disk -b "08:30:00" -e "08:30:30" -s read_bytes_per_sec --rsort --to
     Clone,
     Debug,
     PartialEq,
+    below_derive::EnumIter,
     below_derive::EnumFromStr,
     below_derive::EnumToString
 )]
@@ -404,6 +616,19 @@ impl AggField<SingleProcessModelFieldId> for ProcessAggField {
             }
         }
     }
+
+    fn select_field(&self) -> Option<SingleProcessModelFieldId> {
+        use model::ProcessCpuModelFieldId as Cpu;
+        use model::ProcessIoModelFieldId as Io;
+        use model::ProcessMemoryModelFieldId as Mem;
+        use model::SingleProcessModelFieldId as FieldId;
+
+        match self {
+            Self::Cpu => Some(FieldId::Cpu(Cpu::UsagePct)),
+            Self::Mem => Some(FieldId::Mem(Mem::RssBytes)),
+            Self::Io => Some(FieldId::Io(Io::RwbytesPerSec)),
+        }
+    }
 }
 
 pub type ProcessOptionField = DumpOptionField<SingleProcessModelFieldId, ProcessAggField>;
@@ -418,12 +643,49 @@ pub static DEFAULT_PROCESS_FIELDS: &[ProcessOptionField] = &[
     DumpOptionField::Agg(ProcessAggField::Mem),
     DumpOptionField::Agg(ProcessAggField::Io),
     DumpOptionField::Unit(DumpField::FieldId(SingleProcessModelFieldId::UptimeSecs)),
+    DumpOptionField::Unit(DumpField::FieldId(SingleProcessModelFieldId::StartTime)),
+    DumpOptionField::Unit(DumpField::FieldId(SingleProcessModelFieldId::Age)),
     DumpOptionField::Unit(DumpField::FieldId(SingleProcessModelFieldId::Cgroup)),
     DumpOptionField::Unit(DumpField::Common(CommonField::Timestamp)),
     DumpOptionField::Unit(DumpField::FieldId(SingleProcessModelFieldId::Cmdline)),
     DumpOptionField::Unit(DumpField::FieldId(SingleProcessModelFieldId::ExePath)),
 ];
 
+/// Curated sets of fields for --fields-preset.
+pub static PROCESS_PRESETS: &[FieldsPreset] = &[
+    (
+        "mem-debug",
+        &[
+            "datetime",
+            "pid",
+            "comm",
+            "mem.rss_bytes",
+            "mem.anon",
+            "mem.file",
+            "mem.shmem",
+            "mem.swap",
+            "mem.minorfaults_per_sec",
+            "mem.majorfaults_per_sec",
+            "mem_some_pressure",
+            "cgroup",
+        ],
+    ),
+    (
+        "io-debug",
+        &[
+            "datetime",
+            "pid",
+            "comm",
+            "state",
+            "io.rbytes_per_sec",
+            "io.wbytes_per_sec",
+            "io.rwbytes_per_sec",
+            "io_some_pressure",
+            "cgroup",
+        ],
+    ),
+];
+
 const PROCESS_ABOUT: &str = "Dump process stats";
 
 /// Generated about message for Process dump so supported fields are up-to-date.
@@ -449,12 +711,18 @@ static PROCESS_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 
 * io: includes [{agg_io_fields}].
 
+* --select also takes an aggregated field: [{select_fields}].
+
 * --detail: includes [<agg_field>.*] for each given aggregated field.
 
 * --default: includes [{default_fields}].
 
 * --everything: includes everything (equivalent to --default --detail).
 
+* all: same as --everything when given in --fields.
+
+{presets}
+
 ********************** Example Commands **********************
 
 Simple example:
@@ -471,6 +739,11 @@ $ below dump process -b "08:30:00" -e "08:30:30" -s cpu.usage_pct --rsort --top
 
 "#,
         about = PROCESS_ABOUT,
+        select_fields = join_select_fields(&[
+            ProcessAggField::Cpu,
+            ProcessAggField::Mem,
+            ProcessAggField::Io
+        ]),
         common_fields = join(CommonField::unit_variant_iter()),
         process_fields = join(SingleProcessModelFieldId::unit_variant_iter()),
         all_cpu_fields = join(ProcessAggField::Cpu.expand(true)),
@@ -480,6 +753,7 @@ $ below dump process -b "08:30:00" -e "08:30:30" -s cpu.usage_pct --rsort --top
         agg_memory_fields = join(ProcessAggField::Mem.expand(false)),
         agg_io_fields = join(ProcessAggField::Io.expand(false)),
         default_fields = join(DEFAULT_PROCESS_FIELDS.to_owned()),
+        presets = join_presets(PROCESS_PRESETS),
     )
 });
 
@@ -488,6 +762,7 @@ $ below dump process -b "08:30:00" -e "08:30:30" -s cpu.usage_pct --rsort --top
     Clone,
     Debug,
     PartialEq,
+    below_derive::EnumIter,
     below_derive::EnumFromStr,
     below_derive::EnumToString
 )]
@@ -529,6 +804,21 @@ impl AggField<SingleCgroupModelFieldId> for CgroupAggField {
             }
         }
     }
+
+    fn select_field(&self) -> Option<SingleCgroupModelFieldId> {
+        use model::CgroupCpuModelFieldId as Cpu;
+        use model::CgroupIoModelFieldId as Io;
+        use model::CgroupMemoryModelFieldId as Mem;
+        use model::SingleCgroupModelFieldId as FieldId;
+
+        match self {
+            Self::Cpu => Some(FieldId::Cpu(Cpu::UsagePct)),
+            Self::Mem => Some(FieldId::Mem(Mem::Total)),
+            Self::Io => Some(FieldId::Io(Io::RwbytesPerSec)),
+            // cpu, memory and io pressure are not comparable with each other
+            Self::Pressure => None,
+        }
+    }
 }
 
 pub type CgroupOptionField = DumpOptionField<SingleCgroupModelFieldId, CgroupAggField>;
@@ -544,6 +834,57 @@ pub static DEFAULT_CGROUP_FIELDS: &[CgroupOptionField] = &[
     DumpOptionField::Unit(DumpField::Common(CommonField::Timestamp)),
 ];
 
+/// Curated sets of fields for --fields-preset.
+pub static CGROUP_PRESETS: &[FieldsPreset] = &[
+    (
+        "mem-debug",
+        &[
+            "datetime",
+            "name",
+            "mem.total",
+            "mem.anon",
+            "mem.file",
+            "mem.slab",
+            "mem.swap",
+            "mem.zswap",
+            "mem.memory_high",
+            "mem.workingset_refault",
+            "mem.events_high",
+            "mem.events_oom_kill",
+            "pressure.memory_some_pct",
+            "pressure.memory_full_pct",
+        ],
+    ),
+    (
+        "io-debug",
+        &[
+            "datetime",
+            "name",
+            "io.rbytes_per_sec",
+            "io.wbytes_per_sec",
+            "io.rios_per_sec",
+            "io.wios_per_sec",
+            "io.dbytes_per_sec",
+            "io_weight",
+            "pressure.io_some_pct",
+            "pressure.io_full_pct",
+        ],
+    ),
+    (
+        "throttling",
+        &[
+            "datetime",
+            "name",
+            "cpu_weight",
+            "cpu.usage_pct",
+            "cpu.nr_periods_per_sec",
+            "cpu.nr_throttled_per_sec",
+            "cpu.throttled_pct",
+            "pressure.cpu_some_pct",
+        ],
+    ),
+];
+
 const CGROUP_ABOUT: &str = "Dump cgroup stats";
 
 /// Generated about message for Cgroup dump so supported fields are up-to-date.
@@ -573,12 +914,18 @@ static CGROUP_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 
 * pressure: includes [{agg_pressure_fields}].
 
+* --select also takes an aggregated field: [{select_fields}].
+
 * --detail: includes [<agg_field>.*] for each given aggregated field.
 
 * --default: includes [{default_fields}].
 
 * --everything: includes everything (equivalent to --default --detail).
 
+* all: same as --everything when given in --fields.
+
+{presets}
+
 ********************** Example Commands **********************
 
 Simple example:
@@ -597,6 +944,12 @@ $ below dump cgroup -b "08:30:00" -e "08:30:30" -s cpu.usage_pct --rsort --top 5
 
 "#,
         about = CGROUP_ABOUT,
+        select_fields = join_select_fields(&[
+            CgroupAggField::Cpu,
+            CgroupAggField::Mem,
+            CgroupAggField::Io,
+            CgroupAggField::Pressure
+        ]),
         common_fields = join(CommonField::unit_variant_iter()),
         cgroup_fields = join(SingleCgroupModelFieldId::unit_variant_iter()),
         all_cpu_fields = join(CgroupAggField::Cpu.expand(true)),
@@ -608,6 +961,7 @@ $ below dump cgroup -b "08:30:00" -e "08:30:30" -s cpu.usage_pct --rsort --top 5
         agg_io_fields = join(CgroupAggField::Io.expand(false)),
         agg_pressure_fields = join(CgroupAggField::Pressure.expand(false)),
         default_fields = join(DEFAULT_CGROUP_FIELDS.to_owned()),
+        presets = join_presets(CGROUP_PRESETS),
     )
 });
 
@@ -616,6 +970,7 @@ $ below dump cgroup -b "08:30:00" -e "08:30:30" -s cpu.usage_pct --rsort --top 5
     Clone,
     Debug,
     PartialEq,
+    below_derive::EnumIter,
     below_derive::EnumFromStr,
     below_derive::EnumToString
 )]
@@ -664,6 +1019,14 @@ impl AggField<SingleNetModelFieldId> for IfaceAggField {
             ],
         }
     }
+    fn select_field(&self) -> Option<SingleNetModelFieldId> {
+        use model::SingleNetModelFieldId::*;
+        match self {
+            Self::Rate => Some(ThroughputPerSec),
+            Self::Rx => Some(RxBytes),
+            Self::Tx => Some(TxBytes),
+        }
+    }
 }
 
 pub type IfaceOptionField = DumpOptionField<SingleNetModelFieldId, IfaceAggField>;
@@ -698,12 +1061,16 @@ static IFACE_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 
 * tx: includes [{agg_tx_fields}].
 
+* --select also takes an aggregated field: [{select_fields}].
+
 * --detail: no effect.
 
 * --default: includes [{default_fields}].
 
 * --everything: includes everything (equivalent to --default --detail).
 
+* all: same as --everything when given in --fields.
+
 ********************** Example Commands **********************
 
 Simple example:
@@ -717,6 +1084,8 @@ $ below dump iface -b "08:30:00" -e "08:30:30" -s interface -F eth* -O json
 
 "#,
         about = IFACE_ABOUT,
+        select_fields =
+            join_select_fields(&[IfaceAggField::Rate, IfaceAggField::Rx, IfaceAggField::Tx]),
         common_fields = join(CommonField::unit_variant_iter()),
         agg_rate_fields = join(IfaceAggField::Rate.expand(false)),
         agg_rx_fields = join(IfaceAggField::Rx.expand(false)),
@@ -730,6 +1099,7 @@ $ below dump iface -b "08:30:00" -e "08:30:30" -s interface -F eth* -O json
     Clone,
     Debug,
     PartialEq,
+    below_derive::EnumIter,
     below_derive::EnumFromStr,
     below_derive::EnumToString
 )]
@@ -798,6 +1168,8 @@ static NETWORK_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 
 * --everything: includes everything (equivalent to --default --detail).
 
+* all: same as --everything when given in --fields.
+
 ********************** Example Commands **********************
 
 Example:
@@ -820,6 +1192,7 @@ $ below dump network -b "08:30:00" -e "08:30:30" -f ip ip6 -O json
     Clone,
     Debug,
     PartialEq,
+    below_derive::EnumIter,
     below_derive::EnumFromStr,
     below_derive::EnumToString
 )]
@@ -881,6 +1254,8 @@ static TRANSPORT_LONG_ABOUT: Lazy<String> = Lazy::new(|| {
 
 * --everything: includes everything (equivalent to --default --detail).
 
+* all: same as --everything when given in --fields.
+
 ********************** Example Commands **********************
 
 Example:
@@ -902,8 +1277,203 @@ make_option! (OutputFormat {
     "csv": Csv,
     "json": Json,
     "kv": KeyVal,
+    "graphite": Graphite,
+});
+
+make_option! (RawModelKind {
+    "system": System,
+    "process": Process,
+    "cgroup": Cgroup,
+});
+
+const RAW_MODEL_ABOUT: &str = "Dump the unprojected model as json (unstable)";
+
+const RAW_MODEL_LONG_ABOUT: &str = r#"Dump the unprojected model as json (unstable)
+
+Serializes everything the model of each time slice has captured, bypassing
+the curated fields of the other dump commands. Useful to debug the collector
+when a field seems wrong.
+
+The output follows the internal model structs and is NOT stable: fields can be
+added, renamed or removed between versions. Do not build tooling on it, use the
+other dump commands instead.
+
+Each time slice is dumped as {"timestamp": <unix seconds>, "model": {...}}.
+Options that operate on fields, such as --fields, --select or --sort, have no
+effect.
+
+********************** Example Commands **********************
+
+Example:
+
+$ below dump raw-model cgroup -b "08:30:00" -e "08:30:30" -o cgroup.json
+
+"#;
+
+make_option! (CounterMode {
+    "cumulative": Cumulative,
+    "rate": Rate,
+});
+
+make_option! (FieldPosition {
+    "first": First,
+    "last": Last,
+});
+
+make_option! (FieldOrder {
+    "input": Input,
+    "alpha": Alpha,
+    "grouped": Grouped,
+});
+
+make_option! (ColorWhen {
+    "auto": Auto,
+    "always": Always,
+    "never": Never,
+});
+
+make_option! (SortBy {
+    "value": Value,
+    "delta": Delta,
+    "abs-delta": AbsDelta,
+});
+
+make_option! (QuantizeAlign {
+    "epoch": Epoch,
+    "first": First,
+});
+
+make_option! (ProgressWhen {
+    "auto": Auto,
+    "always": Always,
+    "never": Never,
 });
 
+make_option! (TruncateSide {
+    "left": Left,
+    "right": Right,
+});
+
+/// How raw output fits string values into their columns, from --truncate and
+/// --truncate-side. The default keeps the column widths and folding of each
+/// field.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Truncate {
+    /// Max width of string values, 0 for no truncation.
+    pub width: Option<usize>,
+    pub side: Option<TruncateSide>,
+}
+
+/// A `<field>=<value>` pair. Raw output highlights the field when its value is
+/// above the threshold and --color is enabled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorThreshold {
+    pub field: String,
+    pub threshold: f64,
+}
+
+impl FromStr for ColorThreshold {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.rsplit_once('=') {
+            Some((field, threshold)) if !field.is_empty() => Ok(Self {
+                field: field.to_owned(),
+                threshold: threshold
+                    .parse::<f64>()
+                    .map_err(|_| Error::msg(format!("Invalid threshold value: {}", threshold)))?,
+            }),
+            _ => bail!("Expected <field>=<value>, got: {}", s),
+        }
+    }
+}
+
+/// A `<field>=<name>` pair of --rename.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldRename {
+    pub field: String,
+    pub name: String,
+}
+
+impl FromStr for FieldRename {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once('=') {
+            Some((field, name)) if !field.is_empty() && !name.is_empty() => Ok(Self {
+                field: field.to_owned(),
+                name: name.to_owned(),
+            }),
+            _ => bail!("Expected <field>=<name>, got: {}", s),
+        }
+    }
+}
+
+/// A `<lo>:<hi>` value range for --hist-range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HistRange {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl FromStr for HistRange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (lo, hi) = match s.split_once(':') {
+            Some(range) => range,
+            None => bail!("Expected <lo>:<hi>, got: {}", s),
+        };
+        let parse = |v: &str| {
+            v.parse::<f64>()
+                .map_err(|_| Error::msg(format!("Invalid range value: {}", v)))
+        };
+        let range = Self {
+            lo: parse(lo)?,
+            hi: parse(hi)?,
+        };
+        match range.lo.partial_cmp(&range.hi) {
+            Some(std::cmp::Ordering::Less) => Ok(range),
+            _ => bail!(
+                "Range lower bound must be less than upper bound, got: {}",
+                s
+            ),
+        }
+    }
+}
+
+/// A time range for --range, either `<begin>..<end>` or `last <duration>`,
+/// e.g. `08:30:00..08:35:00` or `last 1h`. Each end is in the format of
+/// --begin and --end, a missing end means now.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeRange {
+    pub begin: String,
+    pub end: Option<String>,
+}
+
+impl FromStr for TimeRange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (begin, end) = if let Some(duration) = s.strip_prefix("last ") {
+            (format!("{} ago", duration.trim()), None)
+        } else if let Some((begin, end)) = s.split_once("..") {
+            let end = end.trim();
+            (
+                begin.trim().to_owned(),
+                Some(end.to_owned()).filter(|end| !end.is_empty()),
+            )
+        } else {
+            bail!("Expected <begin>..<end> or last <duration>, got: {}", s);
+        };
+        if begin.is_empty() {
+            bail!("Missing begin of range: {}", s);
+        }
+        Ok(Self { begin, end })
+    }
+}
+
 #[derive(Debug, Parser, Default, Clone)]
 pub struct GeneralOpt {
     /// Show all top layer fields. If --default is specified, it overrides any specified fields via --fields.
@@ -915,13 +1485,34 @@ pub struct GeneralOpt {
     /// Show more infomation other than default.
     #[clap(short, long)]
     pub detail: bool,
-    /// Begin time, same format as replay
+    /// Start from a curated set of fields, e.g. --fields-preset mem-debug.
+    /// Fields given with --fields are dumped after the preset's. The presets
+    /// of each subcommand are listed in its help.
+    #[clap(long = "fields-preset")]
+    pub fields_preset: Option<String>,
+    /// Begin time, same format as replay. Falls back to $BELOW_DUMP_BEGIN if
+    /// not given, the command line takes precedence.
     #[clap(long, short)]
-    pub begin: String,
-    /// End time, same format as replay
+    pub begin: Option<String>,
+    /// End time, same format as replay. Falls back to $BELOW_DUMP_END if not
+    /// given, the command line takes precedence.
     #[clap(long, short)]
     pub end: Option<String>,
-    /// Take a regex and apply to --select selected field. See command level doc for example.
+    /// Begin and end time in one, as <begin>..<end>, e.g.
+    /// "08:30:00..08:35:00", or as "last <duration>", e.g. "last 1h" for
+    /// -b "1h ago". The end can be left out to dump until now, e.g.
+    /// "08:30:00..". Can't be used with --begin or --end, but overrides
+    /// $BELOW_DUMP_BEGIN and $BELOW_DUMP_END.
+    #[clap(long)]
+    pub range: Option<TimeRange>,
+    /// Read this store directory instead of the one of the below config. Can
+    /// be given multiple times to merge stores, e.g. of several hosts or
+    /// across a store rotation. Samples are dumped in timestamp order and a
+    /// timestamp found in more than one store is taken from the first given.
+    #[clap(long = "store-dir", number_of_values = 1)]
+    pub store_dir: Vec<PathBuf>,
+    /// Take a regex and apply to --select selected field, or to --filter-field
+    /// if given. See command level doc for example.
     #[clap(long, short = 'F')]
     pub filter: Option<Regex>,
     /// Sort (lower to higher) by --select selected field. See command level doc for example.
@@ -930,21 +1521,166 @@ pub struct GeneralOpt {
     /// Sort (higher to lower) by --select selected field. See command level doc for example.
     #[clap(long)]
     pub rsort: bool,
+    /// What --sort and --rsort rank by. Choose from value, the --select
+    /// field itself, delta, its change since the last time slice, or
+    /// abs-delta, the size of the change either way. Entities are matched
+    /// across time slices by pid, cgroup path or disk name, and new ones come
+    /// last with both --sort and --rsort. Default to value.
+    #[clap(long = "sort-by")]
+    pub sort_by: Option<SortBy>,
     // display top N field. See command level doc for example.
     #[clap(long, default_value = "0")]
     pub top: u32,
+    /// Add a rank field holding the 1-based position of each row in the
+    /// order of --sort or --rsort within its time slice. Cgroups are ranked
+    /// among their siblings. Requires --sort or --rsort. Only process, cgroup
+    /// and disk sort their rows.
+    #[clap(long = "with-rank")]
+    pub with_rank: bool,
+    /// Stop after emitting N data rows in total across all time slices. Titles
+    /// are not counted. Applied after --top.
+    #[clap(long = "limit-rows")]
+    pub limit_rows: Option<usize>,
+    /// Only dump every Nth time slice of the time range, i.e. slice 0, N,
+    /// 2N and so on. --top still applies within the dumped slices.
+    #[clap(long = "every-nth")]
+    pub every_nth: Option<usize>,
     /// Repeat title, for each N line, it will render a line of title. Only for raw output format.
     #[clap(long = "repeat-title")]
     pub repeat_title: Option<usize>,
-    /// Output format. Choose from raw, csv, kv, json. Default to raw
+    /// Output format. Choose from raw, csv, kv, json, graphite. Default to
+    /// raw, or output_format of the [dump.options] section of belowrc.
+    /// graphite writes the numeric fields as Graphite plaintext lines, e.g.
+    /// "below.cgroup.system_slice.mem_total 1024 1600000000".
     #[clap(long, short = 'O')]
     pub output_format: Option<OutputFormat>,
-    /// Output destination, default to stdout.
+    /// Output destination, default to stdout. Use unix:<path> to stream to a
+    /// Unix domain socket or fifo:<path> to stream to a named pipe, each time
+    /// slice is flushed as soon as it's dumped. "-" is stdout and a leading
+    /// "~" is expanded into the home directory.
     #[clap(long, short)]
     pub output: Option<String>,
+    /// Create missing parent directories of --output and --output-<format>
+    /// files. Without it, a missing directory is an error.
+    #[clap(long)]
+    pub mkdir: bool,
+    /// Write each time slice to its own file in this directory instead of
+    /// --output, named by the slice timestamp, e.g. 1699999999.json. The
+    /// directory is created if missing. Can't be used with --output or
+    /// --limit-rows, which cap a single output.
+    #[clap(
+        long = "split-by-slice",
+        conflicts_with_all(&["output", "limit-rows"])
+    )]
+    pub split_by_slice: Option<String>,
+    /// Also write raw output to this file. Can be combined with the other
+    /// --output-<format> options to write several formats in one run.
+    #[clap(long = "output-raw")]
+    pub output_raw: Option<String>,
+    /// Also write csv output to this file.
+    #[clap(long = "output-csv")]
+    pub output_csv: Option<String>,
+    /// Also write json output to this file.
+    #[clap(long = "output-json")]
+    pub output_json: Option<String>,
+    /// Also write kv output to this file.
+    #[clap(long = "output-kv")]
+    pub output_kv: Option<String>,
+    /// Format everything as usual but throw the output away, to benchmark
+    /// reading and formatting without the cost of writing. Same as
+    /// --output /dev/null. With --progress, the throughput in slices/s and
+    /// MB/s of formatted output is reported.
+    #[clap(long, conflicts_with_all(&["output", "split-by-slice"]))]
+    pub discard: bool,
     /// Disable title in raw or csv format output
     #[clap(long)]
     pub disable_title: bool,
+    /// Order of the resolved fields. Choose from input, alpha, grouped.
+    /// Default to input, the order given by --fields or of the aggregated
+    /// fields. alpha sorts the fields by name and grouped clusters them by
+    /// aggregated field, e.g. all cpu fields, then mem, io and pressure.
+    /// Timestamps and fields outside of any aggregated field, like name or
+    /// pid, stay first. Applied before --move-field.
+    #[clap(long)]
+    pub order: Option<FieldOrder>,
+    /// Move a field to the first or last column after the fields are
+    /// resolved, e.g. --move-field comm first. Can be given multiple times,
+    /// fields moved to the same end keep the order they are given in.
+    #[clap(
+        long = "move-field",
+        number_of_values = 2,
+        value_names = &["FIELD", "first|last"]
+    )]
+    pub move_field: Vec<String>,
+    /// Leave out the fields that are zero or missing in every dumped row,
+    /// e.g. counters of controllers not in use on this host. Rows are kept
+    /// until the end of the dump to find them, so only json and csv output
+    /// are supported. Timestamps are always kept.
+    #[clap(
+        long = "drop-zero-columns",
+        conflicts_with_all(&[
+            "histogram",
+            "diff",
+            "anomalies",
+            "watch",
+            "quantize",
+            "transpose",
+            "with-meta",
+            "split-by-slice",
+        ])
+    )]
+    pub drop_zero_columns: bool,
+    /// Add a column computed from numeric fields of the row, in the form
+    /// <name>=<expression>, e.g. "rw=io.rbytes_per_sec+io.wbytes_per_sec".
+    /// Supports + - * /, parentheses, numbers and field names as given to
+    /// --fields. Can be given multiple times, columns are added after the
+    /// other fields. Division by zero gives no value, which is null in json
+    /// and empty in other output unless --na-string is given.
+    #[clap(long, number_of_values = 1)]
+    pub expr: Vec<ExprColumn>,
+    /// Dump a field under another title in raw and csv output, or another
+    /// key in json and kv output, in the form <field>=<name>, e.g.
+    /// "cpu.usage_pct=cpu_util". The field must be in the selected fields
+    /// and the new name must not collide with other fields. Can be given
+    /// multiple times.
+    #[clap(long, number_of_values = 1)]
+    pub rename: Vec<FieldRename>,
+    /// Snap --begin down and --end up to a multiple of this interval since
+    /// the epoch, e.g. 5m, so dumps from different hosts cover the same time
+    /// slices.
+    #[clap(long)]
+    pub align: Option<humantime::Duration>,
+    /// Combine the time slices within each bucket of this length, e.g. 5m,
+    /// into one row per bucket. Rates and percentages are averaged, counters
+    /// are dumped as their increase within the bucket and everything else
+    /// takes the value of the last time slice. A Samples column holds the
+    /// number of time slices combined. Rows are told apart by pid, cgroup
+    /// path or name, which have to be dumped. Only works with json and csv
+    /// output.
+    #[clap(
+        long,
+        conflicts_with_all(&["histogram", "transpose", "split-by-slice"])
+    )]
+    pub quantize: Option<humantime::Duration>,
+    /// Where --quantize buckets start. Choose from epoch, buckets at
+    /// multiples of the interval since the epoch, and first, buckets
+    /// starting at the first time slice dumped. Default to epoch.
+    #[clap(long = "quantize-align", requires = "quantize")]
+    pub quantize_align: Option<QuantizeAlign>,
+    /// Compare the time slices at two times, same format as --begin, instead
+    /// of dumping the time series in between. Dumps one row per entity with
+    /// a Status column of kept, added or removed. Counters are dumped as
+    /// their increase, everything else as the value of both slices and the
+    /// difference. --begin and --end are ignored. Rows are told apart by
+    /// pid, cgroup path or name, which have to be dumped. Only works with
+    /// json and csv output.
+    #[clap(
+        long,
+        number_of_values = 2,
+        value_names = &["T1", "T2"],
+        conflicts_with_all(&["histogram", "transpose", "quantize", "split-by-slice", "watch", "dry-run", "output-raw", "output-csv", "output-json", "output-kv"])
+    )]
+    pub diff: Vec<String>,
     /// Days adjuster, same as -r option in replay.
     #[clap(short = 'r')]
     pub yesterdays: Option<String>,
@@ -954,13 +1690,163 @@ pub struct GeneralOpt {
     /// Dump raw data without units or conversion
     #[clap(long)]
     pub raw: bool,
+    /// Render missing or unavailable values as this string in raw, csv and
+    /// kv output, e.g. --na-string "" or --na-string NaN. Default to "?".
+    /// Json output is not affected.
+    #[clap(long = "na-string")]
+    pub na_string: Option<String>,
+    /// Group the digits of integer values with commas in raw output, e.g.
+    /// 1,234,567. Timestamps are not grouped. Csv, json and kv output is not
+    /// affected.
+    #[clap(long = "group-digits")]
+    pub group_digits: bool,
+    /// Max width of string values such as comm and cgroup names in raw
+    /// output, 0 for no truncation. Default to the column width of each
+    /// field. Columns are at least 10 wide. Csv, json and kv output is never
+    /// truncated.
+    #[clap(long)]
+    pub truncate: Option<usize>,
+    /// Which end of a string value raw output cuts off when it is too wide.
+    /// Choose from left, keeping the end, e.g. the leaf of a cgroup path, or
+    /// right, keeping the beginning. Default to right, with some fields
+    /// instead eliding the middle.
+    #[clap(long = "truncate-side")]
+    pub truncate_side: Option<TruncateSide>,
+    /// How to dump monotonic counters that have a per second rate field,
+    /// e.g. mem.events_oom_kill and mem.events_oom_kill_per_sec, or a
+    /// percentage field, e.g. cpu.usage_usec and cpu.usage_pct. Choose from
+    /// cumulative, the counter as read, or rate, the per second rate over the
+    /// time slice. Default to cumulative.
+    #[clap(long)]
+    pub counters: Option<CounterMode>,
+    /// Highlight values above --color-threshold in raw output. Choose from
+    /// auto, always, never. Default to auto, which only colors output written
+    /// to a terminal. Never applies to csv, json or kv output.
+    #[clap(long)]
+    pub color: Option<ColorWhen>,
+    /// Threshold for --color in the form <field>=<value>, e.g.
+    /// cpu.usage_pct=80. Can be given multiple times.
+    #[clap(long = "color-threshold", number_of_values = 1)]
+    pub color_threshold: Vec<ColorThreshold>,
+    /// Print the resolved fields, time range, filter/sort/top settings and
+    /// output format, then exit without reading any data.
+    #[clap(long = "dry-run")]
+    pub dry_run: bool,
+    /// Print each resolved field with where it comes from, e.g. given by
+    /// --fields, a --fields-preset, --default or a glob, or expanded from an
+    /// aggregated field like cpu, then exit without reading any data.
+    #[clap(long = "explain-fields")]
+    pub explain_fields: bool,
+    /// Fail if no rows are dumped. By default the command exits with code 3
+    /// in that case.
+    #[clap(long = "fail-on-empty")]
+    pub fail_on_empty: bool,
+    /// If the dump fails midway, end json output with an object like
+    /// {"error": "...", "last_timestamp": 1600000000} instead of leaving it
+    /// truncated. last_timestamp is the last time slice dumped in full, or
+    /// null. The command still exits non-zero.
+    #[clap(long = "json-errors")]
+    pub json_errors: bool,
+    /// Start the output with a metadata record holding the below version,
+    /// the dumped fields and the time the dump was generated. For json it is
+    /// a first {"_meta": {...}} element, for csv it is header lines starting
+    /// with "#". Only works with json and csv output.
+    #[clap(long = "with-meta", conflicts_with_all(&["histogram", "transpose"]))]
+    pub with_meta: bool,
+    /// Write a progress line with the percent of the time range dumped, the
+    /// time slices done and an ETA to stderr while dumping. --progress alone
+    /// is --progress=auto, which only shows it when stderr is a terminal.
+    /// Choose from auto, always, never. The dumped output is not affected.
+    #[clap(
+        long,
+        min_values = 0,
+        require_equals = true,
+        default_missing_value = "auto"
+    )]
+    pub progress: Option<ProgressWhen>,
+    /// Instead of dumping rows, tally the values of this numeric field over
+    /// the whole time range into --bins buckets and print the counts at the
+    /// end. --select, --filter and --top still decide which rows are counted.
+    #[clap(
+        long,
+        conflicts_with_all(&["output-format", "split-by-slice", "output-raw", "output-csv", "output-json", "output-kv"])
+    )]
+    pub histogram: Option<String>,
+    /// Dump again every N seconds and redraw the output in place, e.g. with
+    /// -b "1m ago" to keep showing the last minute. --begin and --end are
+    /// evaluated again each time. Only works with raw output to a terminal,
+    /// stop with Ctrl-C.
+    #[clap(long, value_name = "SECS")]
+    pub watch: Option<u64>,
+    /// Print fields as lines and rows as columns instead of a wide table.
+    /// Only works with raw output of a single time slice, e.g. -b now, or
+    /// --limit-rows. Combine with --top 1 to get a single column.
+    #[clap(
+        long,
+        conflicts_with_all(&["histogram", "split-by-slice", "output-raw", "output-csv", "output-json", "output-kv"])
+    )]
+    pub transpose: bool,
+    /// Number of --histogram buckets. Default to 10.
+    #[clap(long, requires = "histogram")]
+    pub bins: Option<usize>,
+    /// Value range of --histogram buckets in the form <lo>:<hi>. Values
+    /// outside of it are counted separately. Default to the observed min and
+    /// max.
+    #[clap(long = "hist-range", requires = "histogram")]
+    pub hist_range: Option<HistRange>,
+    /// Instead of dumping rows, write one line per pair of consecutive time
+    /// slices for the rows whose value of this numeric field went up and
+    /// down the most, e.g. "at 2023-01-01 10:00:05: full_path=/system.slice
+    /// cpu.usage_pct jumped +40 (10 -> 50)". Rows are matched by pid, cgroup
+    /// path or name, disk name or interface, which are added to the output.
+    #[clap(
+        long,
+        conflicts_with_all(&["histogram", "transpose", "quantize", "diff", "output-format", "split-by-slice", "output-raw", "output-csv", "output-json", "output-kv"])
+    )]
+    pub anomalies: Option<String>,
+    /// Leave out --anomalies changes smaller than this, in the unit of the
+    /// field. Default to 0, every change is reported.
+    #[clap(long = "anomaly-threshold", requires = "anomalies")]
+    pub anomaly_threshold: Option<f64>,
+}
+
+impl GeneralOpt {
+    /// Pairs of field name and position given by --move-field.
+    pub fn field_moves(&self) -> Result<Vec<(&str, FieldPosition)>> {
+        self.move_field
+            .chunks(2)
+            .map(|pair| match pair {
+                [field, position] => Ok((field.as_str(), FieldPosition::from_str(position)?)),
+                _ => bail!("--move-field takes a field and a position"),
+            })
+            .collect()
+    }
+
+    /// String truncation of raw output.
+    pub fn truncation(&self) -> Truncate {
+        Truncate {
+            width: self.truncate,
+            side: self.truncate_side,
+        }
+    }
+
+    /// Thresholds to highlight in raw output. Empty unless --color has been
+    /// resolved to always for the output being written.
+    pub fn active_color_thresholds(&self) -> &[ColorThreshold] {
+        match self.color {
+            Some(ColorWhen::Always) => &self.color_threshold,
+            _ => &[],
+        }
+    }
 }
 
 #[derive(Debug, Parser, Clone)]
 pub enum DumpCommand {
     #[clap(about = SYSTEM_ABOUT, long_about = SYSTEM_LONG_ABOUT.as_str())]
     System {
-        /// Select which fields to display and in what order.
+        /// Select which fields to display and in what order. Aggregated fields
+        /// are expanded in place, and `all` selects every field. Globs like
+        /// 'mem.pg*' select all matching fields in sorted order.
         #[clap(short, long)]
         fields: Option<Vec<SystemOptionField>>,
         #[clap(flatten)]
@@ -971,63 +1857,119 @@ pub enum DumpCommand {
     },
     #[clap(about = DISK_ABOUT, long_about = DISK_LONG_ABOUT.as_str())]
     Disk {
-        /// Select which fields to display and in what order.
+        /// Select which fields to display and in what order. Aggregated fields
+        /// are expanded in place, and `all` selects every field. Globs like
+        /// 'mem.pg*' select all matching fields in sorted order.
         #[clap(short, long)]
         fields: Option<Vec<DiskOptionField>>,
         #[clap(flatten)]
         opts: GeneralOpt,
-        /// Select field for operation, use with --sort, --rsort, --filter, --top
-        #[clap(long, short)]
+        /// Select field for operation, use with --sort, --rsort, --filter, --top. Aggregated fields
+        /// are mapped to their representative field.
+        #[clap(long, short, parse(try_from_str = parse_select::<SingleDiskModelFieldId, DiskAggField>))]
         select: Option<SingleDiskModelFieldId>,
+        /// Field to apply --filter to, instead of the --select field. Allows
+        /// filtering on one field while sorting by another. Aggregated fields
+        /// are mapped to their representative field.
+        #[clap(long = "filter-field", parse(try_from_str = parse_select::<SingleDiskModelFieldId, DiskAggField>))]
+        filter_field: Option<SingleDiskModelFieldId>,
         /// Saved pattern in the dumprc file under [disk] section.
         #[clap(long, short, conflicts_with("fields"))]
         pattern: Option<String>,
     },
     #[clap(about = PROCESS_ABOUT, long_about = PROCESS_LONG_ABOUT.as_str())]
     Process {
-        /// Select which fields to display and in what order.
+        /// Select which fields to display and in what order. Aggregated fields
+        /// are expanded in place, and `all` selects every field. Globs like
+        /// 'mem.pg*' select all matching fields in sorted order.
         #[clap(short, long)]
         fields: Option<Vec<ProcessOptionField>>,
         #[clap(flatten)]
         opts: GeneralOpt,
-        /// Select field for operation, use with --sort, --rsort, --filter, --top
-        #[clap(long, short)]
+        /// Select field for operation, use with --sort, --rsort, --filter, --top. Aggregated fields
+        /// are mapped to their representative field.
+        #[clap(long, short, parse(try_from_str = parse_select::<SingleProcessModelFieldId, ProcessAggField>))]
         select: Option<SingleProcessModelFieldId>,
+        /// Field to apply --filter to, instead of the --select field. Allows
+        /// filtering on one field while sorting by another. Aggregated fields
+        /// are mapped to their representative field.
+        #[clap(long = "filter-field", parse(try_from_str = parse_select::<SingleProcessModelFieldId, ProcessAggField>))]
+        filter_field: Option<SingleProcessModelFieldId>,
         /// Saved pattern in the dumprc file under [process] section.
         #[clap(long, short, conflicts_with("fields"))]
         pattern: Option<String>,
+        /// Dump each thread of the processes instead, with the tid field added
+        /// after pid. comm, state, uptime and cpu are per thread, io and mem
+        /// are left out. Needs data recorded with `below record --threads`.
+        #[clap(long)]
+        threads: bool,
+        /// Only dump the processes with these pids, e.g. --pids 1,42,1000.
+        /// Applies together with --filter. A pid that doesn't exist in a
+        /// time slice is left out of it. With --threads, dumps the threads
+        /// of these processes.
+        #[clap(long, use_value_delimiter = true)]
+        pids: Option<Vec<i32>>,
     },
     #[clap(about = CGROUP_ABOUT, long_about = CGROUP_LONG_ABOUT.as_str())]
     Cgroup {
-        /// Select which fields to display and in what order.
+        /// Select which fields to display and in what order. Aggregated fields
+        /// are expanded in place, and `all` selects every field. Globs like
+        /// 'mem.pg*' select all matching fields in sorted order.
         #[clap(short, long)]
         fields: Option<Vec<CgroupOptionField>>,
         #[clap(flatten)]
         opts: GeneralOpt,
-        /// Select field for operation, use with --sort, --rsort, --filter, --top
-        #[clap(long, short)]
+        /// Select field for operation, use with --sort, --rsort, --filter, --top. Aggregated fields
+        /// are mapped to their representative field.
+        #[clap(long, short, parse(try_from_str = parse_select::<SingleCgroupModelFieldId, CgroupAggField>))]
         select: Option<SingleCgroupModelFieldId>,
+        /// Field to apply --filter to, instead of the --select field. Allows
+        /// filtering on one field while sorting by another. Aggregated fields
+        /// are mapped to their representative field.
+        #[clap(long = "filter-field", parse(try_from_str = parse_select::<SingleCgroupModelFieldId, CgroupAggField>))]
+        filter_field: Option<SingleCgroupModelFieldId>,
         /// Saved pattern in the dumprc file under [cgroup] section.
         #[clap(long, short, conflicts_with("fields"))]
         pattern: Option<String>,
+        /// Append a <total> row to each time slice with the sum of the
+        /// dumped cgroups. A cgroup and its children are both counted if
+        /// both are dumped, so this is not the host total. In json output it
+        /// follows the cgroup tree and has "total": true.
+        #[clap(long = "with-total")]
+        with_total: bool,
+        /// Dump one row per device in io.stat for each cgroup, with the
+        /// device field added after name. The io fields are of that device,
+        /// the other fields repeat the values of the cgroup.
+        #[clap(long = "per-device")]
+        per_device: bool,
     },
     #[clap(about = IFACE_ABOUT, long_about = IFACE_LONG_ABOUT.as_str())]
     Iface {
-        /// Select which fields to display and in what order.
+        /// Select which fields to display and in what order. Aggregated fields
+        /// are expanded in place, and `all` selects every field. Globs like
+        /// 'mem.pg*' select all matching fields in sorted order.
         #[clap(short, long)]
         fields: Option<Vec<IfaceOptionField>>,
         #[clap(flatten)]
         opts: GeneralOpt,
-        /// Select field for operation, use with --filter
-        #[clap(long, short)]
+        /// Select field for operation, use with --filter. Aggregated fields
+        /// are mapped to their representative field.
+        #[clap(long, short, parse(try_from_str = parse_select::<SingleNetModelFieldId, IfaceAggField>))]
         select: Option<SingleNetModelFieldId>,
+        /// Field to apply --filter to, instead of the --select field. Allows
+        /// filtering on one field while sorting by another. Aggregated fields
+        /// are mapped to their representative field.
+        #[clap(long = "filter-field", parse(try_from_str = parse_select::<SingleNetModelFieldId, IfaceAggField>))]
+        filter_field: Option<SingleNetModelFieldId>,
         /// Saved pattern in the dumprc file under [iface] section.
         #[clap(long, short, conflicts_with("fields"))]
         pattern: Option<String>,
     },
     #[clap(about = NETWORK_ABOUT, long_about = NETWORK_LONG_ABOUT.as_str())]
     Network {
-        /// Select which fields to display and in what order.
+        /// Select which fields to display and in what order. Aggregated fields
+        /// are expanded in place, and `all` selects every field. Globs like
+        /// 'mem.pg*' select all matching fields in sorted order.
         #[clap(short, long)]
         fields: Option<Vec<NetworkOptionField>>,
         #[clap(flatten)]
@@ -1038,7 +1980,9 @@ pub enum DumpCommand {
     },
     #[clap(about = TRANSPORT_ABOUT, long_about = TRANSPORT_LONG_ABOUT.as_str())]
     Transport {
-        /// Select which fields to display and in what order.
+        /// Select which fields to display and in what order. Aggregated fields
+        /// are expanded in place, and `all` selects every field. Globs like
+        /// 'mem.pg*' select all matching fields in sorted order.
         #[clap(short, long)]
         fields: Option<Vec<TransportOptionField>>,
         #[clap(flatten)]
@@ -1047,4 +1991,11 @@ pub enum DumpCommand {
         #[clap(long, short, conflicts_with("fields"))]
         pattern: Option<String>,
     },
+    #[clap(about = RAW_MODEL_ABOUT, long_about = RAW_MODEL_LONG_ABOUT)]
+    RawModel {
+        /// Model to dump. Choose from system, process, cgroup.
+        model: RawModelKind,
+        #[clap(flatten)]
+        opts: GeneralOpt,
+    },
 }
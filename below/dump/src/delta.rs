@@ -0,0 +1,108 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::*;
+use command::SortBy;
+
+/// The --select value of each entity in the last time slice, by the identity
+/// of the entity, e.g. the pid of a process. Used by --sort-by delta.
+#[derive(Default)]
+pub struct LastValues(RefCell<SliceValues>);
+
+#[derive(Default)]
+struct SliceValues {
+    last: HashMap<String, f64>,
+    curr: HashMap<String, f64>,
+}
+
+impl LastValues {
+    /// Start a new time slice. Entities missing from the slice before have
+    /// no last value then, even if they were in an earlier one.
+    pub fn next_slice(&self) {
+        let mut values = self.0.borrow_mut();
+        values.last = std::mem::take(&mut values.curr);
+    }
+}
+
+fn numeric_value(field: Option<Field>) -> Option<f64> {
+    match field? {
+        value @ (Field::U32(_)
+        | Field::U64(_)
+        | Field::I32(_)
+        | Field::I64(_)
+        | Field::F32(_)
+        | Field::F64(_)) => Some(f64::from(value)),
+        _ => None,
+    }
+}
+
+/// Sort by `field_id` as --sort-by says, higher to lower if `reverse`. For
+/// delta and abs-delta, `id` matches entities across time slices and the
+/// values of this slice are kept in `last` for the next one, which must be
+/// started with `LastValues::next_slice`. Entities with no value in the last
+/// slice, e.g. in the first slice, come last in both directions.
+pub fn sort_queriables_by<T: Queriable>(
+    queriables: &mut [&T],
+    field_id: &T::FieldId,
+    reverse: bool,
+    sort_by: Option<SortBy>,
+    id: impl Fn(&T) -> String,
+    last: &LastValues,
+) {
+    let abs = match sort_by.unwrap_or(SortBy::Value) {
+        SortBy::Value => return model::sort_queriables(queriables, field_id, reverse),
+        SortBy::Delta => false,
+        SortBy::AbsDelta => true,
+    };
+    let mut values = last.0.borrow_mut();
+    let mut keyed = queriables
+        .iter()
+        .map(|queriable| {
+            let key = id(queriable);
+            let value = numeric_value(queriable.query(field_id));
+            let delta = value.zip(values.last.get(&key)).map(|(value, last)| {
+                if abs {
+                    (value - last).abs()
+                } else {
+                    value - last
+                }
+            });
+            if let Some(value) = value {
+                values.curr.insert(key, value);
+            }
+            (delta, *queriable)
+        })
+        .collect::<Vec<_>>();
+    keyed.sort_by(|(lhs, _), (rhs, _)| match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => {
+            let order = lhs.partial_cmp(rhs).unwrap_or(Ordering::Equal);
+            if reverse {
+                order.reverse()
+            } else {
+                order
+            }
+        }
+        // Entities without a delta come last either way
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    for (slot, (_, queriable)) in queriables.iter_mut().zip(keyed) {
+        *slot = queriable;
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+
+use super::*;
+
+use quantize::{Agg, Quantize, QuantizeField};
+
+/// Title of the column telling whether an entity was added, removed or kept
+/// between the two time slices.
+pub const STATUS_TITLE: &str = "Status";
+
+type Row = serde_json::Map<String, Value>;
+
+/// Dumper for --diff. The wrapped dumper must dump raw json, which is read
+/// back for the two time slices. Rows are matched by the same fields that
+/// tell rows apart for --quantize, e.g. pid or cgroup path. Counters are
+/// dumped as their increase, everything else as the values of both slices
+/// and their difference. Time fields are left out.
+pub struct Diff {
+    dumper: Box<dyn Dumper>,
+    fields: Vec<QuantizeField>,
+    output_format: Option<OutputFormat>,
+    disable_title: bool,
+    na_string: Option<String>,
+}
+
+/// Parse a value dumped as raw json. Missing values are dumped as "?".
+pub(crate) fn number(value: Option<&Value>) -> Option<f64> {
+    value
+        .and_then(|v| v.as_str())
+        .and_then(|v| v.parse::<f64>().ok())
+}
+
+pub(crate) fn format_number(number: f64) -> String {
+    if number.fract() == 0.0 {
+        format!("{}", number)
+    } else {
+        format!("{:.2}", number)
+    }
+}
+
+/// Render a value dumped as raw json, None if it's missing.
+fn format_value(value: Option<&Value>) -> Option<String> {
+    match value {
+        None | Some(Value::Null) => None,
+        Some(Value::String(v)) if v == "?" => None,
+        Some(Value::String(v)) => Some(v.clone()),
+        Some(v) => Some(v.to_string()),
+    }
+}
+
+impl Diff {
+    pub fn new(
+        dumper: Box<dyn Dumper>,
+        output_format: Option<OutputFormat>,
+        disable_title: bool,
+        na_string: Option<String>,
+    ) -> Result<Self> {
+        if !matches!(
+            output_format,
+            Some(OutputFormat::Json) | Some(OutputFormat::Csv)
+        ) {
+            bail!("--diff only works with json or csv output");
+        }
+        let fields = dumper.quantize_fields()?;
+        if fields.is_empty() {
+            bail!("--diff is not supported by this command");
+        }
+        Ok(Self {
+            dumper,
+            fields,
+            output_format,
+            disable_title,
+            na_string,
+        })
+    }
+
+    /// Rows dumped for one time slice with the key each is matched by.
+    fn get_rows(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+    ) -> Result<Vec<(String, Row)>> {
+        let mut content: Vec<u8> = Vec::new();
+        let mut round = 0;
+        if self
+            .dumper
+            .dump_model(ctx, model, &mut content, &mut round, false)?
+            != IterExecResult::Success
+        {
+            return Ok(Vec::new());
+        }
+        let value: Value = serde_json::from_slice(&content)?;
+        let mut rows = Vec::new();
+        Quantize::collect_rows(&value, &mut rows);
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let key = self
+                    .fields
+                    .iter()
+                    .filter(|field| field.agg == Agg::Key)
+                    .map(|field| row.get(&field.title).unwrap_or(&Value::Null).to_string())
+                    .collect::<Vec<_>>()
+                    .join("\0");
+                (key, row.clone())
+            })
+            .collect())
+    }
+
+    fn titles(&self) -> Vec<String> {
+        let mut titles = vec![STATUS_TITLE.to_owned()];
+        for field in &self.fields {
+            match field.agg {
+                Agg::Time(_) => {}
+                Agg::Key => titles.push(field.title.clone()),
+                Agg::Sum => titles.push(format!("{} Diff", field.title)),
+                Agg::Mean | Agg::Last => {
+                    titles.push(format!("{} Before", field.title));
+                    titles.push(format!("{} After", field.title));
+                    titles.push(format!("{} Diff", field.title));
+                }
+            }
+        }
+        titles
+    }
+
+    /// Values of one row in the order of `titles`, None if missing. Either
+    /// side is missing for entities added or removed.
+    fn render_row(&self, before: Option<&Row>, after: Option<&Row>) -> Vec<Option<String>> {
+        let status = match (before, after) {
+            (Some(_), Some(_)) => "kept",
+            (None, _) => "added",
+            (_, None) => "removed",
+        };
+        let mut res = vec![Some(status.to_owned())];
+        for field in &self.fields {
+            let before = before.and_then(|row| row.get(&field.title));
+            let after = after.and_then(|row| row.get(&field.title));
+            let diff = match (number(before), number(after)) {
+                // Counter was reset, e.g. the process restarted
+                (Some(before), Some(after)) if field.agg == Agg::Sum && after < before => {
+                    Some(format_number(after))
+                }
+                (Some(before), Some(after)) => Some(format_number(after - before)),
+                _ => None,
+            };
+            match field.agg {
+                Agg::Time(_) => {}
+                Agg::Key => res.push(format_value(after.or(before))),
+                Agg::Sum => res.push(diff),
+                Agg::Mean | Agg::Last => {
+                    res.push(format_value(before));
+                    res.push(format_value(after));
+                    res.push(diff);
+                }
+            }
+        }
+        res
+    }
+
+    /// Write one row per entity of either time slice. Entities of the
+    /// first slice come first, in its order, followed by the added ones.
+    /// Returns the number of rows written.
+    pub fn dump(
+        &self,
+        before: (&CommonFieldContext, &model::Model),
+        after: (&CommonFieldContext, &model::Model),
+        output: &mut dyn Write,
+    ) -> Result<usize> {
+        let before_rows = self.get_rows(before.0, before.1)?;
+        let after_rows = self.get_rows(after.0, after.1)?;
+        let after_index = after_rows
+            .iter()
+            .enumerate()
+            .map(|(idx, (key, _))| (key.as_str(), idx))
+            .collect::<HashMap<_, _>>();
+        let before_keys = before_rows
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<HashSet<_>>();
+        let rows = before_rows
+            .iter()
+            .map(|(key, row)| {
+                let after = after_index.get(key.as_str()).map(|idx| &after_rows[*idx].1);
+                self.render_row(Some(row), after)
+            })
+            .chain(
+                after_rows
+                    .iter()
+                    .filter(|(key, _)| !before_keys.contains(key.as_str()))
+                    .map(|(_, row)| self.render_row(None, Some(row))),
+            )
+            .collect::<Vec<_>>();
+
+        let titles = self.titles();
+        if self.output_format == Some(OutputFormat::Json) {
+            let arr = rows
+                .iter()
+                .map(|row| {
+                    let mut obj = json!({});
+                    for (title, value) in titles.iter().zip(row.iter()) {
+                        obj[title] = json!(value.as_deref().unwrap_or("?"));
+                    }
+                    obj
+                })
+                .collect::<Vec<_>>();
+            writeln!(output, "{}", json!(arr))?;
+        } else {
+            if !self.disable_title {
+                write!(output, "{}", print::dump_csv_line(&titles))?;
+            }
+            let na_string = self.na_string.as_deref().unwrap_or("?");
+            for row in &rows {
+                let values = row
+                    .iter()
+                    .map(|value| value.as_deref().unwrap_or(na_string));
+                write!(output, "{}", print::dump_csv_line(values))?;
+            }
+        }
+        Ok(rows.len())
+    }
+}
@@ -21,19 +21,26 @@ impl HasRenderConfigForDump for model::SingleDiskModel {}
 pub struct Disk {
     opts: GeneralOpt,
     select: Option<SingleDiskModelFieldId>,
+    // Field --filter applies to, --select unless --filter-field is given
+    filter_field: Option<SingleDiskModelFieldId>,
     fields: Vec<DiskField>,
+    // Values of the last time slice for --sort-by delta
+    last_values: delta::LastValues,
 }
 
 impl Disk {
     pub fn new(
         opts: &GeneralOpt,
         select: Option<SingleDiskModelFieldId>,
+        filter_field: Option<SingleDiskModelFieldId>,
         fields: Vec<DiskField>,
     ) -> Self {
         Self {
             opts: opts.to_owned(),
+            filter_field: filter_field.or_else(|| select.clone()),
             select,
             fields,
+            last_values: Default::default(),
         }
     }
 }
@@ -47,12 +54,13 @@ impl Dumper for Disk {
         round: &mut usize,
         comma_flag: bool,
     ) -> Result<IterExecResult> {
+        self.last_values.next_slice();
         let mut disks: Vec<_> = model
             .system
             .disks
             .iter()
-            .filter_map(
-                |(_, model)| match (self.select.as_ref(), self.opts.filter.as_ref()) {
+            .filter_map(|(_, model)| {
+                match (self.filter_field.as_ref(), self.opts.filter.as_ref()) {
                     (Some(field_id), Some(filter))
                         if !filter.is_match(
                             &model
@@ -63,29 +71,39 @@ impl Dumper for Disk {
                         None
                     }
                     _ => Some(model),
-                },
-            )
+                }
+            })
             .collect();
 
         if let Some(field_id) = &self.select {
-            if self.opts.sort {
-                model::sort_queriables(&mut disks, &field_id, false);
-            }
-
-            if self.opts.rsort {
-                model::sort_queriables(&mut disks, &field_id, true);
+            if self.opts.sort || self.opts.rsort {
+                delta::sort_queriables_by(
+                    &mut disks,
+                    field_id,
+                    self.opts.rsort,
+                    self.opts.sort_by,
+                    |disk| disk.name.clone().unwrap_or_default(),
+                    &self.last_values,
+                );
             }
 
             if (self.opts.sort || self.opts.rsort) && self.opts.top != 0 {
                 disks.truncate(self.opts.top as usize);
             }
         }
+
+        if let Some(limit) = self.opts.limit_rows {
+            disks.truncate(limit.saturating_sub(*round));
+        }
+        let ranked = self.select.is_some() && (self.opts.sort || self.opts.rsort);
         let json = self.opts.output_format == Some(OutputFormat::Json);
         let mut json_output = json!([]);
 
         disks
             .into_iter()
-            .map(|model| {
+            .enumerate()
+            .map(|(idx, model)| {
+                let ctx = &ctx.with_rank(ranked.then(|| idx + 1));
                 match self.opts.output_format {
                     Some(OutputFormat::Raw) | None => write!(
                         output,
@@ -95,9 +113,7 @@ impl Dumper for Disk {
                             ctx,
                             model,
                             *round,
-                            self.opts.repeat_title,
-                            self.opts.disable_title,
-                            self.opts.raw
+                            &print::RawOptions::new(&self.opts),
                         )
                     )?,
                     Some(OutputFormat::Csv) => write!(
@@ -109,13 +125,36 @@ impl Dumper for Disk {
                             model,
                             *round,
                             self.opts.disable_title,
-                            self.opts.raw
+                            self.opts.raw,
+                            self.opts.na_string.as_deref()
                         )
                     )?,
                     Some(OutputFormat::KeyVal) => write!(
                         output,
                         "{}",
-                        print::dump_kv(&self.fields, ctx, model, self.opts.raw)
+                        print::dump_kv(
+                            &self.fields,
+                            ctx,
+                            model,
+                            self.opts.raw,
+                            self.opts.na_string.as_deref()
+                        )
+                    )?,
+                    Some(OutputFormat::Graphite) => write!(
+                        output,
+                        "{}",
+                        print::dump_graphite(
+                            &self.fields,
+                            ctx,
+                            model,
+                            &format!(
+                                "{}.disk.{}",
+                                print::GRAPHITE_ROOT,
+                                print::graphite_component(
+                                    model.name.as_deref().unwrap_or("unknown")
+                                )
+                            )
+                        )
                     )?,
                     Some(OutputFormat::Json) => {
                         let par = print::dump_json(&self.fields, ctx, model, self.opts.raw);
@@ -130,9 +169,23 @@ impl Dumper for Disk {
         match (json, comma_flag) {
             (true, true) => write!(output, ",{}", json_output)?,
             (true, false) => write!(output, "{}", json_output)?,
+            // Graphite lines are not separated by time slice
+            _ if self.opts.output_format == Some(OutputFormat::Graphite) => {}
             _ => write!(output, "\n")?,
         };
 
         Ok(IterExecResult::Success)
     }
+
+    fn field_names(&self) -> Vec<String> {
+        self.fields.iter().map(ToString::to_string).collect()
+    }
+
+    fn quantize_fields(&self) -> Result<Vec<quantize::QuantizeField>> {
+        quantize::quantize_fields(&self.fields, &[model::SingleDiskModelFieldId::Name])
+    }
+
+    fn nonzero_columns(&self) -> Vec<nonzero::Column> {
+        nonzero::columns(&self.fields)
+    }
 }
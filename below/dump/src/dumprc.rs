@@ -0,0 +1,137 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use command::GeneralOpt;
+
+/// Name of the belowrc table holding option defaults, both under [dump] and
+/// under [dump.SUBCOMMAND].
+const OPTIONS_KEY: &str = "options";
+
+/// Options that can be given a default in belowrc. Only options that take a
+/// value are supported, so that the command line can always override them.
+const OPTION_KEYS: &[&str] = &[
+    "output_format",
+    "repeat_title",
+    "na_string",
+    "truncate",
+    "truncate_side",
+    "counters",
+    "color",
+    "progress",
+    "order",
+];
+
+/// Defaults of `below dump` options from the [dump.options] section of
+/// belowrc, overridden by [dump.SUBCOMMAND.options] for a single subcommand.
+/// Options given on the command line take precedence over both.
+#[derive(Debug, Default, PartialEq)]
+pub struct DumpRc {
+    options: toml::value::Table,
+}
+
+impl DumpRc {
+    /// Get the defaults of `subcommand` from a parsed belowrc. Either section
+    /// can be missing.
+    pub fn from_belowrc(belowrc: &TValue, subcommand: &str) -> Result<Self> {
+        let dump = belowrc.get(get_belowrc_dump_section_key());
+        let sections = [
+            (
+                format!("{}.{}", get_belowrc_dump_section_key(), OPTIONS_KEY),
+                dump.and_then(|dump| dump.get(OPTIONS_KEY)),
+            ),
+            (
+                format!(
+                    "{}.{}.{}",
+                    get_belowrc_dump_section_key(),
+                    subcommand,
+                    OPTIONS_KEY
+                ),
+                dump.and_then(|dump| dump.get(subcommand))
+                    .and_then(|section| section.get(OPTIONS_KEY)),
+            ),
+        ];
+        let mut options = toml::value::Table::new();
+        for (name, section) in sections.iter() {
+            let section = match section {
+                Some(section) => section
+                    .as_table()
+                    .ok_or_else(|| Error::msg(format!("belowrc [{}] is not a table", name)))?,
+                None => continue,
+            };
+            for (key, value) in section {
+                if !OPTION_KEYS.contains(&key.as_str()) {
+                    bail!(
+                        "Unsupported option in belowrc [{}]: {}. Supported: {}",
+                        name,
+                        key,
+                        OPTION_KEYS.join(", ")
+                    );
+                }
+                options.insert(key.clone(), value.clone());
+            }
+        }
+        Ok(Self { options })
+    }
+
+    /// Read the defaults of `subcommand` from the belowrc file. There are no
+    /// defaults if the file doesn't exist, but a file that can't be read or
+    /// parsed is an error.
+    pub fn load(filename: &str, subcommand: &str) -> Result<Self> {
+        match std::fs::read_to_string(filename) {
+            Ok(belowrc_str) => Self::from_belowrc(
+                &belowrc_str
+                    .parse::<TValue>()
+                    .with_context(|| format!("Failed to parse belowrc file {}", filename))?,
+                subcommand,
+            ),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Default::default()),
+            Err(e) => {
+                Err(Error::new(e).context(format!("Failed to read belowrc file {}", filename)))
+            }
+        }
+    }
+
+    /// Set `opt` from the default of `key` unless given on the command line.
+    fn fill<T: FromStr>(&self, opt: &mut Option<T>, key: &str) -> Result<()> {
+        if opt.is_some() {
+            return Ok(());
+        }
+        let value = match self.options.get(key) {
+            Some(TValue::String(value)) => value.clone(),
+            Some(TValue::Integer(value)) => value.to_string(),
+            Some(value) => bail!("Invalid value of {} in belowrc: {}", key, value),
+            None => return Ok(()),
+        };
+        *opt =
+            Some(T::from_str(&value).map_err(|_| {
+                Error::msg(format!("Invalid value of {} in belowrc: {}", key, value))
+            })?);
+        Ok(())
+    }
+
+    /// Fill the options of `opts` not given on the command line.
+    pub fn apply(&self, opts: &mut GeneralOpt) -> Result<()> {
+        self.fill(&mut opts.output_format, "output_format")?;
+        self.fill(&mut opts.repeat_title, "repeat_title")?;
+        self.fill(&mut opts.na_string, "na_string")?;
+        self.fill(&mut opts.truncate, "truncate")?;
+        self.fill(&mut opts.truncate_side, "truncate_side")?;
+        self.fill(&mut opts.counters, "counters")?;
+        self.fill(&mut opts.color, "color")?;
+        self.fill(&mut opts.progress, "progress")?;
+        self.fill(&mut opts.order, "order")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// Arithmetic expression of --expr. `T` is the field name when parsed and
+/// the FieldId once resolved against a dump command.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr<T> {
+    Num(f64),
+    Field(T),
+    Neg(Box<Expr<T>>),
+    Binary(Op, Box<Expr<T>>, Box<Expr<T>>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Op(Op),
+    LParen,
+    RParen,
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' => {
+                chars.next();
+            }
+            '+' | '-' | '*' | '/' => {
+                chars.next();
+                tokens.push(Token::Op(match c {
+                    '+' => Op::Add,
+                    '-' => Op::Sub,
+                    '*' => Op::Mul,
+                    _ => Op::Div,
+                }));
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut num = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                    num.push(c);
+                    chars.next();
+                }
+                match num.parse::<f64>() {
+                    Ok(v) => tokens.push(Token::Num(v)),
+                    Err(_) => bail!("Invalid number: {}", num),
+                }
+            }
+            c if is_ident_char(c) => {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek().filter(|c| is_ident_char(**c)) {
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            c => bail!("Unexpected character: {}", c),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser. `*` and `/` bind tighter than `+` and `-`, all
+/// of them are left associative.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_binary(
+        &mut self,
+        ops: &[Op],
+        operand: fn(&mut Self) -> Result<Expr<String>>,
+    ) -> Result<Expr<String>> {
+        let mut lhs = operand(self)?;
+        while let Some(Token::Op(op)) = self.peek() {
+            let op = *op;
+            if !ops.contains(&op) {
+                break;
+            }
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(operand(self)?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr<String>> {
+        self.parse_binary(&[Op::Add, Op::Sub], Self::parse_term)
+    }
+
+    fn parse_term(&mut self) -> Result<Expr<String>> {
+        self.parse_binary(&[Op::Mul, Op::Div], Self::parse_factor)
+    }
+
+    fn parse_factor(&mut self) -> Result<Expr<String>> {
+        match self.next() {
+            Some(Token::Num(v)) => Ok(Expr::Num(v)),
+            Some(Token::Ident(name)) => Ok(Expr::Field(name)),
+            Some(Token::Op(Op::Sub)) => Ok(Expr::Neg(Box::new(self.parse_factor()?))),
+            Some(Token::LParen) => {
+                let expr = self.parse_expr()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => bail!("Missing closing parenthesis"),
+                }
+            }
+            Some(token) => bail!("Unexpected {:?}", token),
+            None => bail!("Unexpected end of expression"),
+        }
+    }
+}
+
+impl FromStr for Expr<String> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+        };
+        let expr = parser.parse_expr()?;
+        if let Some(token) = parser.peek() {
+            bail!("Unexpected {:?}", token);
+        }
+        Ok(expr)
+    }
+}
+
+impl Expr<String> {
+    /// Resolve field names into FieldIds of a dump command.
+    pub fn resolve<F: FromStr>(&self) -> Result<Expr<F>> {
+        Ok(match self {
+            Self::Num(v) => Expr::Num(*v),
+            Self::Field(name) => match F::from_str(name) {
+                Ok(field_id) => Expr::Field(field_id),
+                Err(_) => bail!("Unknown field: {}", name),
+            },
+            Self::Neg(expr) => Expr::Neg(Box::new(expr.resolve()?)),
+            Self::Binary(op, lhs, rhs) => {
+                Expr::Binary(*op, Box::new(lhs.resolve()?), Box::new(rhs.resolve()?))
+            }
+        })
+    }
+}
+
+impl<F: FieldId> Expr<F> {
+    /// Evaluate over the fields of a model. None if any referenced field is
+    /// missing or not numeric. Division by zero gives a non-finite value.
+    pub fn eval(&self, model: &F::Queriable) -> Option<f64> {
+        Some(match self {
+            Self::Num(v) => *v,
+            Self::Field(field_id) => match model.query(field_id)? {
+                value @ (Field::U32(_)
+                | Field::U64(_)
+                | Field::I32(_)
+                | Field::I64(_)
+                | Field::F32(_)
+                | Field::F64(_)) => f64::from(value),
+                _ => return None,
+            },
+            Self::Neg(expr) => -expr.eval(model)?,
+            Self::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(model)?, rhs.eval(model)?);
+                match op {
+                    Op::Add => lhs + rhs,
+                    Op::Sub => lhs - rhs,
+                    Op::Mul => lhs * rhs,
+                    Op::Div => lhs / rhs,
+                }
+            }
+        })
+    }
+}
+
+/// A named --expr column, in the form `<name>=<expression>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprColumn {
+    pub name: String,
+    pub expr: Expr<String>,
+}
+
+impl FromStr for ExprColumn {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once('=') {
+            Some((name, expr)) if !name.trim().is_empty() => Ok(Self {
+                name: name.trim().to_owned(),
+                expr: expr
+                    .parse()
+                    .with_context(|| format!("Invalid expression: {}", expr))?,
+            }),
+            _ => bail!("Expected <name>=<expression>, got: {}", s),
+        }
+    }
+}
+
+/// An --expr column resolved against the fields of a dump command.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprField<F> {
+    pub name: String,
+    pub expr: Expr<F>,
+}
+
+impl<F: FieldId> ExprField<F> {
+    /// Value of the column. None for results of division by zero, like for
+    /// missing fields.
+    pub fn get_field(&self, model: &F::Queriable) -> Option<Field> {
+        self.expr
+            .eval(model)
+            .filter(|v| v.is_finite())
+            .map(Field::F64)
+    }
+}
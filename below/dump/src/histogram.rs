@@ -0,0 +1,218 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+
+use super::*;
+
+use command::HistRange;
+
+pub const DEFAULT_BINS: usize = 10;
+
+/// Width of the longest bar in the printed histogram.
+const BAR_WIDTH: usize = 40;
+
+/// Dumper for --histogram. Rows are picked by the wrapped dumper, which must
+/// dump the single histogram field as raw json. Values are collected across
+/// all time slices and only printed by `finish`.
+pub struct Histogram {
+    dumper: Box<dyn Dumper>,
+    field: String,
+    bins: usize,
+    range: Option<HistRange>,
+    values: RefCell<Vec<f64>>,
+}
+
+impl Histogram {
+    pub fn new(
+        dumper: Box<dyn Dumper>,
+        field: String,
+        bins: usize,
+        range: Option<HistRange>,
+    ) -> Self {
+        Self {
+            dumper,
+            field,
+            bins,
+            range,
+            values: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Collect values from the json dumped for one time slice. Cgroups are
+    /// nested under "children", everything else is the field value.
+    fn collect_values(&self, value: &Value, values: &mut Vec<f64>) -> Result<()> {
+        match value {
+            Value::Array(arr) => {
+                for v in arr {
+                    self.collect_values(v, values)?;
+                }
+            }
+            Value::Object(obj) => {
+                for (key, v) in obj {
+                    if key == "children" {
+                        self.collect_values(v, values)?;
+                        continue;
+                    }
+                    let v = v.as_str().unwrap_or_default();
+                    // Missing values are dumped as "?"
+                    if v == "?" {
+                        continue;
+                    }
+                    match v.parse::<f64>() {
+                        Ok(v) => values.push(v),
+                        Err(_) => bail!(
+                            "--histogram field {} is not numeric, got value: {}",
+                            self.field,
+                            v
+                        ),
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Tally of values into equal width buckets between `lo` and `hi`.
+#[derive(Debug, PartialEq)]
+pub struct Buckets {
+    pub lo: f64,
+    pub hi: f64,
+    pub counts: Vec<usize>,
+    /// Values outside of [lo, hi], only possible with --hist-range.
+    pub below: usize,
+    pub above: usize,
+}
+
+impl Buckets {
+    /// Tally values into `bins` buckets. The range is taken from the observed
+    /// min and max if not given. The last bucket includes its upper bound.
+    pub fn new(values: &[f64], bins: usize, range: Option<HistRange>) -> Self {
+        let (lo, hi) = match range {
+            Some(range) => (range.lo, range.hi),
+            None => values
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+                    (lo.min(*v), hi.max(*v))
+                }),
+        };
+        let mut buckets = Self {
+            lo,
+            hi,
+            counts: vec![0; bins],
+            below: 0,
+            above: 0,
+        };
+        let width = (hi - lo) / bins as f64;
+        for v in values {
+            if *v < lo {
+                buckets.below += 1;
+            } else if *v > hi {
+                buckets.above += 1;
+            } else if width > 0.0 {
+                let idx = ((v - lo) / width) as usize;
+                buckets.counts[idx.min(bins - 1)] += 1;
+            } else {
+                // All values are the same
+                buckets.counts[0] += 1;
+            }
+        }
+        buckets
+    }
+
+    fn print(&self, output: &mut dyn Write) -> Result<()> {
+        let bins = self.counts.len();
+        let width = (self.hi - self.lo) / bins as f64;
+        let max_count = self.counts.iter().copied().max().unwrap_or_default();
+        for (idx, count) in self.counts.iter().enumerate() {
+            let lo = self.lo + width * idx as f64;
+            let hi = if idx + 1 == bins { self.hi } else { lo + width };
+            let bar = match max_count {
+                0 => 0,
+                max_count => count * BAR_WIDTH / max_count,
+            };
+            writeln!(
+                output,
+                "{:>14.2} - {:<14.2} {:>8} {}",
+                lo,
+                hi,
+                count,
+                "#".repeat(bar)
+            )?;
+        }
+        if self.below != 0 {
+            writeln!(
+                output,
+                "{:>14} {:<16} {:>8}",
+                "<",
+                format!("{:.2}", self.lo),
+                self.below
+            )?;
+        }
+        if self.above != 0 {
+            writeln!(
+                output,
+                "{:>14} {:<16} {:>8}",
+                ">",
+                format!("{:.2}", self.hi),
+                self.above
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Dumper for Histogram {
+    fn dump_model(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+        _output: &mut dyn Write,
+        round: &mut usize,
+        _comma_flag: bool,
+    ) -> Result<IterExecResult> {
+        let mut content: Vec<u8> = Vec::new();
+        if self
+            .dumper
+            .dump_model(ctx, model, &mut content, round, false)?
+            == IterExecResult::Success
+        {
+            let value: Value = serde_json::from_slice(&content)?;
+            self.collect_values(&value, &mut self.values.borrow_mut())?;
+        }
+        // Nothing is written until the end
+        Ok(IterExecResult::Skip)
+    }
+
+    fn finish(&self, output: &mut dyn Write) -> Result<()> {
+        let values = self.values.borrow();
+        if values.is_empty() {
+            writeln!(
+                output,
+                "No values of {} found in the time range",
+                self.field
+            )?;
+            return Ok(());
+        }
+        writeln!(
+            output,
+            "Histogram of {} ({} values)",
+            self.field,
+            values.len()
+        )?;
+        Buckets::new(&values, self.bins, self.range).print(output)
+    }
+}
@@ -20,7 +20,9 @@ impl HasRenderConfigForDump for model::SingleNetModel {}
 
 pub struct Iface {
     opts: GeneralOpt,
-    select: Option<SingleNetModelFieldId>,
+    // Field --filter applies to, --select unless --filter-field is given.
+    // Iface rows are not sorted, so --select is not needed otherwise.
+    filter_field: Option<SingleNetModelFieldId>,
     fields: Vec<IfaceField>,
 }
 
@@ -28,11 +30,12 @@ impl Iface {
     pub fn new(
         opts: &GeneralOpt,
         select: Option<SingleNetModelFieldId>,
+        filter_field: Option<SingleNetModelFieldId>,
         fields: Vec<IfaceField>,
     ) -> Self {
         Self {
             opts: opts.to_owned(),
-            select,
+            filter_field: filter_field.or(select),
             fields,
         }
     }
@@ -49,13 +52,17 @@ impl Dumper for Iface {
     ) -> Result<IterExecResult> {
         let json = self.opts.output_format == Some(OutputFormat::Json);
         let mut json_output = json!([]);
+        let remaining_rows = self
+            .opts
+            .limit_rows
+            .map_or(usize::MAX, |limit| limit.saturating_sub(*round));
 
         model
             .network
             .interfaces
             .iter()
             .filter(
-                |(_, model)| match (self.select.as_ref(), self.opts.filter.as_ref()) {
+                |(_, model)| match (self.filter_field.as_ref(), self.opts.filter.as_ref()) {
                     (Some(field_id), Some(filter))
                         if !filter.is_match(
                             &model
@@ -68,6 +75,7 @@ impl Dumper for Iface {
                     _ => true,
                 },
             )
+            .take(remaining_rows)
             .map(|(_, model)| {
                 match self.opts.output_format {
                     Some(OutputFormat::Raw) | None => write!(
@@ -78,9 +86,7 @@ impl Dumper for Iface {
                             ctx,
                             model,
                             *round,
-                            self.opts.repeat_title,
-                            self.opts.disable_title,
-                            self.opts.raw
+                            &print::RawOptions::new(&self.opts),
                         )
                     )?,
                     Some(OutputFormat::Csv) => write!(
@@ -92,13 +98,34 @@ impl Dumper for Iface {
                             model,
                             *round,
                             self.opts.disable_title,
-                            self.opts.raw
+                            self.opts.raw,
+                            self.opts.na_string.as_deref()
                         )
                     )?,
                     Some(OutputFormat::KeyVal) => write!(
                         output,
                         "{}",
-                        print::dump_kv(&self.fields, ctx, model, self.opts.raw)
+                        print::dump_kv(
+                            &self.fields,
+                            ctx,
+                            model,
+                            self.opts.raw,
+                            self.opts.na_string.as_deref()
+                        )
+                    )?,
+                    Some(OutputFormat::Graphite) => write!(
+                        output,
+                        "{}",
+                        print::dump_graphite(
+                            &self.fields,
+                            ctx,
+                            model,
+                            &format!(
+                                "{}.iface.{}",
+                                print::GRAPHITE_ROOT,
+                                print::graphite_component(&model.interface)
+                            )
+                        )
                     )?,
                     Some(OutputFormat::Json) => {
                         let par = print::dump_json(&self.fields, ctx, model, self.opts.raw);
@@ -113,9 +140,23 @@ impl Dumper for Iface {
         match (json, comma_flag) {
             (true, true) => write!(output, ",{}", json_output)?,
             (true, false) => write!(output, "{}", json_output)?,
+            // Graphite lines are not separated by time slice
+            _ if self.opts.output_format == Some(OutputFormat::Graphite) => {}
             _ => write!(output, "\n")?,
         };
 
         Ok(IterExecResult::Success)
     }
+
+    fn field_names(&self) -> Vec<String> {
+        self.fields.iter().map(ToString::to_string).collect()
+    }
+
+    fn quantize_fields(&self) -> Result<Vec<quantize::QuantizeField>> {
+        quantize::quantize_fields(&self.fields, &[model::SingleNetModelFieldId::Interface])
+    }
+
+    fn nonzero_columns(&self) -> Vec<nonzero::Column> {
+        nonzero::columns(&self.fields)
+    }
 }
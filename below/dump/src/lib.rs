@@ -12,14 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::mpsc::Receiver;
-use std::time::SystemTime;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
 
-use anyhow::{bail, Error, Result};
+use anyhow::{bail, Context, Error, Result};
 use serde_json::{json, Value};
 use toml::value::Value as TValue;
 
@@ -27,28 +29,42 @@ use common::cliutil;
 use common::util::{get_belowrc_dump_section_key, get_belowrc_filename, timestamp_to_datetime};
 use model::{EnumIter, Field, FieldId, Queriable};
 
-use store::advance::{new_advance_local, new_advance_remote};
+use store::advance::{new_advance_local, new_advance_merged, new_advance_remote};
 use store::Advance;
 use store::Direction;
 
+pub mod anomalies;
 pub mod cgroup;
 pub mod command;
+pub mod delta;
+pub mod diff;
 pub mod disk;
+pub mod dumprc;
+pub mod expr;
+pub mod histogram;
 pub mod iface;
 pub mod network;
+pub mod nonzero;
 pub mod print;
 pub mod process;
+pub mod quantize;
+pub mod raw_model;
 pub mod system;
 pub mod tmain;
 pub mod transport;
+pub mod transpose;
 
 #[cfg(test)]
 mod test;
 
 pub use command::DumpCommand;
-use command::{expand_fields, GeneralOpt, OutputFormat};
+use command::{
+    expand_fields, AggField, ColorWhen, CounterMode, DumpOptionField, FieldOrder, FieldPosition,
+    GeneralOpt, OutputFormat, ProgressWhen,
+};
 use print::HasRenderConfigForDump;
-use tmain::{dump_timeseries, Dumper, IterExecResult};
+use tmain::{dump_timeseries, DumpTarget, Dumper, IterExecResult};
+pub use tmain::{EmptyResult, EMPTY_RESULT_EXIT_CODE};
 
 /// Fields available to all commands. Each enum represents some semantics and
 /// knows how to extract relevant data from a CommonFieldContext.
@@ -63,30 +79,48 @@ use tmain::{dump_timeseries, Dumper, IterExecResult};
 pub enum CommonField {
     Timestamp,
     Datetime,
+    // Position of the row in the order of --sort or --rsort, missing if the
+    // rows are not sorted
+    Rank,
 }
 
 /// Context for initializing CommonFields.
 pub struct CommonFieldContext {
     pub timestamp: i64,
+    pub rank: Option<usize>,
+}
+
+impl CommonFieldContext {
+    /// The context of the row ranked `rank` in this time slice.
+    pub fn with_rank(&self, rank: Option<usize>) -> Self {
+        Self {
+            timestamp: self.timestamp,
+            rank,
+        }
+    }
 }
 
 impl CommonField {
     pub fn get_field(&self, ctx: &CommonFieldContext) -> Option<Field> {
         match self {
-            Self::Timestamp => Field::from(ctx.timestamp),
-            Self::Datetime => Field::from(timestamp_to_datetime(&ctx.timestamp)),
+            Self::Timestamp => Some(Field::from(ctx.timestamp)),
+            Self::Datetime => Some(Field::from(timestamp_to_datetime(&ctx.timestamp))),
+            Self::Rank => ctx.rank.map(|rank| Field::from(rank as u64)),
         }
-        .into()
     }
 }
 
 /// Generic field for dumping different types of models. It's either a
-/// CommonField or a FieldId that extracts a Field from a given model. It
-/// represents a unified interface for dumpable items.
+/// CommonField, a FieldId that extracts a Field from a given model, or an
+/// --expr column computed from FieldIds. `Renamed` is any of them dumped
+/// under a title given by --rename. It represents a unified interface for
+/// dumpable items.
 #[derive(Clone, Debug, PartialEq)]
 pub enum DumpField<F: FieldId> {
     Common(CommonField),
     FieldId(F),
+    Expr(expr::ExprField<F>),
+    Renamed(Box<DumpField<F>>, String),
 }
 
 pub type CgroupField = DumpField<model::SingleCgroupModelFieldId>;
@@ -98,28 +132,1053 @@ pub type IfaceField = DumpField<model::SingleNetModelFieldId>;
 // Essentially the same as NetworkField
 pub type TransportField = DumpField<model::NetworkModelFieldId>;
 
-fn get_advance(
+/// Used for printing resolved field lists.
+impl<F: FieldId + ToString> std::fmt::Display for DumpField<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Common(common) => f.write_str(&common.to_string()),
+            Self::FieldId(field_id) => f.write_str(&field_id.to_string()),
+            Self::Expr(expr) => f.write_str(&expr.name),
+            Self::Renamed(field, _) => field.fmt(f),
+        }
+    }
+}
+
+/// Resolve the begin and end time of `opts` as given, i.e. before parsing.
+/// --begin and --end fall back to $BELOW_DUMP_BEGIN and $BELOW_DUMP_END,
+/// which --range overrides. `env_var` looks up an environment variable.
+fn get_time_args(
+    opts: &command::GeneralOpt,
+    env_var: impl Fn(&str) -> Option<String>,
+) -> Result<(String, Option<String>)> {
+    match opts.range.as_ref() {
+        Some(_) if opts.begin.is_some() || opts.end.is_some() => {
+            bail!("--range can't be used with --begin or --end")
+        }
+        Some(range) => Ok((range.begin.clone(), range.end.clone())),
+        None => {
+            let begin = match opts.begin.clone().or_else(|| env_var("BELOW_DUMP_BEGIN")) {
+                Some(begin) => begin,
+                None => bail!("--begin or --range is required"),
+            };
+            let end = opts.end.clone().or_else(|| env_var("BELOW_DUMP_END"));
+            Ok((begin, end))
+        }
+    }
+}
+
+fn get_time_range(opts: &command::GeneralOpt) -> Result<(SystemTime, SystemTime)> {
+    let (begin, end) = get_time_args(opts, |var| std::env::var(var).ok())?;
+    let (time_begin, time_end) = cliutil::system_time_range_from_date_and_adjuster(
+        &begin,
+        end.as_deref(),
+        opts.yesterdays.as_deref(),
+    )?;
+    match opts.align {
+        Some(interval) => cliutil::align_time_range(time_begin, time_end, interval.into()),
+        None => Ok((time_begin, time_end)),
+    }
+}
+
+/// Where a dump reads samples from, i.e. the local store, --store-dir or
+/// --host, and the receiver of external errors like a stop signal.
+struct DumpSource {
     logger: slog::Logger,
     dir: PathBuf,
     host: Option<String>,
     port: Option<u16>,
+    errs: Receiver<Error>,
+}
+
+fn get_advance(
+    src: &DumpSource,
     opts: &command::GeneralOpt,
 ) -> Result<(SystemTime, SystemTime, Advance)> {
-    let (time_begin, time_end) = cliutil::system_time_range_from_date_and_adjuster(
-        opts.begin.as_str(),
-        opts.end.as_deref(),
-        opts.yesterdays.as_deref(),
-    )?;
+    let (time_begin, time_end) = get_time_range(opts)?;
+    let advance = new_advance(src, opts, time_begin)?;
+    Ok((time_begin, time_end, advance))
+}
 
-    let mut advance = if let Some(host) = host {
-        new_advance_remote(logger.clone(), host, port, time_begin)?
-    } else {
-        new_advance_local(logger.clone(), dir, time_begin)
+/// Create an Advance starting at `time_begin` from the store of --host,
+/// --store-dir or the local store.
+fn new_advance(
+    src: &DumpSource,
+    opts: &command::GeneralOpt,
+    time_begin: SystemTime,
+) -> Result<Advance> {
+    let logger = src.logger.clone();
+    let mut advance = match (src.host.clone(), &opts.store_dir[..]) {
+        (Some(_), [_, ..]) => bail!("--store-dir can't be used with --host"),
+        (Some(host), []) => new_advance_remote(logger, host, src.port, time_begin)?,
+        (None, []) => new_advance_local(logger, src.dir.clone(), time_begin),
+        (None, [store_dir]) => new_advance_local(logger, store_dir.clone(), time_begin),
+        (None, store_dirs) => new_advance_merged(logger, store_dirs.to_vec(), time_begin),
     };
 
     advance.initialize();
 
-    Ok((time_begin, time_end, advance))
+    Ok(advance)
+}
+
+/// Where a dump target writes to.
+#[derive(Debug, PartialEq)]
+enum OutputPath<'a> {
+    Stdout,
+    File(&'a str),
+    /// One file per time slice in this directory, see --split-by-slice.
+    SliceDir(&'a str),
+    /// Unix domain socket connected to, given as `unix:<path>`.
+    Socket(&'a str),
+    /// Named pipe, given as `fifo:<path>`.
+    Fifo(&'a str),
+    /// Formatted but thrown away, given as /dev/null or with --discard.
+    Discard,
+}
+
+impl<'a> OutputPath<'a> {
+    /// Parse the value of --output or --output-<format>. `-` is stdout.
+    fn parse(path: &'a str) -> Self {
+        if path == "-" {
+            Self::Stdout
+        } else if path == "/dev/null" {
+            Self::Discard
+        } else if let Some(socket) = path.strip_prefix("unix:") {
+            Self::Socket(socket)
+        } else if let Some(fifo) = path.strip_prefix("fifo:") {
+            Self::Fifo(fifo)
+        } else {
+            Self::File(path)
+        }
+    }
+
+    /// Open the output for writing. Missing parent directories of a file
+    /// are only created with `mkdir`, see --mkdir. Sockets and named pipes
+    /// are buffered until the end of each time slice, and writes to them
+    /// block while the reader is behind.
+    fn open(&self, mkdir: bool) -> Result<Box<dyn Write>> {
+        Ok(match self {
+            Self::Stdout => Box::new(io::stdout()),
+            Self::File(path) => Box::new(create_output_file(&expand_home(path), mkdir)?),
+            Self::SliceDir(dir) => bail!("{} is a directory for --split-by-slice", dir),
+            Self::Discard => Box::new(tmain::DiscardOutput::default()),
+            Self::Socket(path) => Box::new(io::BufWriter::new(
+                UnixStream::connect(expand_home(path))
+                    .with_context(|| format!("Failed to connect to unix socket {}", path))?,
+            )),
+            Self::Fifo(path) => {
+                let fifo = expand_home(path);
+                let metadata = std::fs::metadata(&fifo)
+                    .with_context(|| format!("Failed to open named pipe {}", path))?;
+                if !metadata.file_type().is_fifo() {
+                    bail!("{} is not a named pipe", path);
+                }
+                // Blocks until the pipe is opened for reading
+                Box::new(io::BufWriter::new(
+                    OpenOptions::new()
+                        .write(true)
+                        .open(&fifo)
+                        .with_context(|| format!("Failed to open named pipe {}", path))?,
+                ))
+            }
+        })
+    }
+}
+
+/// Expand a leading `~` of an output path into the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(home).join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Create the output file at `path`. An existing directory is an error
+/// rather than a confusing "Is a directory" os error, and missing parent
+/// directories are only created with `mkdir`.
+fn create_output_file(path: &Path, mkdir: bool) -> Result<File> {
+    if path.is_dir() {
+        bail!(
+            "Output {} is a directory, use --split-by-slice to write one file per time slice to it",
+            path.display()
+        );
+    }
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() && !parent.is_dir() => {
+            if !mkdir {
+                bail!(
+                    "Directory {} of output {} doesn't exist, use --mkdir to create it",
+                    parent.display(),
+                    path.display()
+                );
+            }
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        _ => {}
+    }
+    File::create(path).with_context(|| format!("Failed to create output file {}", path.display()))
+}
+
+impl std::fmt::Display for OutputPath<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Stdout => write!(f, "stdout"),
+            Self::File(path) => write!(f, "{}", path),
+            Self::SliceDir(dir) => write!(f, "{} (one file per time slice)", dir),
+            Self::Socket(path) => write!(f, "{} (unix socket)", path),
+            Self::Fifo(path) => write!(f, "{} (named pipe)", path),
+            Self::Discard => write!(f, "nowhere (discarded)"),
+        }
+    }
+}
+
+/// Output targets selected by --output/--split-by-slice/--output-format and
+/// --output-<format>, in that order. The stdout target is dropped if only
+/// --output-<format> options are given.
+fn get_output_targets(opts: &command::GeneralOpt) -> Vec<(OutputPath<'_>, Option<OutputFormat>)> {
+    let mut targets = vec![];
+    let extra_targets = [
+        (opts.output_raw.as_deref(), OutputFormat::Raw),
+        (opts.output_csv.as_deref(), OutputFormat::Csv),
+        (opts.output_json.as_deref(), OutputFormat::Json),
+        (opts.output_kv.as_deref(), OutputFormat::KeyVal),
+    ];
+    let has_extra_targets = extra_targets.iter().any(|(path, _)| path.is_some());
+    let path = match (opts.output.as_deref(), opts.split_by_slice.as_deref()) {
+        _ if opts.discard => OutputPath::Discard,
+        (_, Some(dir)) => OutputPath::SliceDir(dir),
+        (Some(file_path), None) => OutputPath::parse(file_path),
+        (None, None) => OutputPath::Stdout,
+    };
+    if !has_extra_targets || path != OutputPath::Stdout || opts.output_format.is_some() {
+        targets.push((path, opts.output_format));
+    }
+    for (path, format) in extra_targets.iter() {
+        if let Some(path) = path {
+            targets.push((OutputPath::parse(path), Some(*format)));
+        }
+    }
+    targets
+}
+
+/// Resolve --color auto into always or never. Colors are only written to
+/// stdout when it's a terminal.
+fn resolve_color(color: Option<ColorWhen>, to_stdout: bool) -> ColorWhen {
+    match color.unwrap_or(ColorWhen::Auto) {
+        ColorWhen::Auto if to_stdout && atty::is(atty::Stream::Stdout) => ColorWhen::Always,
+        ColorWhen::Auto => ColorWhen::Never,
+        color => color,
+    }
+}
+
+/// Resolve --progress into whether to show progress. Progress is only shown
+/// when requested, and --progress=auto only when stderr is a terminal.
+fn resolve_progress(progress: Option<ProgressWhen>) -> bool {
+    match progress {
+        Some(ProgressWhen::Always) => true,
+        Some(ProgressWhen::Auto) => atty::is(atty::Stream::Stderr),
+        Some(ProgressWhen::Never) | None => false,
+    }
+}
+
+/// Resolve --fields-preset into the fields of the preset, followed by the
+/// given fields. Fields in both are only dumped once.
+fn apply_fields_preset<T>(
+    fields: Option<Vec<T>>,
+    opts: &command::GeneralOpt,
+    presets: &[command::FieldsPreset],
+) -> Result<Option<Vec<T>>>
+where
+    T: FromStr<Err = Error> + PartialEq,
+{
+    let name = match opts.fields_preset.as_ref() {
+        Some(name) => name,
+        None => return Ok(fields),
+    };
+    let preset = match presets.iter().find(|(preset, _)| preset == name) {
+        Some((_, preset)) => preset,
+        None if presets.is_empty() => bail!("This command has no --fields-preset"),
+        None => bail!(
+            "Unknown --fields-preset {}, choose from {}",
+            name,
+            presets
+                .iter()
+                .map(|(preset, _)| *preset)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let mut res = preset
+        .iter()
+        .map(|field| T::from_str(field))
+        .collect::<Result<Vec<_>>>()?;
+    for field in fields.into_iter().flatten() {
+        if !res.contains(&field) {
+            res.push(field);
+        }
+    }
+    Ok(Some(res))
+}
+
+/// Append the --expr columns to the resolved fields.
+fn add_expr_fields<F>(
+    mut fields: Vec<DumpField<F>>,
+    opts: &command::GeneralOpt,
+) -> Result<Vec<DumpField<F>>>
+where
+    F: FieldId + FromStr,
+{
+    for column in &opts.expr {
+        fields.push(DumpField::Expr(expr::ExprField {
+            name: column.name.clone(),
+            expr: column
+                .expr
+                .resolve()
+                .with_context(|| format!("--expr {}", column.name))?,
+        }));
+    }
+    Ok(fields)
+}
+
+/// Reorder the resolved fields by --order. Common fields and fields outside
+/// of every aggregated field are identity fields like name or pid, which keep
+/// their order before all others. With grouped, --expr columns come after
+/// the aggregated fields.
+fn order_fields<F, A>(fields: Vec<DumpField<F>>, opts: &command::GeneralOpt) -> Vec<DumpField<F>>
+where
+    F: FieldId + PartialEq + ToString,
+    A: AggField<F> + EnumIter,
+{
+    let order = opts.order.unwrap_or(FieldOrder::Input);
+    if order == FieldOrder::Input {
+        return fields;
+    }
+    let groups = A::unit_variant_iter()
+        .map(|agg| agg.expand(true))
+        .collect::<Vec<_>>();
+    let group_of = |field: &DumpField<F>| match field {
+        DumpField::FieldId(field_id) => groups.iter().position(|group| group.contains(field_id)),
+        _ => None,
+    };
+    let (mut pinned, mut rest): (Vec<_>, Vec<_>) = fields
+        .into_iter()
+        .partition(|field| group_of(field).is_none() && !matches!(field, DumpField::Expr(_)));
+    match order {
+        FieldOrder::Alpha => rest.sort_by_key(|field| field.to_string()),
+        FieldOrder::Grouped => rest.sort_by_key(|field| group_of(field).unwrap_or(groups.len())),
+        FieldOrder::Input => {}
+    }
+    pinned.append(&mut rest);
+    pinned
+}
+
+/// Reorder the resolved fields by --move-field. Aggregated fields move all the
+/// fields they expand to, which must all be in `fields`. --expr columns are
+/// moved by their name.
+fn move_fields<F, A>(
+    mut fields: Vec<DumpField<F>>,
+    opts: &command::GeneralOpt,
+    detail: bool,
+) -> Result<Vec<DumpField<F>>>
+where
+    F: FieldId + Clone + PartialEq + FromStr + EnumIter + ToString,
+    A: AggField<F> + FromStr + EnumIter,
+{
+    let mut first = Vec::new();
+    let mut last = Vec::new();
+    for (name, position) in opts.field_moves()? {
+        let moved = match fields
+            .iter()
+            .find(|field| matches!(field, DumpField::Expr(expr) if expr.name == name))
+        {
+            Some(field) => vec![field.clone()],
+            None => expand_fields(&[DumpOptionField::<F, A>::from_str(name)?], detail),
+        };
+        if moved.is_empty() {
+            bail!("--move-field {}: not a field that can be moved", name);
+        }
+        for field in moved {
+            match fields.iter().position(|f| *f == field) {
+                Some(idx) => match position {
+                    FieldPosition::First => first.push(fields.remove(idx)),
+                    FieldPosition::Last => last.push(fields.remove(idx)),
+                },
+                // Also covers fields moved twice
+                None => bail!("--move-field {}: not in the selected fields", name),
+            }
+        }
+    }
+    first.append(&mut fields);
+    first.append(&mut last);
+    Ok(first)
+}
+
+/// Insert the rank field for --with-rank after the leading timestamp and
+/// datetime fields, unless it's already dumped.
+fn add_rank_field<F: FieldId + PartialEq>(
+    mut fields: Vec<DumpField<F>>,
+    opts: &command::GeneralOpt,
+) -> Result<Vec<DumpField<F>>> {
+    if !opts.with_rank {
+        return Ok(fields);
+    }
+    if !opts.sort && !opts.rsort {
+        bail!("--with-rank requires --sort or --rsort");
+    }
+    let rank = DumpField::Common(CommonField::Rank);
+    if !fields.contains(&rank) {
+        let idx = fields
+            .iter()
+            .position(|field| {
+                !matches!(
+                    field,
+                    DumpField::Common(CommonField::Timestamp | CommonField::Datetime)
+                )
+            })
+            .unwrap_or(fields.len());
+        fields.insert(idx, rank);
+    }
+    Ok(fields)
+}
+
+/// Append the --anomalies field to the resolved fields, unless it's already
+/// dumped.
+fn add_anomaly_field<F>(
+    mut fields: Vec<DumpField<F>>,
+    opts: &command::GeneralOpt,
+) -> Result<Vec<DumpField<F>>>
+where
+    F: FieldId + FromStr + PartialEq,
+{
+    let name = match opts.anomalies.as_deref() {
+        Some(name) => name,
+        None => return Ok(fields),
+    };
+    let field = DumpField::FieldId(
+        F::from_str(name).map_err(|_| Error::msg(format!("--anomalies {}: not a field", name)))?,
+    );
+    if !fields.contains(&field) {
+        fields.push(field);
+    }
+    Ok(fields)
+}
+
+/// Insert the tid field after the pid field for --threads, unless it's
+/// already dumped. Without a pid field the fields are left as is.
+fn add_tid_field(mut fields: Vec<ProcessField>) -> Vec<ProcessField> {
+    let tid = DumpField::FieldId(model::SingleProcessModelFieldId::Tid);
+    let pid = DumpField::FieldId(model::SingleProcessModelFieldId::Pid);
+    if !fields.contains(&tid) {
+        if let Some(idx) = fields.iter().position(|field| *field == pid) {
+            fields.insert(idx + 1, tid);
+        }
+    }
+    fields
+}
+
+/// Insert the device field after the name or full path field for
+/// --per-device, unless it's already dumped. Without either it goes first.
+fn add_device_field(mut fields: Vec<CgroupField>) -> Vec<CgroupField> {
+    let device = DumpField::FieldId(model::SingleCgroupModelFieldId::Device);
+    if !fields.contains(&device) {
+        let idx = fields
+            .iter()
+            .position(|field| {
+                *field == DumpField::FieldId(model::SingleCgroupModelFieldId::Name)
+                    || *field == DumpField::FieldId(model::SingleCgroupModelFieldId::FullPath)
+            })
+            .map_or(0, |idx| idx + 1);
+        fields.insert(idx, device);
+    }
+    fields
+}
+
+/// Rate field of a counter, i.e. `<counter>_per_sec`, or `<counter>_pct` for
+/// microsecond counters `<counter>_usec`. None if the field isn't a counter.
+fn counter_rate<F: FromStr + ToString>(field_id: &F) -> Option<F> {
+    let name = field_id.to_string();
+    let rate = match name.strip_suffix("_usec") {
+        Some(counter) => format!("{}_pct", counter),
+        None => format!("{}_per_sec", name),
+    };
+    F::from_str(&rate).ok()
+}
+
+/// Apply --counters rate by replacing each counter with its `<counter>_per_sec`
+/// rate field, if the model has one. Microsecond counters `<counter>_usec`
+/// are replaced with their `<counter>_pct` field instead. Rates that are also
+/// selected directly are only dumped once.
+fn apply_counters<F>(fields: Vec<DumpField<F>>, opts: &command::GeneralOpt) -> Vec<DumpField<F>>
+where
+    F: FieldId + PartialEq + FromStr + ToString,
+{
+    if opts.counters != Some(CounterMode::Rate) {
+        return fields;
+    }
+    let mut res = Vec::new();
+    for field in fields {
+        let field = match field {
+            DumpField::FieldId(field_id) => match counter_rate(&field_id) {
+                Some(rate) => DumpField::FieldId(rate),
+                None => DumpField::FieldId(field_id),
+            },
+            field => field,
+        };
+        if !res.contains(&field) {
+            res.push(field);
+        }
+    }
+    res
+}
+
+/// Apply --rename to the resolved fields. Only the title or key of a field
+/// changes, it's selected and dumped as before. New titles must not collide
+/// with the titles of other fields.
+fn rename_fields<F>(
+    mut fields: Vec<DumpField<F>>,
+    opts: &command::GeneralOpt,
+) -> Result<Vec<DumpField<F>>>
+where
+    F: FieldId + ToString,
+    F::Queriable: HasRenderConfigForDump,
+{
+    for rename in &opts.rename {
+        let idx = match fields
+            .iter()
+            .position(|field| field.to_string() == rename.field)
+        {
+            Some(idx) => idx,
+            None => bail!("--rename {}: not in the selected fields", rename.field),
+        };
+        if matches!(fields[idx], DumpField::Renamed(..)) {
+            bail!("--rename {}: renamed more than once", rename.field);
+        }
+        let field = fields.remove(idx);
+        fields.insert(
+            idx,
+            DumpField::Renamed(Box::new(field), rename.name.clone()),
+        );
+    }
+    let titles = fields
+        .iter()
+        .map(|field| field.get_render_config().render_title(false))
+        .collect::<Vec<_>>();
+    for (idx, field) in fields.iter().enumerate() {
+        if let DumpField::Renamed(_, name) = field {
+            if titles
+                .iter()
+                .enumerate()
+                .any(|(other, title)| other != idx && title == name)
+            {
+                bail!(
+                    "--rename {}: {} collides with another field",
+                    field.to_string(),
+                    name
+                );
+            }
+        }
+    }
+    Ok(fields)
+}
+
+/// Parse the --histogram field. Same as --select, aggregated fields are
+/// mapped to their representative field.
+fn get_histogram_field<F, A>(field: &str) -> Result<DumpField<F>>
+where
+    F: FieldId + FromStr,
+    A: AggField<F> + FromStr,
+{
+    Ok(DumpField::FieldId(command::parse_select::<F, A>(field)?))
+}
+
+/// Path of the single output written by --histogram, --transpose and --diff.
+fn get_single_output_path(opts: &command::GeneralOpt) -> OutputPath<'_> {
+    match opts.output.as_deref() {
+        _ if opts.discard => OutputPath::Discard,
+        Some(file_path) => OutputPath::parse(file_path),
+        None => OutputPath::Stdout,
+    }
+}
+
+/// Output of the single target written by --histogram, --transpose and --diff.
+fn get_single_output(opts: &command::GeneralOpt) -> Result<Box<dyn Write>> {
+    get_single_output_path(opts).open(opts.mkdir)
+}
+
+/// The single target of --histogram, --transpose and --anomalies, which is
+/// written in the format of its dumper.
+fn get_single_target(dumper: Box<dyn Dumper>, opts: &command::GeneralOpt) -> Result<DumpTarget> {
+    Ok(match get_single_output_path(opts) {
+        OutputPath::Discard => DumpTarget::new_discard(dumper, None),
+        path => DumpTarget::new(dumper, path.open(opts.mkdir)?, None),
+    })
+}
+
+/// Create one Dumper per output target, each writing in its own format.
+/// --histogram and --transpose write a single target, which is only printed
+/// at the end.
+fn get_dump_targets<D: Dumper + 'static>(
+    opts: &command::GeneralOpt,
+    new_dumper: impl Fn(&command::GeneralOpt) -> D,
+) -> Result<Vec<DumpTarget>> {
+    if let Some(field) = opts.histogram.as_ref() {
+        // Values are read back from the raw json output of the dumper
+        let mut hist_opts = opts.clone();
+        hist_opts.output_format = Some(OutputFormat::Json);
+        hist_opts.raw = true;
+        let bins = opts.bins.unwrap_or(histogram::DEFAULT_BINS);
+        if bins == 0 {
+            bail!("--bins must be at least 1");
+        }
+        let dumper = histogram::Histogram::new(
+            Box::new(new_dumper(&hist_opts)),
+            field.clone(),
+            bins,
+            opts.hist_range,
+        );
+        return Ok(vec![get_single_target(Box::new(dumper), opts)?]);
+    }
+    if let Some(field) = opts.anomalies.as_ref() {
+        // Rows are read back from the raw json output of the dumper
+        let mut inner_opts = opts.clone();
+        inner_opts.output_format = Some(OutputFormat::Json);
+        inner_opts.raw = true;
+        let dumper = anomalies::Anomalies::new(
+            Box::new(new_dumper(&inner_opts)),
+            field,
+            opts.anomaly_threshold,
+        )?;
+        return Ok(vec![get_single_target(Box::new(dumper), opts)?]);
+    }
+    if opts.transpose {
+        if !matches!(opts.output_format, Some(OutputFormat::Raw) | None) {
+            bail!("--transpose only works with raw output");
+        }
+        // Rows are read back from the kv output of the dumper
+        let mut kv_opts = opts.clone();
+        kv_opts.output_format = Some(OutputFormat::KeyVal);
+        let dumper = transpose::Transpose::new(Box::new(new_dumper(&kv_opts)));
+        return Ok(vec![get_single_target(Box::new(dumper), opts)?]);
+    }
+    get_output_targets(opts)
+        .into_iter()
+        .map(|(path, output_format)| {
+            let mut target_opts = opts.clone();
+            target_opts.output_format = output_format;
+            target_opts.color = Some(resolve_color(opts.color, path == OutputPath::Stdout));
+            let dumper: Box<dyn Dumper> = match opts.quantize {
+                Some(interval) => {
+                    // Rows are read back from the raw json output of the dumper
+                    let mut inner_opts = target_opts.clone();
+                    inner_opts.output_format = Some(OutputFormat::Json);
+                    inner_opts.raw = true;
+                    inner_opts.limit_rows = None;
+                    Box::new(quantize::Quantize::new(
+                        Box::new(new_dumper(&inner_opts)),
+                        interval.into(),
+                        opts.quantize_align,
+                        output_format,
+                        opts.disable_title,
+                    )?)
+                }
+                None if opts.drop_zero_columns => {
+                    // Zeros are found in raw json, other values are kept
+                    // formatted as the target asks for
+                    let mut detect_opts = target_opts.clone();
+                    detect_opts.output_format = Some(OutputFormat::Json);
+                    detect_opts.raw = true;
+                    let mut inner_opts = target_opts.clone();
+                    inner_opts.output_format = Some(OutputFormat::Json);
+                    Box::new(nonzero::NonZero::new(
+                        Box::new(new_dumper(&detect_opts)),
+                        Box::new(new_dumper(&inner_opts)),
+                        output_format,
+                        opts.disable_title,
+                        opts.na_string.clone(),
+                    )?)
+                }
+                None => Box::new(new_dumper(&target_opts)),
+            };
+            let target = match path {
+                OutputPath::SliceDir(dir) => {
+                    let dir_path = expand_home(dir);
+                    std::fs::create_dir_all(&dir_path)
+                        .with_context(|| format!("Failed to create directory {}", dir))?;
+                    DumpTarget::new_split_by_slice(dumper, dir_path, output_format)
+                }
+                OutputPath::Discard => DumpTarget::new_discard(dumper, output_format),
+                path => DumpTarget::new(dumper, path.open(opts.mkdir)?, output_format),
+            };
+            if opts.with_meta {
+                target.with_meta()
+            } else {
+                Ok(target)
+            }
+        })
+        .collect()
+}
+
+/// Dump the time range of `opts` to its targets. With --watch, the time range
+/// is evaluated again and the dump redrawn every interval until stopped.
+fn run_dump<D: Dumper + 'static>(
+    src: &DumpSource,
+    opts: &command::GeneralOpt,
+    new_dumper: impl Fn(&command::GeneralOpt) -> D,
+) -> Result<()> {
+    if !opts.diff.is_empty() {
+        return run_diff(src, opts, new_dumper);
+    }
+    let dump = || {
+        let (time_begin, time_end, advance) = get_advance(src, opts)?;
+        let targets = get_dump_targets(opts, &new_dumper)?;
+        dump_timeseries(
+            advance,
+            time_begin,
+            time_end,
+            targets,
+            &tmain::TimeseriesOpts {
+                br: opts.br.clone(),
+                limit_rows: opts.limit_rows,
+                every_nth: opts.every_nth,
+                fail_on_empty: opts.fail_on_empty,
+                json_errors: opts.json_errors,
+                progress: resolve_progress(opts.progress),
+            },
+            &src.errs,
+        )
+    };
+    if opts.every_nth == Some(0) {
+        bail!("--every-nth must be at least 1");
+    }
+    let interval = match opts.watch {
+        Some(0) => bail!("--watch interval must be at least 1 second"),
+        Some(secs) => Duration::from_secs(secs),
+        None => return dump(),
+    };
+    let to_raw_stdout = matches!(
+        &get_output_targets(opts)[..],
+        [(OutputPath::Stdout, None | Some(OutputFormat::Raw))]
+    );
+    if !to_raw_stdout || opts.histogram.is_some() || opts.transpose {
+        bail!("--watch only works with raw output to stdout");
+    }
+    if !atty::is(atty::Stream::Stdout) {
+        bail!("--watch only works when stdout is a terminal");
+    }
+    loop {
+        // Clear the screen and move the cursor to the top left
+        print!("\x1b[2J\x1b[H");
+        match dump() {
+            // Keep watching until data shows up
+            Err(e) if e.is::<EmptyResult>() => {}
+            res => res?,
+        }
+        match src.errs.recv_timeout(interval) {
+            // Stopped, e.g. by Ctrl-C
+            Ok(e) => return Err(e),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+/// Dump the difference between the time slices at the two --diff times
+/// instead of a time series.
+fn run_diff<D: Dumper + 'static>(
+    src: &DumpSource,
+    opts: &command::GeneralOpt,
+    new_dumper: impl Fn(&command::GeneralOpt) -> D,
+) -> Result<()> {
+    let (time_before, time_after) = match &opts.diff[..] {
+        [before, after] => cliutil::system_time_range_from_date_and_adjuster(
+            before,
+            Some(after),
+            opts.yesterdays.as_deref(),
+        )?,
+        _ => bail!("--diff takes two times"),
+    };
+    // Rows are read back from the raw json output of the dumper
+    let mut inner_opts = opts.clone();
+    inner_opts.output_format = Some(OutputFormat::Json);
+    inner_opts.raw = true;
+    inner_opts.limit_rows = None;
+    let dumper = diff::Diff::new(
+        Box::new(new_dumper(&inner_opts)),
+        opts.output_format,
+        opts.disable_title,
+        opts.na_string.clone(),
+    )?;
+
+    let mut advance = new_advance(src, opts, time_before)?;
+    let mut get_slice = |time: SystemTime| match advance.jump_sample_to(time) {
+        Some(model) => {
+            cliutil::check_initial_sample_time_with_requested_time(model.timestamp, time);
+            let ctx = CommonFieldContext {
+                timestamp: common::util::get_unix_timestamp(model.timestamp) as i64,
+                rank: None,
+            };
+            Ok((ctx, model))
+        }
+        None => Err(Error::msg(format!(
+            "No sample could be found at {}",
+            timestamp_to_datetime(&(common::util::get_unix_timestamp(time) as i64))
+        ))),
+    };
+    let before = get_slice(time_before)?;
+    let after = get_slice(time_after)?;
+
+    let mut output = get_single_output(opts)?;
+    let rows = dumper.dump(
+        (&before.0, &before.1),
+        (&after.0, &after.1),
+        output.as_mut(),
+    )?;
+    output.flush()?;
+    if rows == 0 {
+        if opts.fail_on_empty {
+            bail!("{} (--fail-on-empty)", EmptyResult);
+        }
+        return Err(EmptyResult.into());
+    }
+    Ok(())
+}
+
+/// Resolved fields with where each comes from, see `field_sources`.
+type FieldSources<F> = Vec<(DumpField<F>, String)>;
+
+/// Where each field resolved from --fields, a belowrc pattern, a preset or
+/// the default fields comes from, for --explain-fields. `fields` are the
+/// fields given by --fields or the pattern, before the preset is applied. A
+/// field given more than once is explained by the first source, the same way
+/// it's only dumped once.
+fn field_sources<F, A>(
+    fields: Option<&[DumpOptionField<F, A>]>,
+    pattern: Option<&str>,
+    opts: &command::GeneralOpt,
+    presets: &[command::FieldsPreset],
+    default_fields: &[DumpOptionField<F, A>],
+) -> Result<FieldSources<F>>
+where
+    F: FieldId + Clone + PartialEq + FromStr + EnumIter + ToString,
+    A: AggField<F> + Clone + FromStr + EnumIter + ToString,
+{
+    let everything = opts.everything || command::has_all_field(fields);
+    let detail = everything || opts.detail;
+    let preset = opts
+        .fields_preset
+        .as_ref()
+        .and_then(|name| presets.iter().find(|(preset, _)| preset == name));
+    let mut groups = Vec::new();
+    if everything || opts.default || (fields.is_none() && preset.is_none()) {
+        let source = if opts.everything {
+            "--everything"
+        } else if everything {
+            "--fields all"
+        } else if opts.default {
+            "--default"
+        } else {
+            "default fields"
+        };
+        groups.push((default_fields.to_vec(), source.to_owned()));
+    } else {
+        if let Some((name, preset)) = preset {
+            groups.push((
+                preset
+                    .iter()
+                    .map(|field| DumpOptionField::from_str(field))
+                    .collect::<Result<Vec<_>>>()?,
+                format!("preset {}", name),
+            ));
+        }
+        if let Some(fields) = fields {
+            let source = match pattern {
+                Some(pattern) => format!("pattern {}", pattern),
+                None => "--fields".to_owned(),
+            };
+            groups.push((fields.to_vec(), source));
+        }
+    }
+
+    let mut sources: FieldSources<F> = Vec::new();
+    for (fields, source) in groups {
+        for field in fields {
+            let resolved = match &field {
+                DumpOptionField::Agg(agg) => {
+                    let brief = agg.expand(false);
+                    agg.expand(detail)
+                        .into_iter()
+                        .map(|field_id| {
+                            let source = if brief.contains(&field_id) {
+                                format!("{}, group {}", source, agg.to_string())
+                            } else {
+                                format!("{}, group {} with detail", source, agg.to_string())
+                            };
+                            (DumpField::FieldId(field_id), source)
+                        })
+                        .collect()
+                }
+                DumpOptionField::Glob(pattern, fields) => fields
+                    .iter()
+                    .map(|field| (field.clone(), format!("{}, glob {}", source, pattern)))
+                    .collect(),
+                DumpOptionField::Unit(field) => vec![(field.clone(), source.clone())],
+                DumpOptionField::All => Vec::new(),
+            };
+            for (field, source) in resolved {
+                if !sources.iter().any(|(known, _)| *known == field) {
+                    sources.push((field, source));
+                }
+            }
+        }
+    }
+    Ok(sources)
+}
+
+/// Why `field` is dumped. Fields not in `sources` were added or changed by
+/// options applied after the fields are resolved.
+fn field_source<F>(
+    field: &DumpField<F>,
+    sources: &[(DumpField<F>, String)],
+    opts: &command::GeneralOpt,
+) -> String
+where
+    F: FieldId + PartialEq + FromStr + ToString,
+{
+    if opts.histogram.is_some() {
+        return "--histogram".to_owned();
+    }
+    if let Some((_, source)) = sources.iter().find(|(known, _)| known == field) {
+        return source.clone();
+    }
+    match field {
+        DumpField::Renamed(field, name) => format!(
+            "{}, renamed to {} by --rename",
+            field_source(field, sources, opts),
+            name
+        ),
+        DumpField::Expr(_) => "--expr".to_owned(),
+        DumpField::Common(CommonField::Rank) if opts.with_rank => "--with-rank".to_owned(),
+        _ if opts.anomalies.as_deref() == Some(field.to_string().as_str()) => {
+            "--anomalies".to_owned()
+        }
+        // A counter replaced by its rate
+        _ => sources
+            .iter()
+            .find_map(|(known, source)| match known {
+                DumpField::FieldId(counter)
+                    if counter_rate(counter).map(DumpField::FieldId).as_ref() == Some(field) =>
+                {
+                    Some(format!(
+                        "{}, rate of {} by --counters",
+                        source,
+                        counter.to_string()
+                    ))
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| "?".to_owned()),
+    }
+}
+
+/// Print each resolved field with where it comes from, e.g. "cpu.usage_pct:
+/// --fields, group cpu", then exit without reading any data. Used by
+/// --explain-fields.
+fn print_field_sources<F>(
+    output: &mut dyn Write,
+    opts: &command::GeneralOpt,
+    fields: &[DumpField<F>],
+    sources: &[(DumpField<F>, String)],
+) -> Result<()>
+where
+    F: FieldId + PartialEq + FromStr + ToString,
+{
+    for field in fields {
+        writeln!(output, "{}: {}", field, field_source(field, sources, opts))?;
+    }
+    Ok(())
+}
+
+/// Print what a dump command would do, i.e. the resolved fields, time range
+/// and row operations, without reading any data. Used by --dry-run.
+fn print_dry_run<F: FieldId + ToString>(
+    output: &mut dyn Write,
+    opts: &command::GeneralOpt,
+    select: Option<String>,
+    fields: &[DumpField<F>],
+    time_begin: SystemTime,
+    time_end: SystemTime,
+) -> Result<()> {
+    let format_time = |time: SystemTime| {
+        let timestamp = common::util::get_unix_timestamp(time) as i64;
+        format!("{} ({})", timestamp_to_datetime(&timestamp), timestamp)
+    };
+    let sort = if opts.rsort {
+        "descending"
+    } else if opts.sort {
+        "ascending"
+    } else {
+        "none"
+    };
+    let sort = match opts.sort_by {
+        Some(sort_by) if sort != "none" && sort_by != command::SortBy::Value => {
+            format!("{} by {}", sort, sort_by)
+        }
+        _ => sort.to_owned(),
+    };
+
+    writeln!(
+        output,
+        "fields: {}",
+        fields
+            .iter()
+            .map(|field| field.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )?;
+    writeln!(output, "begin: {}", format_time(time_begin))?;
+    writeln!(output, "end: {}", format_time(time_end))?;
+    writeln!(output, "select: {}", select.as_deref().unwrap_or("none"))?;
+    writeln!(
+        output,
+        "filter: {}",
+        opts.filter
+            .as_ref()
+            .map_or("none", |filter| filter.as_str())
+    )?;
+    writeln!(output, "sort: {}", sort)?;
+    match opts.top {
+        0 => writeln!(output, "top: none")?,
+        top => writeln!(output, "top: {}", top)?,
+    }
+    match opts.limit_rows {
+        Some(limit) => writeln!(output, "limit-rows: {}", limit)?,
+        None => writeln!(output, "limit-rows: none")?,
+    }
+    if let Some(n) = opts.every_nth {
+        writeln!(output, "every-nth: {}", n)?;
+    }
+    if opts.drop_zero_columns {
+        writeln!(output, "drop-zero-columns: yes")?;
+    }
+    if let Some(interval) = opts.quantize {
+        writeln!(
+            output,
+            "quantize: {}, aligned to {}",
+            interval,
+            opts.quantize_align.unwrap_or(command::QuantizeAlign::Epoch)
+        )?;
+    }
+    for (path, output_format) in get_output_targets(opts) {
+        writeln!(
+            output,
+            "output-format: {}",
+            output_format.unwrap_or(OutputFormat::Raw)
+        )?;
+        writeln!(output, "output: {}", path)?;
+    }
+    Ok(())
 }
 
 /// Try to read $HOME/.config/below/belowrc file and generate a list of keys which will
@@ -174,15 +1233,154 @@ pub fn parse_pattern<T: FromStr>(
     )
 }
 
+/// Fill the options not given on the command line with the defaults of
+/// belowrc. raw-model only writes json and rejects most options, so it has no
+/// defaults.
+fn apply_dumprc(filename: &str, cmd: &mut DumpCommand) -> Result<()> {
+    let (subcommand, opts) = match cmd {
+        DumpCommand::System { opts, .. } => ("system", opts),
+        DumpCommand::Disk { opts, .. } => ("disk", opts),
+        DumpCommand::Process { opts, .. } => ("process", opts),
+        DumpCommand::Cgroup { opts, .. } => ("cgroup", opts),
+        DumpCommand::Iface { opts, .. } => ("iface", opts),
+        DumpCommand::Network { opts, .. } => ("network", opts),
+        DumpCommand::Transport { opts, .. } => ("transport", opts),
+        DumpCommand::RawModel { .. } => return Ok(()),
+    };
+    dumprc::DumpRc::load(filename, subcommand)?.apply(opts)
+}
+
+/// How a subcommand resolves the fields it dumps, see `resolve_fields`.
+struct FieldArgs<F: FieldId + 'static, A: AggField<F> + 'static> {
+    /// --fields, if given
+    fields: Option<Vec<DumpOptionField<F, A>>>,
+    /// --pattern, a key of the belowrc section of the subcommand
+    pattern: Option<String>,
+    section: &'static str,
+    presets: &'static [command::FieldsPreset],
+    default_fields: &'static [DumpOptionField<F, A>],
+    /// Whether the rows of each time slice can be ranked by --with-rank
+    rank: bool,
+    /// Field added by an option of the subcommand, e.g. tid by --threads,
+    /// with the option and the function inserting it into the fields
+    extra_field: Option<(DumpField<F>, &'static str, AddField<F>)>,
+}
+
+/// Insert a field into the resolved fields, see `FieldArgs::extra_field`.
+type AddField<F> = fn(Vec<DumpField<F>>) -> Vec<DumpField<F>>;
+
+/// Resolve the fields dumped by a subcommand from --fields or the belowrc
+/// pattern, the preset and the options changing the fields, in this order.
+/// Also returns where each field comes from for --explain-fields.
+fn resolve_fields<F, A>(
+    args: FieldArgs<F, A>,
+    filename: String,
+    opts: &command::GeneralOpt,
+) -> Result<(Vec<DumpField<F>>, FieldSources<F>)>
+where
+    F: FieldId + Clone + PartialEq + FromStr + EnumIter + ToString,
+    F::Queriable: HasRenderConfigForDump,
+    A: AggField<F> + Clone + PartialEq + FromStr + EnumIter + ToString,
+{
+    let fields = match args.pattern.clone() {
+        Some(pattern_key) => parse_pattern(filename, pattern_key, args.section),
+        None => args.fields,
+    };
+    let mut sources = field_sources(
+        fields.as_deref(),
+        args.pattern.as_deref(),
+        opts,
+        args.presets,
+        args.default_fields,
+    )?;
+    if let Some((field, option, _)) = args.extra_field.as_ref() {
+        sources.push((field.clone(), option.to_string()));
+    }
+    let fields = apply_fields_preset(fields, opts, args.presets)?;
+    let everything = opts.everything || command::has_all_field(fields.as_deref());
+    let default = everything || opts.default;
+    let detail = everything || opts.detail;
+    let fields = expand_fields(
+        match fields.as_ref() {
+            Some(fields) if !default => fields,
+            _ => args.default_fields,
+        },
+        detail,
+    );
+    let fields = add_expr_fields(fields, opts)?;
+    let fields = order_fields::<_, A>(fields, opts);
+    let fields = move_fields::<_, A>(fields, opts, detail)?;
+    let fields = match args.extra_field {
+        Some((_, _, add_field)) => add_field(fields),
+        None => fields,
+    };
+    let fields = if args.rank {
+        add_rank_field(fields, opts)?
+    } else {
+        fields
+    };
+    let fields = apply_counters(fields, opts);
+    let fields = add_anomaly_field(fields, opts)?;
+    let fields = rename_fields(fields, opts)?;
+    let fields = match opts.histogram.as_deref() {
+        Some(field) => vec![get_histogram_field::<F, A>(field)?],
+        None => fields,
+    };
+    Ok((fields, sources))
+}
+
+/// Resolve the fields of a subcommand and dump them with the dumpers created
+/// by `new_dumper`. --explain-fields and --dry-run only print the resolved
+/// fields. `select` is the --select field of the subcommand, if any.
+fn dump_fields<F, A, D>(
+    src: DumpSource,
+    filename: String,
+    opts: &command::GeneralOpt,
+    args: FieldArgs<F, A>,
+    select: Option<String>,
+    new_dumper: impl Fn(&command::GeneralOpt, Vec<DumpField<F>>) -> D,
+) -> Result<()>
+where
+    F: FieldId + Clone + PartialEq + FromStr + EnumIter + ToString,
+    F::Queriable: HasRenderConfigForDump,
+    A: AggField<F> + Clone + PartialEq + FromStr + EnumIter + ToString,
+    D: Dumper + 'static,
+{
+    let (fields, sources) = resolve_fields(args, filename, opts)?;
+    if opts.explain_fields {
+        return print_field_sources(&mut io::stdout(), opts, &fields, &sources);
+    }
+    if opts.dry_run {
+        let (time_begin, time_end) = get_time_range(opts)?;
+        return print_dry_run(
+            &mut io::stdout(),
+            opts,
+            select,
+            &fields,
+            time_begin,
+            time_end,
+        );
+    }
+    run_dump(&src, opts, |opts| new_dumper(opts, fields.clone()))
+}
+
 pub fn run(
     logger: slog::Logger,
     errs: Receiver<Error>,
     dir: PathBuf,
     host: Option<String>,
     port: Option<u16>,
-    cmd: DumpCommand,
+    mut cmd: DumpCommand,
 ) -> Result<()> {
     let filename = get_belowrc_filename();
+    apply_dumprc(&filename, &mut cmd)?;
+    let src = DumpSource {
+        logger,
+        dir,
+        host,
+        port,
+        errs,
+    };
 
     match cmd {
         DumpCommand::System {
@@ -190,256 +1388,165 @@ pub fn run(
             opts,
             pattern,
         } => {
-            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
-            let default = opts.everything || opts.default;
-            let detail = opts.everything || opts.detail;
-            let fields = if let Some(pattern_key) = pattern {
-                parse_pattern(filename, pattern_key, "system")
-            } else {
-                fields
+            let args = FieldArgs {
+                fields,
+                pattern,
+                section: "system",
+                presets: command::SYSTEM_PRESETS,
+                default_fields: command::DEFAULT_SYSTEM_FIELDS,
+                rank: false,
+                extra_field: None,
             };
-            let fields = expand_fields(
-                match fields.as_ref() {
-                    Some(fields) if !default => fields,
-                    _ => command::DEFAULT_SYSTEM_FIELDS,
-                },
-                detail,
-            );
-            let system = system::System::new(&opts, fields);
-            let mut output: Box<dyn Write> = match opts.output.as_ref() {
-                Some(file_path) => Box::new(File::create(file_path)?),
-                None => Box::new(io::stdout()),
-            };
-            dump_timeseries(
-                advance,
-                time_begin,
-                time_end,
-                &system,
-                output.as_mut(),
-                opts.output_format,
-                opts.br,
-                errs,
-            )
+            dump_fields(src, filename, &opts, args, None, |opts, fields| {
+                system::System::new(opts, fields)
+            })
         }
         DumpCommand::Disk {
             fields,
             opts,
             select,
+            filter_field,
             pattern,
         } => {
-            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
-            let default = opts.everything || opts.default;
-            let detail = opts.everything || opts.detail;
-            let fields = if let Some(pattern_key) = pattern {
-                parse_pattern(filename, pattern_key, "disk")
-            } else {
-                fields
+            let args = FieldArgs {
+                fields,
+                pattern,
+                section: "disk",
+                presets: command::DISK_PRESETS,
+                default_fields: command::DEFAULT_DISK_FIELDS,
+                rank: true,
+                extra_field: None,
             };
-            let fields = expand_fields(
-                match fields.as_ref() {
-                    Some(fields) if !default => fields,
-                    _ => command::DEFAULT_DISK_FIELDS,
-                },
-                detail,
-            );
-            let disk = disk::Disk::new(&opts, select, fields);
-            let mut output: Box<dyn Write> = match opts.output.as_ref() {
-                Some(file_path) => Box::new(File::create(file_path)?),
-                None => Box::new(io::stdout()),
-            };
-            dump_timeseries(
-                advance,
-                time_begin,
-                time_end,
-                &disk,
-                output.as_mut(),
-                opts.output_format,
-                opts.br,
-                errs,
-            )
+            let select_name = select.as_ref().map(|s| s.to_string());
+            dump_fields(src, filename, &opts, args, select_name, |opts, fields| {
+                disk::Disk::new(opts, select.clone(), filter_field.clone(), fields)
+            })
         }
         DumpCommand::Process {
             fields,
             opts,
             select,
+            filter_field,
             pattern,
+            threads,
+            pids,
         } => {
-            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
-            let default = opts.everything || opts.default;
-            let detail = opts.everything || opts.detail;
-            let fields = if let Some(pattern_key) = pattern {
-                parse_pattern(filename, pattern_key, "process")
-            } else {
-                fields
+            let tid = DumpField::FieldId(model::SingleProcessModelFieldId::Tid);
+            let args = FieldArgs {
+                fields,
+                pattern,
+                section: "process",
+                presets: command::PROCESS_PRESETS,
+                default_fields: command::DEFAULT_PROCESS_FIELDS,
+                rank: true,
+                extra_field: threads.then_some((tid, "--threads", add_tid_field as _)),
             };
-            let fields = expand_fields(
-                match fields.as_ref() {
-                    Some(fields) if !default => fields,
-                    _ => command::DEFAULT_PROCESS_FIELDS,
-                },
-                detail,
-            );
-            let process = process::Process::new(&opts, select, fields);
-            let mut output: Box<dyn Write> = match opts.output.as_ref() {
-                Some(file_path) => Box::new(File::create(file_path)?),
-                None => Box::new(io::stdout()),
-            };
-            dump_timeseries(
-                advance,
-                time_begin,
-                time_end,
-                &process,
-                output.as_mut(),
-                opts.output_format,
-                opts.br,
-                errs,
-            )
+            let select_name = select.as_ref().map(|s| s.to_string());
+            dump_fields(src, filename, &opts, args, select_name, |opts, fields| {
+                process::Process::new(
+                    opts,
+                    select.clone(),
+                    filter_field.clone(),
+                    threads,
+                    pids.clone(),
+                    fields,
+                )
+            })
         }
         DumpCommand::Cgroup {
             fields,
             opts,
             select,
+            filter_field,
             pattern,
+            with_total,
+            per_device,
         } => {
-            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
-            let default = opts.everything || opts.default;
-            let detail = opts.everything || opts.detail;
-            let fields = if let Some(pattern_key) = pattern {
-                parse_pattern(filename, pattern_key, "cgroup")
-            } else {
-                fields
+            let device = DumpField::FieldId(model::SingleCgroupModelFieldId::Device);
+            let args = FieldArgs {
+                fields,
+                pattern,
+                section: "cgroup",
+                presets: command::CGROUP_PRESETS,
+                default_fields: command::DEFAULT_CGROUP_FIELDS,
+                rank: true,
+                extra_field: per_device.then_some((device, "--per-device", add_device_field as _)),
             };
-            let fields = expand_fields(
-                match fields.as_ref() {
-                    Some(fields) if !default => fields,
-                    _ => command::DEFAULT_CGROUP_FIELDS,
-                },
-                detail,
-            );
-            let cgroup = cgroup::Cgroup::new(&opts, select, fields);
-            let mut output: Box<dyn Write> = match opts.output.as_ref() {
-                Some(file_path) => Box::new(File::create(file_path)?),
-                None => Box::new(io::stdout()),
-            };
-            dump_timeseries(
-                advance,
-                time_begin,
-                time_end,
-                &cgroup,
-                output.as_mut(),
-                opts.output_format,
-                opts.br,
-                errs,
-            )
+            let select_name = select.as_ref().map(|s| s.to_string());
+            dump_fields(src, filename, &opts, args, select_name, |opts, fields| {
+                cgroup::Cgroup::new(
+                    opts,
+                    select.clone(),
+                    filter_field.clone(),
+                    with_total,
+                    per_device,
+                    fields,
+                )
+            })
         }
         DumpCommand::Iface {
             fields,
             opts,
             select,
+            filter_field,
             pattern,
         } => {
-            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
-            let default = opts.everything || opts.default;
-            let detail = opts.everything || opts.detail;
-            let fields = if let Some(pattern_key) = pattern {
-                parse_pattern(filename, pattern_key, "iface")
-            } else {
-                fields
+            let args = FieldArgs {
+                fields,
+                pattern,
+                section: "iface",
+                presets: &[],
+                default_fields: command::DEFAULT_IFACE_FIELDS,
+                rank: false,
+                extra_field: None,
             };
-            let fields = expand_fields(
-                match fields.as_ref() {
-                    Some(fields) if !default => fields,
-                    _ => command::DEFAULT_IFACE_FIELDS,
-                },
-                detail,
-            );
-            let iface = iface::Iface::new(&opts, select, fields);
-            let mut output: Box<dyn Write> = match opts.output.as_ref() {
-                Some(file_path) => Box::new(File::create(file_path)?),
-                None => Box::new(io::stdout()),
-            };
-            dump_timeseries(
-                advance,
-                time_begin,
-                time_end,
-                &iface,
-                output.as_mut(),
-                opts.output_format,
-                opts.br,
-                errs,
-            )
+            let select_name = select.as_ref().map(|s| s.to_string());
+            dump_fields(src, filename, &opts, args, select_name, |opts, fields| {
+                iface::Iface::new(opts, select.clone(), filter_field.clone(), fields)
+            })
         }
         DumpCommand::Network {
             fields,
             opts,
             pattern,
         } => {
-            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
-            let default = opts.everything || opts.default;
-            let detail = opts.everything || opts.detail;
-            let fields = if let Some(pattern_key) = pattern {
-                parse_pattern(filename, pattern_key, "network")
-            } else {
-                fields
+            let args = FieldArgs {
+                fields,
+                pattern,
+                section: "network",
+                presets: &[],
+                default_fields: command::DEFAULT_NETWORK_FIELDS,
+                rank: false,
+                extra_field: None,
             };
-            let fields = expand_fields(
-                match fields.as_ref() {
-                    Some(fields) if !default => fields,
-                    _ => command::DEFAULT_NETWORK_FIELDS,
-                },
-                detail,
-            );
-            let network = network::Network::new(&opts, fields);
-            let mut output: Box<dyn Write> = match opts.output.as_ref() {
-                Some(file_path) => Box::new(File::create(file_path)?),
-                None => Box::new(io::stdout()),
-            };
-            dump_timeseries(
-                advance,
-                time_begin,
-                time_end,
-                &network,
-                output.as_mut(),
-                opts.output_format,
-                opts.br,
-                errs,
-            )
+            dump_fields(src, filename, &opts, args, None, |opts, fields| {
+                network::Network::new(opts, fields)
+            })
         }
         DumpCommand::Transport {
             fields,
             opts,
             pattern,
         } => {
-            let (time_begin, time_end, advance) = get_advance(logger, dir, host, port, &opts)?;
-            let default = opts.everything || opts.default;
-            let detail = opts.everything || opts.detail;
-            let fields = if let Some(pattern_key) = pattern {
-                parse_pattern(filename, pattern_key, "transport")
-            } else {
-                fields
+            let args = FieldArgs {
+                fields,
+                pattern,
+                section: "transport",
+                presets: &[],
+                default_fields: command::DEFAULT_TRANSPORT_FIELDS,
+                rank: false,
+                extra_field: None,
             };
-            let fields = expand_fields(
-                match fields.as_ref() {
-                    Some(fields) if !default => fields,
-                    _ => command::DEFAULT_TRANSPORT_FIELDS,
-                },
-                detail,
-            );
-            let transport = transport::Transport::new(&opts, fields);
-            let mut output: Box<dyn Write> = match opts.output.as_ref() {
-                Some(file_path) => Box::new(File::create(file_path)?),
-                None => Box::new(io::stdout()),
-            };
-            dump_timeseries(
-                advance,
-                time_begin,
-                time_end,
-                &transport,
-                output.as_mut(),
-                opts.output_format,
-                opts.br,
-                errs,
-            )
+            dump_fields(src, filename, &opts, args, None, |opts, fields| {
+                transport::Transport::new(opts, fields)
+            })
+        }
+        DumpCommand::RawModel { model, mut opts } => {
+            raw_model::check_opts(&opts)?;
+            if opts.output_json.is_none() {
+                opts.output_format = Some(OutputFormat::Json);
+            }
+            run_dump(&src, &opts, |_| raw_model::RawModel::new(model))
         }
     }
 }
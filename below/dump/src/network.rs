@@ -48,9 +48,7 @@ impl Dumper for Network {
                     ctx,
                     &model.network,
                     *round,
-                    self.opts.repeat_title,
-                    self.opts.disable_title,
-                    self.opts.raw
+                    &print::RawOptions::new(&self.opts),
                 )
             )?,
             Some(OutputFormat::Csv) => write!(
@@ -62,13 +60,30 @@ impl Dumper for Network {
                     &model.network,
                     *round,
                     self.opts.disable_title,
-                    self.opts.raw
+                    self.opts.raw,
+                    self.opts.na_string.as_deref()
                 )
             )?,
             Some(OutputFormat::KeyVal) => write!(
                 output,
                 "{}",
-                print::dump_kv(&self.fields, ctx, &model.network, self.opts.raw)
+                print::dump_kv(
+                    &self.fields,
+                    ctx,
+                    &model.network,
+                    self.opts.raw,
+                    self.opts.na_string.as_deref()
+                )
+            )?,
+            Some(OutputFormat::Graphite) => write!(
+                output,
+                "{}",
+                print::dump_graphite(
+                    &self.fields,
+                    ctx,
+                    &model.network,
+                    &format!("{}.network", print::GRAPHITE_ROOT)
+                )
             )?,
             Some(OutputFormat::Json) => {
                 let json_output =
@@ -82,9 +97,25 @@ impl Dumper for Network {
         };
         *round += 1;
 
-        if self.opts.output_format != Some(OutputFormat::Json) {
+        // Graphite lines are not separated by time slice
+        if !matches!(
+            self.opts.output_format,
+            Some(OutputFormat::Json) | Some(OutputFormat::Graphite)
+        ) {
             write!(output, "\n")?;
         }
         Ok(IterExecResult::Success)
     }
+
+    fn field_names(&self) -> Vec<String> {
+        self.fields.iter().map(ToString::to_string).collect()
+    }
+
+    fn quantize_fields(&self) -> Result<Vec<quantize::QuantizeField>> {
+        quantize::quantize_fields(&self.fields, &[])
+    }
+
+    fn nonzero_columns(&self) -> Vec<nonzero::Column> {
+        nonzero::columns(&self.fields)
+    }
 }
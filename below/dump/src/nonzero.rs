@@ -0,0 +1,239 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use super::*;
+
+use print::HasRenderConfigForDump;
+use quantize::Quantize;
+
+/// A dumped field for --drop-zero-columns, by its title, the key of its value
+/// in json output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub title: String,
+    /// Common fields like the timestamp are kept even if they are zero
+    pub keep: bool,
+}
+
+/// Get the columns of `fields` for --drop-zero-columns.
+pub fn columns<F>(fields: &[DumpField<F>]) -> Vec<Column>
+where
+    F: FieldId,
+    F::Queriable: HasRenderConfigForDump,
+{
+    fields
+        .iter()
+        .map(|field| Column {
+            title: field.get_render_config().render_title(false),
+            keep: matches!(field, DumpField::Common(_)),
+        })
+        .collect()
+}
+
+/// Whether a value dumped as raw json is zero or missing. Missing values are
+/// dumped as "?".
+pub fn is_zero(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(v) => v == "?" || v.parse::<f64>().is_ok_and(|v| v == 0.0),
+        Value::Number(v) => v.as_f64() == Some(0.0),
+        _ => false,
+    }
+}
+
+/// Remove the keys of `dropped` from the objects of a time slice dumped as
+/// json, including nested cgroups and devices.
+fn drop_keys(value: &mut Value, dropped: &HashSet<&str>) {
+    match value {
+        Value::Array(arr) => {
+            for v in arr {
+                drop_keys(v, dropped);
+            }
+        }
+        Value::Object(obj) => {
+            for key in dropped {
+                obj.remove(*key);
+            }
+            for v in obj.values_mut() {
+                drop_keys(v, dropped);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Render a value dumped as json the way it's written in csv output.
+fn csv_value(value: Option<&Value>, na_string: Option<&str>) -> String {
+    match value {
+        None | Some(Value::Null) => na_string.unwrap_or("?").to_owned(),
+        Some(Value::String(v)) if v == "?" => na_string.unwrap_or("?").to_owned(),
+        Some(Value::String(v)) => v.clone(),
+        Some(Value::Array(arr)) => arr
+            .iter()
+            .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_owned))
+            .collect::<Vec<_>>()
+            .join(","),
+        Some(v) => v.to_string(),
+    }
+}
+
+#[derive(Default)]
+struct State {
+    /// Json dumped for each time slice, written by `finish`
+    slices: Vec<Value>,
+    /// Titles with a non-zero value in any row
+    non_zero: HashSet<String>,
+}
+
+/// Dumper for --drop-zero-columns. Each time slice is dumped by two wrapped
+/// dumpers, which must both dump json. The first dumps raw json, which is
+/// read back to find the fields with a non-zero value. The json of the
+/// second is buffered until `finish`, which writes it without the fields
+/// that were zero or missing in every row.
+pub struct NonZero {
+    detect: Box<dyn Dumper>,
+    dumper: Box<dyn Dumper>,
+    columns: Vec<Column>,
+    output_format: Option<OutputFormat>,
+    disable_title: bool,
+    na_string: Option<String>,
+    state: RefCell<State>,
+}
+
+impl NonZero {
+    pub fn new(
+        detect: Box<dyn Dumper>,
+        dumper: Box<dyn Dumper>,
+        output_format: Option<OutputFormat>,
+        disable_title: bool,
+        na_string: Option<String>,
+    ) -> Result<Self> {
+        if !matches!(
+            output_format,
+            Some(OutputFormat::Json) | Some(OutputFormat::Csv)
+        ) {
+            bail!("--drop-zero-columns only works with json or csv output");
+        }
+        let columns = dumper.nonzero_columns();
+        if columns.is_empty() {
+            bail!("--drop-zero-columns is not supported by this command");
+        }
+        Ok(Self {
+            detect,
+            dumper,
+            columns,
+            output_format,
+            disable_title,
+            na_string,
+            state: RefCell::new(Default::default()),
+        })
+    }
+
+    /// Columns with a non-zero value in any row so far, and the kept ones.
+    fn kept_columns<'a>(&'a self, state: &State) -> Vec<&'a Column> {
+        self.columns
+            .iter()
+            .filter(|column| column.keep || state.non_zero.contains(&column.title))
+            .collect()
+    }
+}
+
+impl Dumper for NonZero {
+    fn dump_model(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+        _output: &mut dyn Write,
+        round: &mut usize,
+        _comma_flag: bool,
+    ) -> Result<IterExecResult> {
+        let mut state = self.state.borrow_mut();
+        // Rows are cut by --limit-rows the same way in both dumps
+        let mut detect_round = *round;
+        let mut content: Vec<u8> = Vec::new();
+        if self
+            .detect
+            .dump_model(ctx, model, &mut content, &mut detect_round, false)?
+            == IterExecResult::Success
+        {
+            let value: Value = serde_json::from_slice(&content)?;
+            let mut rows = Vec::new();
+            Quantize::collect_rows(&value, &mut rows);
+            for row in rows {
+                for (title, value) in row {
+                    if !is_zero(value) && !state.non_zero.contains(title) {
+                        state.non_zero.insert(title.clone());
+                    }
+                }
+            }
+        }
+
+        let mut content: Vec<u8> = Vec::new();
+        if self
+            .dumper
+            .dump_model(ctx, model, &mut content, round, false)?
+            == IterExecResult::Success
+        {
+            state.slices.push(serde_json::from_slice(&content)?);
+        }
+        // Nothing is written before `finish`, so there is nothing to separate
+        Ok(IterExecResult::Skip)
+    }
+
+    fn finish(&self, output: &mut dyn Write) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        let kept = self.kept_columns(&state);
+        let slices = std::mem::take(&mut state.slices);
+        if self.output_format == Some(OutputFormat::Json) {
+            let dropped = self
+                .columns
+                .iter()
+                .filter(|column| !kept.contains(column))
+                .map(|column| column.title.as_str())
+                .collect::<HashSet<_>>();
+            for (idx, mut value) in slices.into_iter().enumerate() {
+                drop_keys(&mut value, &dropped);
+                // Separated like the time slices of other dumps
+                let sep = if idx == 0 { "" } else { "\n," };
+                write!(output, "{}{}", sep, value)?;
+            }
+            return Ok(());
+        }
+        if slices.is_empty() {
+            return Ok(());
+        }
+        if !self.disable_title {
+            let titles = kept.iter().map(|column| &column.title);
+            write!(output, "{}", print::dump_csv_line(titles))?;
+        }
+        for value in &slices {
+            let mut rows = Vec::new();
+            Quantize::collect_rows(value, &mut rows);
+            for row in rows {
+                let values = kept
+                    .iter()
+                    .map(|column| csv_value(row.get(&column.title), self.na_string.as_deref()));
+                write!(output, "{}", print::dump_csv_line(values))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn field_names(&self) -> Vec<String> {
+        self.dumper.field_names()
+    }
+}
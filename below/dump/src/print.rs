@@ -14,8 +14,9 @@
 
 use super::*;
 
+use command::{ColorThreshold, GeneralOpt, Truncate, TruncateSide};
 use model::{Field, FieldId, Queriable, Recursive};
-use render::{HasRenderConfig, RenderConfig};
+use render::{FoldOption, HasRenderConfig, RenderConfig};
 
 impl CommonField {
     /// Default RenderConfig for CommonField
@@ -24,6 +25,7 @@ impl CommonField {
         match self {
             Self::Timestamp => rc.title("Timestamp").width(10),
             Self::Datetime => rc.title("Datetime").width(19),
+            Self::Rank => rc.title("Rank").width(5),
         }
         .get()
     }
@@ -35,6 +37,62 @@ pub trait HasRenderConfigForDump: HasRenderConfig {
     }
 }
 
+/// Render --na-string in place of a missing value, padded like the value
+/// would be. Formatting meant for the value is not applied.
+fn render_na_string(mut config: RenderConfig, na_string: &str, fixed_width: bool) -> String {
+    config.format = None;
+    config.suffix = None;
+    config.fold = None;
+    config.indented_prefix = None;
+    config.render(Some(Field::from(na_string.to_owned())), fixed_width)
+}
+
+/// Insert a comma every three digits of an integer, e.g. 1,234,567.
+pub fn group_digits(value: &str) -> String {
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", value),
+    };
+    let mut res = String::from(sign);
+    for (idx, c) in digits.chars().enumerate() {
+        if idx != 0 && (digits.len() - idx) % 3 == 0 {
+            res.push(',');
+        }
+        res.push(c);
+    }
+    res
+}
+
+/// Apply --truncate and --truncate-side to the fixed width config of a field.
+/// Only string values are affected, so numbers are never cut.
+fn apply_truncate(
+    config: &mut RenderConfig,
+    truncate: Truncate,
+    field: &Option<Field>,
+    depth: usize,
+) {
+    let value = match field {
+        Some(value @ Field::Str(_)) => value,
+        _ => return,
+    };
+    match truncate.width {
+        Some(0) => {
+            let len = config
+                .render_indented(Some(value.clone()), false, depth)
+                .chars()
+                .count();
+            config.width = Some(std::cmp::max(config.get_width(), len));
+        }
+        Some(width) => config.width = Some(width),
+        None => {}
+    }
+    match truncate.side {
+        Some(TruncateSide::Left) => config.fold = Some(FoldOption::KeepEnd),
+        Some(TruncateSide::Right) => config.fold = None,
+        None => {}
+    }
+}
+
 impl<F> DumpField<F>
 where
     F: FieldId,
@@ -44,6 +102,13 @@ where
         match self {
             Self::Common(common) => common.get_render_config(),
             Self::FieldId(field_id) => F::Queriable::get_render_config_for_dump(field_id),
+            Self::Expr(expr) => render::RenderConfigBuilder::new()
+                .title(&expr.name)
+                .format(render::RenderFormat::Precision(2))
+                .get(),
+            Self::Renamed(field, name) => field
+                .get_render_config()
+                .update(render::RenderConfigBuilder::new().title(name)),
         }
     }
 
@@ -51,22 +116,95 @@ where
         match self {
             Self::Common(common) => common.get_field(ctx),
             Self::FieldId(field_id) => model.query(field_id),
+            Self::Expr(expr) => expr.get_field(model),
+            Self::Renamed(field, _) => field.get_field(ctx, model),
         }
     }
 
-    pub fn dump_field(
+    fn is_common(&self) -> bool {
+        match self {
+            Self::Common(_) => true,
+            Self::Renamed(field, _) => field.is_common(),
+            _ => false,
+        }
+    }
+
+    fn is_expr(&self) -> bool {
+        match self {
+            Self::Expr(_) => true,
+            Self::Renamed(field, _) => field.is_expr(),
+            _ => false,
+        }
+    }
+
+    /// String to render a missing value with. --expr columns without a value,
+    /// e.g. after division by zero, are empty unless `na_string` is given.
+    fn missing_value<'a>(&self, na_string: Option<&'a str>) -> Option<&'a str> {
+        match na_string {
+            None if self.is_expr() => Some(""),
+            na_string => na_string,
+        }
+    }
+
+    /// Like get_field, but with the digits of integers grouped if `group` is
+    /// set. Fields with a format are left as is, as are the common fields.
+    fn get_grouped_field(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &F::Queriable,
+        config: &RenderConfig,
+        group: bool,
+    ) -> Option<Field> {
+        match self.get_field(ctx, model)? {
+            value @ (Field::U32(_) | Field::U64(_) | Field::I32(_) | Field::I64(_))
+                if group && config.format.is_none() && !self.is_common() =>
+            {
+                Some(Field::Str(group_digits(&value.to_string())))
+            }
+            value => Some(value),
+        }
+    }
+
+    /// Config to render the field of a model with, and its value. In fixed
+    /// width, string values are fit to their column as given by the Truncate.
+    fn get_value_render_config(
         &self,
         ctx: &CommonFieldContext,
         model: &F::Queriable,
         raw: bool,
-        fixed_width: bool,
-    ) -> String {
+        fixed_width: Option<Truncate>,
+        group: bool,
+        depth: usize,
+    ) -> (RenderConfig, Option<Field>) {
         let mut config = self.get_render_config();
         if raw {
             config.format = None;
             config.suffix = None;
         }
-        config.render(self.get_field(ctx, model), fixed_width)
+        let field = self.get_grouped_field(ctx, model, &config, group);
+        if let Some(truncate) = fixed_width {
+            apply_truncate(&mut config, truncate, &field, depth);
+        }
+        (config, field)
+    }
+
+    /// Render the field of a model. Missing values are rendered as
+    /// `na_string` if given, otherwise as "?". `fixed_width` pads or cuts the
+    /// value to its column, with string values cut as given.
+    pub fn dump_field(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &F::Queriable,
+        raw: bool,
+        na_string: Option<&str>,
+        fixed_width: Option<Truncate>,
+        group: bool,
+    ) -> String {
+        let (config, field) = self.get_value_render_config(ctx, model, raw, fixed_width, group, 0);
+        match (field, self.missing_value(na_string)) {
+            (None, Some(na_string)) => render_na_string(config, na_string, fixed_width.is_some()),
+            (field, _) => config.render(field, fixed_width.is_some()),
+        }
     }
 }
 
@@ -80,14 +218,17 @@ where
         ctx: &CommonFieldContext,
         model: &F::Queriable,
         raw: bool,
-        fixed_width: bool,
+        na_string: Option<&str>,
+        fixed_width: Option<Truncate>,
+        group: bool,
     ) -> String {
-        let mut config = self.get_render_config();
-        if raw {
-            config.format = None;
-            config.suffix = None;
+        let depth = model.get_depth();
+        let (config, field) =
+            self.get_value_render_config(ctx, model, raw, fixed_width, group, depth);
+        match (field, self.missing_value(na_string)) {
+            (None, Some(na_string)) => render_na_string(config, na_string, fixed_width.is_some()),
+            (field, _) => config.render_indented(field, fixed_width.is_some(), depth),
         }
-        config.render_indented(self.get_field(ctx, model), fixed_width, model.get_depth())
     }
 }
 
@@ -96,6 +237,7 @@ pub fn dump_kv<T: HasRenderConfigForDump>(
     ctx: &CommonFieldContext,
     model: &T,
     raw: bool,
+    na_string: Option<&str>,
 ) -> String {
     let mut res = String::new();
     for field in fields {
@@ -103,7 +245,7 @@ pub fn dump_kv<T: HasRenderConfigForDump>(
         res.push_str(&format!(
             "{}: {}\n",
             config.render_title(false),
-            field.dump_field(ctx, model, raw, false),
+            field.dump_field(ctx, model, raw, na_string, None, false),
         ));
     }
     res.push('\n');
@@ -119,41 +261,186 @@ pub fn dump_json<T: HasRenderConfigForDump>(
     let mut res = json!({});
     for field in fields {
         let config = field.get_render_config();
-        res[config.render_title(false)] = json!(field.dump_field(ctx, model, raw, false));
+        // Lists are kept as json arrays instead of the comma-joined string,
+        // bools as json bools instead of yes or no. --expr columns without a
+        // value are null.
+        res[config.render_title(false)] = match field.get_field(ctx, model) {
+            Some(Field::StrList(v)) => json!(v),
+            Some(Field::Bool(v)) => json!(v),
+            None if field.is_expr() => Value::Null,
+            _ => json!(field.dump_field(ctx, model, raw, None, None, false)),
+        };
     }
     res
 }
 
-fn dump_title_line<F>(fields: &[DumpField<F>], sep: &'static str, fixed_width: bool) -> String
+/// Root of the Graphite metric paths of --output-format graphite.
+pub const GRAPHITE_ROOT: &str = "below";
+
+/// Make `name` a single component of a Graphite metric path, replacing dots
+/// and anything else but letters, digits, '_' and '-' with '_'.
+pub fn graphite_component(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Write the numeric fields of `model` as Graphite plaintext lines of the
+/// form "<prefix>.<field> <value> <timestamp>". `prefix` identifies the
+/// entity, e.g. below.cgroup.system_slice. Fields without a numeric value,
+/// and the timestamp and datetime fields, are left out.
+pub fn dump_graphite<T: HasRenderConfigForDump>(
+    fields: &[DumpField<T::FieldId>],
+    ctx: &CommonFieldContext,
+    model: &T,
+    prefix: &str,
+) -> String
 where
-    F: FieldId,
-    F::Queriable: HasRenderConfigForDump,
+    T::FieldId: ToString,
 {
-    let mut line = String::new();
+    let mut res = String::new();
     for field in fields {
-        line.push_str(&field.get_render_config().render_title(fixed_width));
+        let name = match field {
+            DumpField::Common(CommonField::Timestamp | CommonField::Datetime) => continue,
+            DumpField::Renamed(_, title) => title.clone(),
+            field => field.to_string(),
+        };
+        let value = match field.get_field(ctx, model) {
+            Some(Field::U32(v)) => v.to_string(),
+            Some(Field::U64(v)) => v.to_string(),
+            Some(Field::I32(v)) => v.to_string(),
+            Some(Field::I64(v)) => v.to_string(),
+            Some(Field::F32(v)) if v.is_finite() => v.to_string(),
+            Some(Field::F64(v)) if v.is_finite() => v.to_string(),
+            _ => continue,
+        };
+        res.push_str(&format!(
+            "{}.{} {} {}\n",
+            prefix,
+            graphite_component(&name),
+            value,
+            ctx.timestamp
+        ));
+    }
+    res
+}
+
+fn dump_title_line<I>(configs: I, sep: &'static str, fixed_width: bool) -> String
+where
+    I: IntoIterator<Item = RenderConfig>,
+{
+    let mut line = String::new();
+    for config in configs {
+        line.push_str(&config.render_title(fixed_width));
         line.push_str(sep);
     }
     line.push('\n');
     line
 }
 
+const COLOR_RED: &str = "\x1b[31m";
+const COLOR_RESET: &str = "\x1b[0m";
+
+/// Check if a numeric field is above its --color-threshold, if any.
+fn is_above_color_threshold<F>(
+    field: &DumpField<F>,
+    ctx: &CommonFieldContext,
+    model: &F::Queriable,
+    color_thresholds: &[ColorThreshold],
+) -> bool
+where
+    F: FieldId + ToString,
+    F::Queriable: HasRenderConfigForDump,
+{
+    if color_thresholds.is_empty() {
+        return false;
+    }
+    let name = field.to_string();
+    let threshold = match color_thresholds.iter().find(|t| t.field == name) {
+        Some(t) => t.threshold,
+        None => return false,
+    };
+    match field.get_field(ctx, model) {
+        Some(
+            value @ (Field::U32(_)
+            | Field::U64(_)
+            | Field::I32(_)
+            | Field::I64(_)
+            | Field::F32(_)
+            | Field::F64(_)),
+        ) => f64::from(value) > threshold,
+        _ => false,
+    }
+}
+
+/// Options of raw output.
+#[derive(Default)]
+pub struct RawOptions<'a> {
+    pub repeat_title: Option<usize>,
+    pub disable_title: bool,
+    pub raw: bool,
+    pub na_string: Option<&'a str>,
+    /// Group the digits of integers
+    pub group: bool,
+    pub truncate: Truncate,
+    /// Values above these are highlighted
+    pub color_thresholds: &'a [ColorThreshold],
+}
+
+impl<'a> RawOptions<'a> {
+    pub fn new(opts: &'a GeneralOpt) -> Self {
+        Self {
+            repeat_title: opts.repeat_title,
+            disable_title: opts.disable_title,
+            raw: opts.raw,
+            na_string: opts.na_string.as_deref(),
+            group: opts.group_digits,
+            truncate: opts.truncation(),
+            color_thresholds: opts.active_color_thresholds(),
+        }
+    }
+}
+
 pub fn dump_raw<T: HasRenderConfigForDump>(
     fields: &[DumpField<T::FieldId>],
     ctx: &CommonFieldContext,
     model: &T,
     round: usize,
-    repeat_title: Option<usize>,
-    disable_title: bool,
-    raw: bool,
-) -> String {
+    opts: &RawOptions,
+) -> String
+where
+    T::FieldId: ToString,
+{
     let mut res = String::new();
-    let repeat = repeat_title.unwrap_or(0);
-    if !disable_title && (round == 0 || (repeat != 0 && round % repeat == 0)) {
-        res.push_str(&dump_title_line(fields, " ", true));
+    let repeat = opts.repeat_title.unwrap_or(0);
+    if !opts.disable_title && (round == 0 || (repeat != 0 && round.is_multiple_of(repeat))) {
+        let configs = fields.iter().map(|field| {
+            field
+                .get_value_render_config(ctx, model, opts.raw, Some(opts.truncate), opts.group, 0)
+                .0
+        });
+        res.push_str(&dump_title_line(configs, " ", true));
     }
     for field in fields {
-        res.push_str(&field.dump_field(ctx, model, raw, true));
+        let value = field.dump_field(
+            ctx,
+            model,
+            opts.raw,
+            opts.na_string,
+            Some(opts.truncate),
+            opts.group,
+        );
+        if is_above_color_threshold(field, ctx, model, opts.color_thresholds) {
+            res.push_str(&format!("{}{}{}", COLOR_RED, value, COLOR_RESET));
+        } else {
+            res.push_str(&value);
+        }
         res.push(' ');
     }
     res.push('\n');
@@ -165,23 +452,65 @@ pub fn dump_raw_indented<T: HasRenderConfigForDump + Recursive>(
     ctx: &CommonFieldContext,
     model: &T,
     round: usize,
-    repeat_title: Option<usize>,
-    disable_title: bool,
-    raw: bool,
-) -> String {
+    opts: &RawOptions,
+) -> String
+where
+    T::FieldId: ToString,
+{
     let mut res = String::new();
-    let repeat = repeat_title.unwrap_or(0);
-    if !disable_title && (round == 0 || (repeat != 0 && round % repeat == 0)) {
-        res.push_str(&dump_title_line(fields, " ", true));
+    let repeat = opts.repeat_title.unwrap_or(0);
+    if !opts.disable_title && (round == 0 || (repeat != 0 && round.is_multiple_of(repeat))) {
+        let depth = model.get_depth();
+        let configs = fields.iter().map(|field| {
+            field
+                .get_value_render_config(
+                    ctx,
+                    model,
+                    opts.raw,
+                    Some(opts.truncate),
+                    opts.group,
+                    depth,
+                )
+                .0
+        });
+        res.push_str(&dump_title_line(configs, " ", true));
     }
     for field in fields {
-        res.push_str(&field.dump_field_indented(ctx, model, raw, true));
+        let value = field.dump_field_indented(
+            ctx,
+            model,
+            opts.raw,
+            opts.na_string,
+            Some(opts.truncate),
+            opts.group,
+        );
+        if is_above_color_threshold(field, ctx, model, opts.color_thresholds) {
+            res.push_str(&format!("{}{}{}", COLOR_RED, value, COLOR_RESET));
+        } else {
+            res.push_str(&value);
+        }
         res.push(' ');
     }
     res.push('\n');
     res
 }
 
+/// Write one line of csv, each value followed by a comma. Titles and rows
+/// are written the same way.
+pub fn dump_csv_line<I, S>(values: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut line = String::new();
+    for value in values {
+        line.push_str(value.as_ref());
+        line.push(',');
+    }
+    line.push('\n');
+    line
+}
+
 pub fn dump_csv<T: HasRenderConfigForDump>(
     fields: &[DumpField<T::FieldId>],
     ctx: &CommonFieldContext,
@@ -189,15 +518,18 @@ pub fn dump_csv<T: HasRenderConfigForDump>(
     round: usize,
     disable_title: bool,
     raw: bool,
+    na_string: Option<&str>,
 ) -> String {
     let mut res = String::new();
     if !disable_title && round == 0 {
-        res.push_str(&dump_title_line(fields, ",", false));
-    }
-    for field in fields {
-        res.push_str(&field.dump_field(ctx, model, raw, false));
-        res.push(',');
+        res.push_str(&dump_csv_line(
+            fields
+                .iter()
+                .map(|field| field.get_render_config().render_title(false)),
+        ));
     }
-    res.push('\n');
+    res.push_str(&dump_csv_line(fields.iter().map(|field| {
+        field.dump_field(ctx, model, raw, na_string, None, false)
+    })));
     res
 }
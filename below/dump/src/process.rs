@@ -38,19 +38,34 @@ impl HasRenderConfigForDump for model::SingleProcessModel {
 pub struct Process {
     opts: GeneralOpt,
     select: Option<SingleProcessModelFieldId>,
+    // Field --filter applies to, --select unless --filter-field is given
+    filter_field: Option<SingleProcessModelFieldId>,
+    // Dump threads instead of processes
+    threads: bool,
+    // Only dump these pids if given
+    pids: Option<Vec<i32>>,
     fields: Vec<ProcessField>,
+    // Values of the last time slice for --sort-by delta
+    last_values: delta::LastValues,
 }
 
 impl Process {
     pub fn new(
         opts: &GeneralOpt,
         select: Option<SingleProcessModelFieldId>,
+        filter_field: Option<SingleProcessModelFieldId>,
+        threads: bool,
+        pids: Option<Vec<i32>>,
         fields: Vec<ProcessField>,
     ) -> Self {
         Self {
             opts: opts.to_owned(),
+            filter_field: filter_field.or_else(|| select.clone()),
             select,
+            threads,
+            pids,
             fields,
+            last_values: Default::default(),
         }
     }
 }
@@ -64,21 +79,26 @@ impl Dumper for Process {
         round: &mut usize,
         comma_flag: bool,
     ) -> Result<IterExecResult> {
-        let mut processes: Vec<_> = model
-            .process
-            .processes
-            .iter()
-            .filter_map(
-                |(_, spm)| match (self.select.as_ref(), self.opts.filter.as_ref()) {
-                    (Some(field_id), Some(filter))
-                        if !filter.is_match(
-                            &spm.query(&field_id)
-                                .map_or("?".to_owned(), |v| v.to_string()),
-                        ) =>
-                    {
-                        None
-                    }
-                    _ => Some(spm),
+        self.last_values.next_slice();
+        let processes = if self.threads {
+            &model.process.threads
+        } else {
+            &model.process.processes
+        };
+        let mut processes: Vec<_> = processes
+            .values()
+            .filter(|spm| match (self.pids.as_ref(), spm.pid) {
+                (Some(pids), Some(pid)) => pids.contains(&pid),
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            .filter(
+                |spm| match (self.filter_field.as_ref(), self.opts.filter.as_ref()) {
+                    (Some(field_id), Some(filter)) => filter.is_match(
+                        &spm.query(field_id)
+                            .map_or("?".to_owned(), |v| v.to_string()),
+                    ),
+                    _ => true,
                 },
             )
             .collect();
@@ -89,24 +109,38 @@ impl Dumper for Process {
         }
 
         if let Some(field_id) = self.select.as_ref() {
-            if self.opts.sort {
-                model::sort_queriables(&mut processes, &field_id, false);
-            }
-
-            if self.opts.rsort {
-                model::sort_queriables(&mut processes, &field_id, true);
+            if self.opts.sort || self.opts.rsort {
+                delta::sort_queriables_by(
+                    &mut processes,
+                    field_id,
+                    self.opts.rsort,
+                    self.opts.sort_by,
+                    |spm| {
+                        spm.tid
+                            .or(spm.pid)
+                            .map_or_else(String::new, |id| id.to_string())
+                    },
+                    &self.last_values,
+                );
             }
 
             if (self.opts.sort || self.opts.rsort) && self.opts.top != 0 {
                 processes.truncate(self.opts.top as usize);
             }
         }
+
+        if let Some(limit) = self.opts.limit_rows {
+            processes.truncate(limit.saturating_sub(*round));
+        }
+        let ranked = self.select.is_some() && (self.opts.sort || self.opts.rsort);
         let json = self.opts.output_format == Some(OutputFormat::Json);
         let mut json_output = json!([]);
 
         processes
             .into_iter()
-            .map(|spm| {
+            .enumerate()
+            .map(|(idx, spm)| {
+                let ctx = &ctx.with_rank(ranked.then(|| idx + 1));
                 match self.opts.output_format {
                     Some(OutputFormat::Raw) | None => write!(
                         output,
@@ -116,9 +150,7 @@ impl Dumper for Process {
                             ctx,
                             spm,
                             *round,
-                            self.opts.repeat_title,
-                            self.opts.disable_title,
-                            self.opts.raw
+                            &print::RawOptions::new(&self.opts),
                         )
                     )?,
                     Some(OutputFormat::Csv) => write!(
@@ -130,13 +162,39 @@ impl Dumper for Process {
                             spm,
                             *round,
                             self.opts.disable_title,
-                            self.opts.raw
+                            self.opts.raw,
+                            self.opts.na_string.as_deref()
                         )
                     )?,
                     Some(OutputFormat::KeyVal) => write!(
                         output,
                         "{}",
-                        print::dump_kv(&self.fields, ctx, spm, self.opts.raw)
+                        print::dump_kv(
+                            &self.fields,
+                            ctx,
+                            spm,
+                            self.opts.raw,
+                            self.opts.na_string.as_deref()
+                        )
+                    )?,
+                    Some(OutputFormat::Graphite) => write!(
+                        output,
+                        "{}",
+                        print::dump_graphite(
+                            &self.fields,
+                            ctx,
+                            spm,
+                            &match (self.threads, spm.tid, spm.pid) {
+                                (true, Some(tid), _) => {
+                                    format!("{}.thread.{}", print::GRAPHITE_ROOT, tid)
+                                }
+                                (_, _, pid) => format!(
+                                    "{}.process.{}",
+                                    print::GRAPHITE_ROOT,
+                                    pid.map_or_else(|| "unknown".to_owned(), |pid| pid.to_string())
+                                ),
+                            }
+                        )
                     )?,
                     Some(OutputFormat::Json) => {
                         let par = print::dump_json(&self.fields, ctx, spm, self.opts.raw);
@@ -151,9 +209,29 @@ impl Dumper for Process {
         match (json, comma_flag) {
             (true, true) => write!(output, ",{}", json_output)?,
             (true, false) => write!(output, "{}", json_output)?,
+            // Graphite lines are not separated by time slice
+            _ if self.opts.output_format == Some(OutputFormat::Graphite) => {}
             _ => write!(output, "\n")?,
         };
 
         Ok(IterExecResult::Success)
     }
+
+    fn field_names(&self) -> Vec<String> {
+        self.fields.iter().map(ToString::to_string).collect()
+    }
+
+    fn quantize_fields(&self) -> Result<Vec<quantize::QuantizeField>> {
+        quantize::quantize_fields(
+            &self.fields,
+            &[
+                model::SingleProcessModelFieldId::Pid,
+                model::SingleProcessModelFieldId::Tid,
+            ],
+        )
+    }
+
+    fn nonzero_columns(&self) -> Vec<nonzero::Column> {
+        nonzero::columns(&self.fields)
+    }
 }
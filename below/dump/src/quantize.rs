@@ -0,0 +1,386 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::*;
+
+use command::QuantizeAlign;
+use print::HasRenderConfigForDump;
+
+/// Title of the column with the number of time slices aggregated in a row.
+pub const SAMPLES_TITLE: &str = "Samples";
+
+/// How the values of a field are combined within a --quantize bucket.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Agg {
+    /// Tells rows apart, e.g. pid or cgroup path. Kept as is.
+    Key,
+    /// Timestamp and datetime, replaced by the start of the bucket.
+    Time(CommonField),
+    /// Rates and percentages, averaged over the time slices.
+    Mean,
+    /// Cumulative counters, dumped as their increase within the bucket.
+    Sum,
+    /// Gauges and everything else, the value of the last time slice.
+    Last,
+}
+
+/// A dumped field with its title, the key of its value in json output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuantizeField {
+    pub title: String,
+    pub agg: Agg,
+}
+
+fn get_agg<F>(field: &DumpField<F>, keys: &[F]) -> Agg
+where
+    F: FieldId + FromStr + ToString + PartialEq,
+{
+    match field {
+        DumpField::Common(common) => Agg::Time(common.clone()),
+        DumpField::FieldId(field_id) if keys.contains(field_id) => Agg::Key,
+        DumpField::FieldId(field_id) => {
+            let name = field_id.to_string();
+            if name.ends_with("_per_sec") || name.ends_with("_pct") {
+                Agg::Mean
+            } else if counter_rate(field_id).is_some() {
+                Agg::Sum
+            } else {
+                Agg::Last
+            }
+        }
+        DumpField::Expr(_) => Agg::Last,
+        DumpField::Renamed(field, _) => get_agg(field, keys),
+    }
+}
+
+/// Get how each of `fields` is aggregated by --quantize. `keys` are the
+/// fields that identify a row, at least one of them has to be dumped unless
+/// there is only one row per time slice.
+pub fn quantize_fields<F>(fields: &[DumpField<F>], keys: &[F]) -> Result<Vec<QuantizeField>>
+where
+    F: FieldId + FromStr + ToString + PartialEq,
+    F::Queriable: HasRenderConfigForDump,
+{
+    let res = fields
+        .iter()
+        .map(|field| QuantizeField {
+            title: field.get_render_config().render_title(false),
+            agg: get_agg(field, keys),
+        })
+        .collect::<Vec<_>>();
+    if !keys.is_empty() && !res.iter().any(|field| field.agg == Agg::Key) {
+        bail!(
+            "--quantize and --diff need one of these fields to tell rows apart: {}",
+            keys.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    Ok(res)
+}
+
+/// Combined values of one field in one row of a bucket.
+enum Acc {
+    Value(Value),
+    Mean { sum: f64, count: usize },
+    Sum(f64),
+}
+
+struct Row {
+    samples: usize,
+    values: Vec<Acc>,
+}
+
+#[derive(Default)]
+struct State {
+    bucket: Option<i64>,
+    /// First bucket boundary for --quantize-align first
+    offset: Option<i64>,
+    rows: Vec<Row>,
+    /// Index of each row in `rows` by key
+    row_index: HashMap<String, usize>,
+    /// Last value of each counter, by row key and title
+    counters: HashMap<(String, String), f64>,
+    /// Whether the json array already has an element, e.g. the
+    /// --with-meta record, before the first bucket is written
+    comma: Option<bool>,
+    written: usize,
+    /// Whether the last time slice wrote a bucket, which isn't followed by
+    /// a line break if it's also the last one dumped
+    wrote_last: bool,
+}
+
+/// Dumper for --quantize. The wrapped dumper must dump raw json, which is
+/// read back for each time slice and combined into one row per bucket and
+/// entity. Rows are written once a time slice of the next bucket comes in,
+/// the last bucket by `finish`.
+pub struct Quantize {
+    dumper: Box<dyn Dumper>,
+    fields: Vec<QuantizeField>,
+    interval: i64,
+    align: QuantizeAlign,
+    output_format: Option<OutputFormat>,
+    disable_title: bool,
+    state: RefCell<State>,
+}
+
+impl Quantize {
+    pub fn new(
+        dumper: Box<dyn Dumper>,
+        interval: Duration,
+        align: Option<QuantizeAlign>,
+        output_format: Option<OutputFormat>,
+        disable_title: bool,
+    ) -> Result<Self> {
+        if !matches!(
+            output_format,
+            Some(OutputFormat::Json) | Some(OutputFormat::Csv)
+        ) {
+            bail!("--quantize only works with json or csv output");
+        }
+        if interval.as_secs() == 0 {
+            bail!("--quantize interval must be at least 1 second");
+        }
+        let fields = dumper.quantize_fields()?;
+        if fields.is_empty() {
+            bail!("--quantize is not supported by this command");
+        }
+        Ok(Self {
+            dumper,
+            fields,
+            interval: interval.as_secs() as i64,
+            align: align.unwrap_or(QuantizeAlign::Epoch),
+            output_format,
+            disable_title,
+            state: RefCell::new(Default::default()),
+        })
+    }
+
+    /// Start of the bucket a timestamp falls in.
+    fn bucket_of(&self, state: &mut State, timestamp: i64) -> i64 {
+        let offset = match self.align {
+            QuantizeAlign::Epoch => 0,
+            QuantizeAlign::First => *state.offset.get_or_insert(timestamp),
+        };
+        offset + (timestamp - offset).div_euclid(self.interval) * self.interval
+    }
+
+    /// Collect the rows of the json dumped for one time slice. Cgroups are
+    /// nested under "children", which are flattened. The rows of
+    /// --per-device under "devices" replace the row of their cgroup.
+    pub fn collect_rows<'a>(value: &'a Value, rows: &mut Vec<&'a serde_json::Map<String, Value>>) {
+        match value {
+            Value::Array(arr) => {
+                for v in arr {
+                    Self::collect_rows(v, rows);
+                }
+            }
+            Value::Object(obj) => {
+                match obj.get("devices") {
+                    Some(devices) => Self::collect_rows(devices, rows),
+                    None => rows.push(obj),
+                }
+                if let Some(children) = obj.get("children") {
+                    Self::collect_rows(children, rows);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add_row(&self, state: &mut State, obj: &serde_json::Map<String, Value>) {
+        let get = |title: &str| obj.get(title).cloned().unwrap_or(Value::Null);
+        let key = self
+            .fields
+            .iter()
+            .filter(|field| field.agg == Agg::Key)
+            .map(|field| get(&field.title).to_string())
+            .collect::<Vec<_>>()
+            .join("\0");
+        let idx = match state.row_index.get(&key) {
+            Some(idx) => *idx,
+            None => {
+                let values = self
+                    .fields
+                    .iter()
+                    .map(|field| match field.agg {
+                        Agg::Mean => Acc::Mean { sum: 0.0, count: 0 },
+                        Agg::Sum => Acc::Sum(0.0),
+                        _ => Acc::Value(Value::Null),
+                    })
+                    .collect();
+                state.rows.push(Row { samples: 0, values });
+                state.row_index.insert(key.clone(), state.rows.len() - 1);
+                state.rows.len() - 1
+            }
+        };
+        let row = &mut state.rows[idx];
+        row.samples += 1;
+        for (field, acc) in self.fields.iter().zip(row.values.iter_mut()) {
+            let value = get(&field.title);
+            // Missing values are dumped as "?"
+            let number = value.as_str().and_then(|v| v.parse::<f64>().ok());
+            match acc {
+                Acc::Value(v) => *v = value,
+                Acc::Mean { sum, count } => {
+                    if let Some(number) = number {
+                        *sum += number;
+                        *count += 1;
+                    }
+                }
+                Acc::Sum(sum) => {
+                    if let Some(number) = number {
+                        let last = state
+                            .counters
+                            .insert((key.clone(), field.title.clone()), number);
+                        match last {
+                            Some(last) if number >= last => *sum += number - last,
+                            // Counter was reset, e.g. the process restarted
+                            Some(_) => *sum += number,
+                            // Nothing to compare the first value with
+                            None => {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_row(&self, bucket: i64, row: &Row) -> Vec<(String, String)> {
+        let ctx = CommonFieldContext {
+            timestamp: bucket,
+            rank: None,
+        };
+        let mut res = self
+            .fields
+            .iter()
+            .zip(row.values.iter())
+            .map(|(field, acc)| {
+                let value = match (&field.agg, acc) {
+                    (Agg::Time(common), _) => common
+                        .get_field(&ctx)
+                        .map_or_else(String::new, |f| f.to_string()),
+                    (_, Acc::Mean { count: 0, .. }) => "?".to_owned(),
+                    (_, Acc::Mean { sum, count }) => format!("{:.2}", sum / *count as f64),
+                    (_, Acc::Sum(sum)) if sum.fract() == 0.0 => format!("{}", sum),
+                    (_, Acc::Sum(sum)) => format!("{:.2}", sum),
+                    (_, Acc::Value(Value::String(v))) => v.clone(),
+                    (_, Acc::Value(Value::Null)) => "?".to_owned(),
+                    (_, Acc::Value(v)) => v.to_string(),
+                };
+                (field.title.clone(), value)
+            })
+            .collect::<Vec<_>>();
+        res.push((SAMPLES_TITLE.to_owned(), row.samples.to_string()));
+        res
+    }
+
+    /// Write the rows of the current bucket, if any.
+    fn flush(&self, state: &mut State, output: &mut dyn Write) -> Result<bool> {
+        let bucket = match state.bucket.take() {
+            Some(bucket) if !state.rows.is_empty() => bucket,
+            _ => return Ok(false),
+        };
+        state.row_index.clear();
+        let rows = std::mem::take(&mut state.rows)
+            .iter()
+            .map(|row| self.render_row(bucket, row))
+            .collect::<Vec<_>>();
+        if self.output_format == Some(OutputFormat::Json) {
+            let arr = rows
+                .iter()
+                .map(|row| {
+                    let mut obj = json!({});
+                    for (title, value) in row {
+                        obj[title] = json!(value);
+                    }
+                    obj
+                })
+                .collect::<Vec<_>>();
+            let comma = state.written != 0 || state.comma.unwrap_or(false);
+            write!(output, "{}{}", if comma { "," } else { "" }, json!(arr))?;
+        } else {
+            if state.written == 0 && !self.disable_title {
+                let titles = rows[0].iter().map(|(title, _)| title);
+                write!(output, "{}", print::dump_csv_line(titles))?;
+            }
+            for row in &rows {
+                let values = row.iter().map(|(_, value)| value);
+                write!(output, "{}", print::dump_csv_line(values))?;
+            }
+        }
+        state.written += 1;
+        Ok(true)
+    }
+}
+
+impl Dumper for Quantize {
+    fn dump_model(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+        output: &mut dyn Write,
+        round: &mut usize,
+        comma_flag: bool,
+    ) -> Result<IterExecResult> {
+        let mut state = self.state.borrow_mut();
+        state.comma.get_or_insert(comma_flag);
+        let bucket = self.bucket_of(&mut state, ctx.timestamp);
+        let mut res = IterExecResult::Skip;
+        if state.bucket.is_some_and(|current| current != bucket)
+            && self.flush(&mut state, output)?
+        {
+            res = IterExecResult::Success;
+        }
+        state.bucket = Some(bucket);
+        state.wrote_last = res == IterExecResult::Success;
+
+        let mut content: Vec<u8> = Vec::new();
+        let mut inner_round = 0;
+        if self
+            .dumper
+            .dump_model(ctx, model, &mut content, &mut inner_round, false)?
+            == IterExecResult::Success
+        {
+            let value: Value = serde_json::from_slice(&content)?;
+            let mut rows = Vec::new();
+            Self::collect_rows(&value, &mut rows);
+            for obj in rows {
+                let new_rows = state.rows.len();
+                self.add_row(&mut state, obj);
+                // Count rows as they start, so --limit-rows applies to the
+                // dumped rows
+                *round += state.rows.len() - new_rows;
+            }
+        }
+        Ok(res)
+    }
+
+    fn finish(&self, output: &mut dyn Write) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        if state.wrote_last && self.output_format == Some(OutputFormat::Json) {
+            writeln!(output)?;
+        }
+        self.flush(&mut state, output)?;
+        Ok(())
+    }
+
+    fn field_names(&self) -> Vec<String> {
+        self.dumper.field_names()
+    }
+}
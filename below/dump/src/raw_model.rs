@@ -0,0 +1,89 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use command::RawModelKind;
+
+/// Dumper for `below dump raw-model`. Serializes the whole sub-model of each
+/// time slice as is, without going through any FieldIds. The layout follows
+/// the model structs and is not stable across versions.
+pub struct RawModel {
+    kind: RawModelKind,
+}
+
+impl RawModel {
+    pub fn new(kind: RawModelKind) -> Self {
+        Self { kind }
+    }
+}
+
+/// Reject GeneralOpt options that have no meaning without fields. Output is
+/// always json, so only --output-format json is accepted.
+pub fn check_opts(opts: &command::GeneralOpt) -> Result<()> {
+    if !matches!(opts.output_format, Some(OutputFormat::Json) | None) {
+        bail!("raw-model only supports json output");
+    }
+    let unsupported = [
+        ("--output-raw", opts.output_raw.is_some()),
+        ("--output-csv", opts.output_csv.is_some()),
+        ("--output-kv", opts.output_kv.is_some()),
+        ("--expr", !opts.expr.is_empty()),
+        ("--fields-preset", opts.fields_preset.is_some()),
+        ("--histogram", opts.histogram.is_some()),
+        ("--quantize", opts.quantize.is_some()),
+        ("--diff", !opts.diff.is_empty()),
+        ("--drop-zero-columns", opts.drop_zero_columns),
+        ("--transpose", opts.transpose),
+        ("--with-meta", opts.with_meta),
+        ("--watch", opts.watch.is_some()),
+        ("--dry-run", opts.dry_run),
+        ("--explain-fields", opts.explain_fields),
+    ];
+    for (name, given) in unsupported.iter() {
+        if *given {
+            bail!("{} is not supported by raw-model", name);
+        }
+    }
+    Ok(())
+}
+
+impl Dumper for RawModel {
+    fn dump_model(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+        output: &mut dyn Write,
+        round: &mut usize,
+        comma_flag: bool,
+    ) -> Result<IterExecResult> {
+        let value = match self.kind {
+            RawModelKind::System => serde_json::to_value(&model.system)?,
+            RawModelKind::Process => serde_json::to_value(&model.process)?,
+            RawModelKind::Cgroup => serde_json::to_value(&model.cgroup)?,
+        };
+        let par = json!({
+            "timestamp": ctx.timestamp,
+            "model": value,
+        });
+        if comma_flag {
+            write!(output, ",{}", par)?;
+        } else {
+            write!(output, "{}", par)?;
+        }
+
+        *round += 1;
+
+        Ok(IterExecResult::Success)
+    }
+}
@@ -48,9 +48,7 @@ impl Dumper for System {
                     ctx,
                     &model.system,
                     *round,
-                    self.opts.repeat_title,
-                    self.opts.disable_title,
-                    self.opts.raw
+                    &print::RawOptions::new(&self.opts),
                 )
             )?,
             Some(OutputFormat::Csv) => write!(
@@ -62,13 +60,30 @@ impl Dumper for System {
                     &model.system,
                     *round,
                     self.opts.disable_title,
-                    self.opts.raw
+                    self.opts.raw,
+                    self.opts.na_string.as_deref()
                 )
             )?,
             Some(OutputFormat::KeyVal) => write!(
                 output,
                 "{}",
-                print::dump_kv(&self.fields, ctx, &model.system, self.opts.raw)
+                print::dump_kv(
+                    &self.fields,
+                    ctx,
+                    &model.system,
+                    self.opts.raw,
+                    self.opts.na_string.as_deref()
+                )
+            )?,
+            Some(OutputFormat::Graphite) => write!(
+                output,
+                "{}",
+                print::dump_graphite(
+                    &self.fields,
+                    ctx,
+                    &model.system,
+                    &format!("{}.system", print::GRAPHITE_ROOT)
+                )
             )?,
             Some(OutputFormat::Json) => {
                 let par = print::dump_json(&self.fields, ctx, &model.system, self.opts.raw);
@@ -84,4 +99,16 @@ impl Dumper for System {
 
         Ok(IterExecResult::Success)
     }
+
+    fn field_names(&self) -> Vec<String> {
+        self.fields.iter().map(ToString::to_string).collect()
+    }
+
+    fn quantize_fields(&self) -> Result<Vec<quantize::QuantizeField>> {
+        quantize::quantize_fields(&self.fields, &[])
+    }
+
+    fn nonzero_columns(&self) -> Vec<nonzero::Column> {
+        nonzero::columns(&self.fields)
+    }
 }
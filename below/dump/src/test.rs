@@ -13,9 +13,12 @@
 // limitations under the License.
 
 use serde_json::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
 
 use super::*;
-use command::{expand_fields, GeneralOpt, OutputFormat};
+use command::{expand_fields, AggField, DumpOptionField, GeneralOpt, OutputFormat};
 use common::logutil::get_logger;
 use model::{Collector, EnumIter, Queriable};
 use print::HasRenderConfigForDump;
@@ -23,6 +26,26 @@ use tmain::Dumper;
 
 use tempdir::TempDir;
 
+/// Parse the arguments of `below dump`, e.g. ["process", "-b", "10:00"].
+fn parse_dump(args: &[&str]) -> clap::Result<DumpCommand> {
+    use clap::Parser;
+    DumpCommand::try_parse_from(std::iter::once("dump").chain(args.iter().copied()))
+}
+
+/// Parse the arguments of `below dump` and get the options of the subcommand.
+fn parse_opts(args: &[&str]) -> clap::Result<GeneralOpt> {
+    parse_dump(args).map(|cmd| match cmd {
+        DumpCommand::System { opts, .. }
+        | DumpCommand::Disk { opts, .. }
+        | DumpCommand::Process { opts, .. }
+        | DumpCommand::Cgroup { opts, .. }
+        | DumpCommand::Iface { opts, .. }
+        | DumpCommand::Network { opts, .. }
+        | DumpCommand::Transport { opts, .. }
+        | DumpCommand::RawModel { opts, .. } => opts,
+    })
+}
+
 #[test]
 // Test correctness of system decoration
 fn test_dump_sys_content() {
@@ -51,7 +74,10 @@ fn test_dump_sys_content() {
         .expect("Fail to get model");
     let mut system_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
     system_dumper
         .dump_model(&ctx, &model, &mut system_content, &mut round, false)
         .expect("Failed to dump system model");
@@ -63,7 +89,7 @@ fn test_dump_sys_content() {
 
     for dump_field in fields.iter() {
         match dump_field {
-            DumpField::Common(_) => continue,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => continue,
             DumpField::FieldId(field_id) => {
                 let rc = model::SystemModel::get_render_config_for_dump(&field_id);
                 assert_eq!(
@@ -96,7 +122,7 @@ fn test_dump_sys_titles() {
             }),
         )
         .filter_map(|dump_field| match dump_field {
-            DumpField::Common(_) => None,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => None,
             DumpField::FieldId(field_id) => {
                 let rc = model::SystemModel::get_render_config_for_dump(&field_id);
                 Some(rc.render_title(false))
@@ -119,6 +145,7 @@ fn test_dump_sys_titles() {
         "Total",
         "Free",
         "Available",
+        "Headroom",
         "Buffers",
         "Cached",
         "Swap Cached",
@@ -130,6 +157,9 @@ fn test_dump_sys_titles() {
         "Mlocked",
         "Swap Total",
         "Swap Free",
+        "Zswap Total",
+        "Zswap Compressed",
+        "Zswap Pool",
         "Dirty",
         "Writeback",
         "Anon Pages",
@@ -199,7 +229,7 @@ fn test_dump_process_content() {
     let mut opts: GeneralOpt = Default::default();
     let fields = command::expand_fields(command::DEFAULT_PROCESS_FIELDS, true);
     opts.output_format = Some(OutputFormat::Json);
-    let process_dumper = process::Process::new(&opts, None, fields.clone());
+    let process_dumper = process::Process::new(&opts, None, None, false, None, fields.clone());
 
     // update model again to populate cpu and io data
     let model = collector
@@ -207,7 +237,10 @@ fn test_dump_process_content() {
         .expect("Fail to get model");
     let mut process_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
     process_dumper
         .dump_model(&ctx, &model, &mut process_content, &mut round, false)
         .expect("Failed to dump process model");
@@ -229,7 +262,7 @@ fn test_dump_process_content() {
 
         for dump_field in fields.iter() {
             match dump_field {
-                DumpField::Common(_) => continue,
+                DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => continue,
                 DumpField::FieldId(field_id) => {
                     let rc = model::SingleProcessModel::get_render_config_for_dump(&field_id);
                     assert_eq!(
@@ -259,7 +292,7 @@ fn test_dump_proc_titles() {
     let titles = expand_fields(command::DEFAULT_PROCESS_FIELDS, true)
         .iter()
         .filter_map(|dump_field| match dump_field {
-            DumpField::Common(_) => None,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => None,
             DumpField::FieldId(field_id) => {
                 let rc = model::SingleProcessModel::get_render_config_for_dump(&field_id);
                 Some(rc.render_title(false))
@@ -291,6 +324,8 @@ fn test_dump_proc_titles() {
         "Writes",
         "RW",
         "Uptime(sec)",
+        "Start Time",
+        "Age",
         "Cgroup",
         "Cmdline",
         "Exe Path",
@@ -321,12 +356,18 @@ fn test_dump_proc_select() {
     let process_dumper = process::Process::new(
         &opts,
         Some(model::SingleProcessModelFieldId::Pid),
+        None,
+        false,
+        None,
         fields.clone(),
     );
 
     let mut process_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
     process_dumper
         .dump_model(&ctx, &model, &mut process_content, &mut round, false)
         .expect("Failed to dump process model");
@@ -343,6 +384,9 @@ fn test_dump_proc_select() {
     let process_dumper = process::Process::new(
         &opts,
         Some(model::SingleProcessModelFieldId::Pid),
+        None,
+        false,
+        None,
         fields.clone(),
     );
 
@@ -369,8 +413,14 @@ fn test_dump_proc_select() {
     // test select sort top
     opts.sort = false;
     opts.rsort = true;
-    let process_dumper =
-        process::Process::new(&opts, Some(model::SingleProcessModelFieldId::Pid), fields);
+    let process_dumper = process::Process::new(
+        &opts,
+        Some(model::SingleProcessModelFieldId::Pid),
+        None,
+        false,
+        None,
+        fields,
+    );
 
     process_content = Vec::new();
     round = 0;
@@ -396,7 +446,7 @@ fn test_dump_proc_select() {
 fn traverse_cgroup_tree(model: &model::CgroupModel, jval: &Value) {
     for dump_field in expand_fields(command::DEFAULT_CGROUP_FIELDS, true) {
         match dump_field {
-            DumpField::Common(_) => continue,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => continue,
             DumpField::FieldId(field_id) => {
                 let rc = model::SingleCgroupModel::get_render_config_for_dump(&field_id);
                 assert_eq!(
@@ -433,7 +483,7 @@ fn test_dump_cgroup_content() {
     let mut opts: GeneralOpt = Default::default();
     let fields = command::expand_fields(command::DEFAULT_CGROUP_FIELDS, true);
     opts.output_format = Some(OutputFormat::Json);
-    let cgroup_dumper = cgroup::Cgroup::new(&opts, None, fields);
+    let cgroup_dumper = cgroup::Cgroup::new(&opts, None, None, false, false, fields);
 
     // update model again to populate cpu and io data
     let model = collector
@@ -441,7 +491,10 @@ fn test_dump_cgroup_content() {
         .expect("Fail to get model");
     let mut cgroup_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
     cgroup_dumper
         .dump_model(&ctx, &model, &mut cgroup_content, &mut round, false)
         .expect("Failed to dump cgroup model");
@@ -458,7 +511,7 @@ fn test_dump_cgroup_titles() {
     let titles = expand_fields(command::DEFAULT_CGROUP_FIELDS, true)
         .iter()
         .filter_map(|dump_field| match dump_field {
-            DumpField::Common(_) => None,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => None,
             DumpField::FieldId(field_id) => {
                 let rc = model::SingleCgroupModel::get_render_config_for_dump(&field_id);
                 Some(rc.render_title(false))
@@ -474,8 +527,15 @@ fn test_dump_cgroup_titles() {
         "Nr Period",
         "Nr Throttled",
         "Throttled Pct",
+        "CPU Usage(usec)",
+        "CPU User(usec)",
+        "CPU Sys(usec)",
+        "Nr Periods",
+        "Nr Throttled(total)",
+        "Throttled(usec)",
         "Mem Total",
         "Mem Swap",
+        "Mem Zswap",
         "Mem Anon",
         "Mem File",
         "Kernel Stack",
@@ -501,6 +561,10 @@ fn test_dump_cgroup_titles() {
         "Pgrefill",
         "Pgscan",
         "Pgsteal",
+        "Pgscan Kswapd",
+        "Pgscan Direct",
+        "Pgsteal Kswapd",
+        "Pgsteal Direct",
         "Pgactivate",
         "Pgdeactivate",
         "Pglazyfree",
@@ -508,11 +572,18 @@ fn test_dump_cgroup_titles() {
         "THP Fault Alloc",
         "THP Collapse Alloc",
         "Memory High",
+        "Memory Max",
+        "Memory Headroom",
         "Events Low",
         "Events High",
         "Events Max",
         "Events OOM",
         "Events Kill",
+        "Events Low/s",
+        "Events High/s",
+        "Events Max/s",
+        "Events OOM/s",
+        "Events Kill/s",
         "RBytes",
         "WBytes",
         "R I/O",
@@ -520,11 +591,29 @@ fn test_dump_cgroup_titles() {
         "DBytes",
         "D I/O",
         "RW Total",
+        "IO Cost Usage",
+        "IO Cost Vrate",
+        "IO Cost Debt",
         "CPU Pressure",
         "I/O Some Pressure",
         "I/O Pressure",
         "Memory Some Pressure",
         "Memory Pressure",
+        "CPU Some Pressure Avg10",
+        "CPU Some Pressure Avg60",
+        "CPU Some Pressure Avg300",
+        "I/O Some Pressure Avg10",
+        "I/O Some Pressure Avg60",
+        "I/O Some Pressure Avg300",
+        "I/O Full Pressure Avg10",
+        "I/O Full Pressure Avg60",
+        "I/O Full Pressure Avg300",
+        "Memory Some Pressure Avg10",
+        "Memory Some Pressure Avg60",
+        "Memory Some Pressure Avg300",
+        "Memory Full Pressure Avg10",
+        "Memory Full Pressure Avg60",
+        "Memory Full Pressure Avg300",
     ];
     assert_eq!(titles, expected_titles);
 }
@@ -542,7 +631,7 @@ fn test_dump_iface_content() {
     let mut opts: GeneralOpt = Default::default();
     let fields = command::expand_fields(command::DEFAULT_IFACE_FIELDS, true);
     opts.output_format = Some(OutputFormat::Json);
-    let iface_dumper = iface::Iface::new(&opts, None, fields.clone());
+    let iface_dumper = iface::Iface::new(&opts, None, None, fields.clone());
 
     // update model again to populate net data
     let model = collector
@@ -550,7 +639,10 @@ fn test_dump_iface_content() {
         .expect("Fail to get model");
     let mut iface_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
     iface_dumper
         .dump_model(&ctx, &model, &mut iface_content, &mut round, false)
         .expect("Failed to dump iface model");
@@ -572,7 +664,7 @@ fn test_dump_iface_content() {
 
         for dump_field in fields.iter() {
             match dump_field {
-                DumpField::Common(_) => continue,
+                DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => continue,
                 DumpField::FieldId(field_id) => {
                     let rc = model::SingleNetModel::get_render_config_for_dump(&field_id);
                     assert_eq!(
@@ -602,7 +694,7 @@ fn test_dump_iface_titles() {
     let titles = expand_fields(command::DEFAULT_IFACE_FIELDS, true)
         .iter()
         .filter_map(|dump_field| match dump_field {
-            DumpField::Common(_) => None,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => None,
             DumpField::FieldId(field_id) => {
                 let rc = model::SingleNetModel::get_render_config_for_dump(&field_id);
                 Some(rc.render_title(false))
@@ -665,7 +757,10 @@ fn test_dump_network_content() {
         .expect("Fail to get model");
     let mut network_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
     network_dumper
         .dump_model(&ctx, &model, &mut network_content, &mut round, false)
         .expect("Failed to dump network model");
@@ -677,7 +772,7 @@ fn test_dump_network_content() {
 
     for dump_field in fields.iter() {
         match dump_field {
-            DumpField::Common(_) => continue,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => continue,
             DumpField::FieldId(field_id) => {
                 let rc = model::NetworkModel::get_render_config_for_dump(&field_id);
                 assert_eq!(
@@ -702,7 +797,7 @@ fn test_dump_network_titles() {
     let titles = expand_fields(command::DEFAULT_NETWORK_FIELDS, true)
         .iter()
         .filter_map(|dump_field| match dump_field {
-            DumpField::Common(_) => None,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => None,
             DumpField::FieldId(field_id) => {
                 let rc = model::NetworkModel::get_render_config_for_dump(&field_id);
                 Some(rc.render_title(false))
@@ -783,7 +878,10 @@ fn test_dump_transport_content() {
         .expect("Fail to get model");
     let mut transport_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
     transport_dumper
         .dump_model(&ctx, &model, &mut transport_content, &mut round, false)
         .expect("Failed to dump transport model");
@@ -795,7 +893,7 @@ fn test_dump_transport_content() {
 
     for dump_field in fields.iter() {
         match dump_field {
-            DumpField::Common(_) => continue,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => continue,
             DumpField::FieldId(field_id) => {
                 let rc = model::NetworkModel::get_render_config_for_dump(&field_id);
                 assert_eq!(
@@ -820,7 +918,7 @@ fn test_dump_transport_titles() {
     let titles = expand_fields(command::DEFAULT_TRANSPORT_FIELDS, true)
         .iter()
         .filter_map(|dump_field| match dump_field {
-            DumpField::Common(_) => None,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => None,
             DumpField::FieldId(field_id) => {
                 let rc = model::NetworkModel::get_render_config_for_dump(&field_id);
                 Some(rc.render_title(false))
@@ -840,6 +938,10 @@ fn test_dump_transport_titles() {
         "TcpInErrors",
         "TcpOutRsts/s",
         "TcpInCsumErrors",
+        "TcpTimeWait",
+        "TcpCloseWait",
+        "TcpSocketsInUse",
+        "TcpOrphanSockets",
         "UdpInPkts/s",
         "UdpNoPorts",
         "UdpInErrs",
@@ -872,7 +974,7 @@ fn test_dump_disk_content() {
     let mut opts: GeneralOpt = Default::default();
     let fields = command::expand_fields(command::DEFAULT_DISK_FIELDS, true);
     opts.output_format = Some(OutputFormat::Json);
-    let disk_dumper = disk::Disk::new(&opts, None, fields.clone());
+    let disk_dumper = disk::Disk::new(&opts, None, None, fields.clone());
 
     // update model again to populate disk data
     let model = collector
@@ -880,7 +982,10 @@ fn test_dump_disk_content() {
         .expect("Fail to get model");
     let mut disk_content: Vec<u8> = Vec::new();
     let mut round = 0;
-    let ctx = CommonFieldContext { timestamp: 0 };
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
     disk_dumper
         .dump_model(&ctx, &model, &mut disk_content, &mut round, false)
         .expect("Failed to dump disk model");
@@ -901,7 +1006,7 @@ fn test_dump_disk_content() {
 
         for dump_field in fields.iter() {
             match dump_field {
-                DumpField::Common(_) => continue,
+                DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => continue,
                 DumpField::FieldId(field_id) => {
                     let rc = model::SingleDiskModel::get_render_config_for_dump(&field_id);
                     assert_eq!(
@@ -931,7 +1036,7 @@ fn test_dump_disk_titles() {
     let titles = expand_fields(command::DEFAULT_DISK_FIELDS, true)
         .iter()
         .filter_map(|dump_field| match dump_field {
-            DumpField::Common(_) => None,
+            DumpField::Common(_) | DumpField::Expr(_) | DumpField::Renamed(..) => None,
             DumpField::FieldId(field_id) => {
                 let rc = model::SingleDiskModel::get_render_config_for_dump(&field_id);
                 Some(rc.render_title(false))
@@ -1024,3 +1129,2948 @@ proc = ["datetime", "mem.anon"]
         ))
     );
 }
+
+#[test]
+fn test_dump_process_limit_rows() {
+    let mut model = model::get_sample_model();
+    let spm = model.process.processes[&1].clone();
+    for pid in 2..5 {
+        let mut spm = spm.clone();
+        spm.pid = Some(pid);
+        model.process.processes.insert(pid, spm);
+    }
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::Csv),
+        disable_title: true,
+        limit_rows: Some(2),
+        ..Default::default()
+    };
+    let fields = command::expand_fields(command::DEFAULT_PROCESS_FIELDS, false);
+    let process_dumper = process::Process::new(&opts, None, None, false, None, fields);
+
+    let mut process_content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    process_dumper
+        .dump_model(&ctx, &model, &mut process_content, &mut round, false)
+        .expect("Failed to dump process model");
+    assert_eq!(round, 2);
+
+    let content = String::from_utf8(process_content).expect("Fail to decode dump output");
+    assert_eq!(content.lines().filter(|line| !line.is_empty()).count(), 2);
+
+    // Once the limit is reached, nothing more is emitted for later slices
+    let mut process_content: Vec<u8> = Vec::new();
+    process_dumper
+        .dump_model(&ctx, &model, &mut process_content, &mut round, false)
+        .expect("Failed to dump process model");
+    assert_eq!(round, 2);
+}
+
+#[test]
+fn test_dump_dry_run() {
+    let opts = GeneralOpt {
+        rsort: true,
+        top: 5,
+        output_format: Some(OutputFormat::KeyVal),
+        ..Default::default()
+    };
+    let fields = command::expand_fields(
+        &[
+            command::ProcessOptionField::Unit(ProcessField::Common(CommonField::Datetime)),
+            command::ProcessOptionField::Agg(command::ProcessAggField::Io),
+        ],
+        false,
+    );
+    let time_begin = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+    let time_end = time_begin + Duration::from_secs(60);
+
+    let mut plan: Vec<u8> = Vec::new();
+    print_dry_run(
+        &mut plan,
+        &opts,
+        Some("io.rbytes_per_sec".to_owned()),
+        &fields,
+        time_begin,
+        time_end,
+    )
+    .expect("Failed to print dry run");
+    let plan = String::from_utf8(plan).expect("Fail to decode dry run output");
+    let lines = plan.lines().collect::<Vec<_>>();
+
+    let expected_fields = std::iter::once("datetime".to_owned())
+        .chain(
+            command::ProcessAggField::Io
+                .expand(false)
+                .iter()
+                .map(|field_id| field_id.to_string()),
+        )
+        .collect::<Vec<_>>()
+        .join(", ");
+    assert_eq!(lines[0], format!("fields: {}", expected_fields));
+    assert!(lines[1].starts_with("begin: ") && lines[1].ends_with("(1600000000)"));
+    assert!(lines[2].starts_with("end: ") && lines[2].ends_with("(1600000060)"));
+    assert_eq!(
+        &lines[3..],
+        &[
+            "select: io.rbytes_per_sec",
+            "filter: none",
+            "sort: descending",
+            "top: 5",
+            "limit-rows: none",
+            "output-format: kv",
+            "output: stdout",
+        ]
+    );
+}
+
+#[test]
+fn test_dump_output_targets() {
+    let mut opts: GeneralOpt = Default::default();
+    assert_eq!(get_output_targets(&opts), vec![(OutputPath::Stdout, None)]);
+
+    // Only --output-<format> given, so nothing goes to stdout
+    opts.output_json = Some("out.json".to_owned());
+    opts.output_csv = Some("out.csv".to_owned());
+    assert_eq!(
+        get_output_targets(&opts),
+        vec![
+            (OutputPath::File("out.csv"), Some(OutputFormat::Csv)),
+            (OutputPath::File("out.json"), Some(OutputFormat::Json)),
+        ]
+    );
+
+    opts.output_format = Some(OutputFormat::KeyVal);
+    assert_eq!(
+        get_output_targets(&opts),
+        vec![
+            (OutputPath::Stdout, Some(OutputFormat::KeyVal)),
+            (OutputPath::File("out.csv"), Some(OutputFormat::Csv)),
+            (OutputPath::File("out.json"), Some(OutputFormat::Json)),
+        ]
+    );
+
+    // Slice files replace stdout and are kept along with --output-<format>
+    opts.output_format = None;
+    opts.split_by_slice = Some("slices".to_owned());
+    assert_eq!(
+        get_output_targets(&opts),
+        vec![
+            (OutputPath::SliceDir("slices"), None),
+            (OutputPath::File("out.csv"), Some(OutputFormat::Csv)),
+            (OutputPath::File("out.json"), Some(OutputFormat::Json)),
+        ]
+    );
+
+    opts.split_by_slice = None;
+    opts.output = Some("unix:/run/agent.sock".to_owned());
+    opts.output_csv = Some("fifo:/run/agent.fifo".to_owned());
+    assert_eq!(
+        get_output_targets(&opts),
+        vec![
+            (OutputPath::Socket("/run/agent.sock"), None),
+            (OutputPath::Fifo("/run/agent.fifo"), Some(OutputFormat::Csv)),
+            (OutputPath::File("out.json"), Some(OutputFormat::Json)),
+        ]
+    );
+}
+
+#[test]
+fn test_dump_output_socket() {
+    let model = model::get_sample_model();
+    let tempdir = TempDir::new("below_dump_output_socket").expect("Failed to create temp dir");
+    let socket_path = tempdir.path().join("dump.sock");
+    let listener =
+        std::os::unix::net::UnixListener::bind(&socket_path).expect("Failed to bind socket");
+    let socket = format!("unix:{}", socket_path.display());
+
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::Csv),
+        ..Default::default()
+    };
+    let fields = vec![
+        DumpField::Common(CommonField::Timestamp),
+        DumpField::FieldId(model::SystemModelFieldId::Hostname),
+    ];
+    let mut target = tmain::DumpTarget::new(
+        Box::new(system::System::new(&opts, fields)),
+        OutputPath::parse(&socket)
+            .open(false)
+            .expect("Failed to connect to socket"),
+        opts.output_format,
+    );
+    target
+        .dump_slice(
+            &CommonFieldContext {
+                timestamp: 100,
+                rank: None,
+            },
+            &model,
+        )
+        .expect("Failed to dump slice");
+
+    // The slice is flushed without closing the connection
+    let (stream, _) = listener.accept().expect("Failed to accept connection");
+    let mut lines = io::BufRead::lines(io::BufReader::new(stream));
+    let mut next_line = || lines.next().unwrap().expect("Failed to read socket");
+    assert_eq!(next_line(), "Timestamp,Hostname,");
+    assert_eq!(next_line(), format!("100,{},", model.system.hostname));
+    drop(target);
+
+    // Nothing is listening any more
+    drop(listener);
+    std::fs::remove_file(&socket_path).expect("Failed to remove socket");
+    assert!(OutputPath::parse(&socket).open(false).is_err());
+
+    // A regular file is not mistaken for a named pipe
+    let file_path = tempdir.path().join("regular");
+    std::fs::write(&file_path, "").expect("Failed to create file");
+    assert!(OutputPath::parse(&format!("fifo:{}", file_path.display()))
+        .open(false)
+        .is_err());
+}
+
+#[test]
+fn test_dump_output_path() {
+    assert_eq!(OutputPath::parse("-"), OutputPath::Stdout);
+    assert_eq!(expand_home("out.csv"), PathBuf::from("out.csv"));
+    assert_eq!(expand_home("~user/out.csv"), PathBuf::from("~user/out.csv"));
+    if let Some(home) = std::env::var_os("HOME") {
+        assert_eq!(
+            expand_home("~/dumps/out.csv"),
+            PathBuf::from(home).join("dumps/out.csv")
+        );
+    }
+
+    let tempdir = TempDir::new("below_dump_output_path").expect("Failed to create temp dir");
+    // An existing directory is not written to
+    let err = OutputPath::parse(tempdir.path().to_str().unwrap())
+        .open(false)
+        .err()
+        .expect("Directory should not be opened");
+    assert!(err.to_string().contains("is a directory"));
+
+    // Missing directories are only created with --mkdir
+    let file_path = tempdir.path().join("2023/01/02/out.csv");
+    let output = OutputPath::parse(file_path.to_str().unwrap());
+    let err = output
+        .open(false)
+        .err()
+        .expect("Missing directory should not be created");
+    assert!(err.to_string().contains("--mkdir"));
+    assert!(!tempdir.path().join("2023").exists());
+    output.open(true).expect("Failed to create output");
+    assert!(file_path.is_file());
+}
+
+#[test]
+fn test_dump_cgroup_total() {
+    use model::CgroupMemoryModelFieldId as Mem;
+    use model::SingleCgroupModelFieldId as CgroupFieldId;
+
+    let model = model::get_sample_model();
+    let dump = |output_format| {
+        let opts = GeneralOpt {
+            output_format: Some(output_format),
+            raw: true,
+            filter: Some(regex::Regex::new("slice$").expect("Failed to parse regex")),
+            ..Default::default()
+        };
+        let fields = vec![
+            CgroupField::FieldId(CgroupFieldId::Name),
+            CgroupField::FieldId(CgroupFieldId::Mem(Mem::Total)),
+        ];
+        let dumper =
+            cgroup::Cgroup::new(&opts, None, Some(CgroupFieldId::Name), true, false, fields);
+        let mut content: Vec<u8> = Vec::new();
+        let mut round = 0;
+        dumper
+            .dump_model(
+                &CommonFieldContext {
+                    timestamp: 0,
+                    rank: None,
+                },
+                &model,
+                &mut content,
+                &mut round,
+                false,
+            )
+            .expect("Failed to dump cgroup model");
+        (String::from_utf8(content).expect("Invalid utf8"), round)
+    };
+
+    // Only the matched cgroups are summed, not the root
+    let (csv, round) = dump(OutputFormat::Csv);
+    assert_eq!(
+        csv,
+        "Name,Mem Total,\n\
+        child_a.slice,8000,\n\
+        child_b.slice,30000000,\n\
+        <total>,30008000,\n"
+    );
+    assert_eq!(round, 3);
+
+    let (json, _) = dump(OutputFormat::Json);
+    let value: Value = serde_json::from_str(&format!("[{}]", json)).expect("Invalid json");
+    assert_eq!(
+        value[0]["children"].as_array().map(|arr| arr.len()),
+        Some(2)
+    );
+    // The total is marked as such, the cgroup tree isn't
+    assert_eq!(value.as_array().map(|arr| arr.len()), Some(2));
+    assert!(value[0].get("total").is_none());
+    assert_eq!(value[1]["total"], json!(true));
+    assert_eq!(value[1]["Name"], json!(cgroup::TOTAL_NAME));
+    assert_eq!(value[1]["Mem Total"], json!("30008000"));
+
+    let total = cgroup::get_total(&[]);
+    assert_eq!(total.full_path, cgroup::TOTAL_NAME);
+    assert!(total.memory.is_none());
+}
+
+#[test]
+fn test_dump_cgroup_per_device() {
+    use model::CgroupIoModelFieldId as Io;
+    use model::SingleCgroupModelFieldId as CgroupFieldId;
+
+    let name = CgroupField::FieldId(CgroupFieldId::Name);
+    let device = CgroupField::FieldId(CgroupFieldId::Device);
+    let rbytes = CgroupField::FieldId(CgroupFieldId::Io(Io::RbytesPerSec));
+    assert_eq!(
+        add_device_field(vec![name.clone(), rbytes.clone()]),
+        vec![name.clone(), device.clone(), rbytes.clone()]
+    );
+    assert_eq!(
+        add_device_field(vec![rbytes.clone()]),
+        vec![device.clone(), rbytes.clone()]
+    );
+    let with_device = vec![rbytes.clone(), device.clone()];
+    assert_eq!(add_device_field(with_device.clone()), with_device);
+
+    let mut model = model::get_sample_model();
+    let io = |rbytes_per_sec| model::CgroupIoModel {
+        rbytes_per_sec: Some(rbytes_per_sec),
+        ..Default::default()
+    };
+    model.cgroup.data.io = Some(
+        [("8:0".to_owned(), io(10.0)), ("8:16".to_owned(), io(30.0))]
+            .into_iter()
+            .collect(),
+    );
+    model.cgroup.data.io_total = Some(io(40.0));
+    let dump = |output_format, with_total| {
+        let opts = GeneralOpt {
+            output_format: Some(output_format),
+            raw: true,
+            filter: Some(regex::Regex::new("^<root>$").expect("Failed to parse regex")),
+            ..Default::default()
+        };
+        let fields = add_device_field(vec![name.clone(), rbytes.clone()]);
+        let dumper = cgroup::Cgroup::new(
+            &opts,
+            None,
+            Some(CgroupFieldId::Name),
+            with_total,
+            true,
+            fields,
+        );
+        let mut content: Vec<u8> = Vec::new();
+        let mut round = 0;
+        dumper
+            .dump_model(
+                &CommonFieldContext {
+                    timestamp: 0,
+                    rank: None,
+                },
+                &model,
+                &mut content,
+                &mut round,
+                false,
+            )
+            .expect("Failed to dump cgroup model");
+        String::from_utf8(content).expect("Invalid utf8")
+    };
+
+    assert_eq!(
+        dump(OutputFormat::Csv, false),
+        "Name,Device,RBytes,\n\
+        <root>,8:0,10,\n\
+        <root>,8:16,30,\n"
+    );
+    // The total is broken down by device as well
+    assert_eq!(
+        dump(OutputFormat::Csv, true),
+        "Name,Device,RBytes,\n\
+        <root>,8:0,10,\n\
+        <root>,8:16,30,\n\
+        <total>,8:0,10,\n\
+        <total>,8:16,30,\n"
+    );
+
+    let value: Value =
+        serde_json::from_str(&dump(OutputFormat::Json, false)).expect("Invalid json");
+    assert_eq!(value["RBytes"], json!("40"));
+    assert_eq!(value["devices"][0]["Device"], json!("8:0"));
+    assert_eq!(value["devices"][1]["RBytes"], json!("30"));
+}
+
+#[test]
+fn test_dump_cgroup_frozen() {
+    use model::SingleCgroupModelFieldId as CgroupFieldId;
+
+    let mut model = model::get_sample_model();
+    model.cgroup.data.frozen = Some(true);
+    let fields = vec![
+        CgroupField::FieldId(CgroupFieldId::Frozen),
+        CgroupField::FieldId(CgroupFieldId::NrDyingDescendants),
+    ];
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    let field = |raw| fields[0].dump_field(&ctx, &model.cgroup.data, raw, None, None, false);
+    assert_eq!(field(false), "yes");
+    assert_eq!(field(true), "true");
+
+    // Bools go to json as such
+    let value = print::dump_json(&fields, &ctx, &model.cgroup.data, false);
+    assert_eq!(value["Frozen"], json!(true));
+    assert_eq!(value["Nr Dying Descendants"], json!("1"));
+}
+
+#[test]
+fn test_dump_cgroup_controllers() {
+    let model = model::get_sample_model();
+    let fields = vec![
+        CgroupField::FieldId(model::SingleCgroupModelFieldId::Name),
+        CgroupField::FieldId(model::SingleCgroupModelFieldId::Controllers),
+    ];
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+
+    // Comma-joined in text formats
+    let kv = print::dump_kv(&fields, &ctx, &model.cgroup.data, false, None);
+    assert!(kv.contains("Controllers: cpuset,cpu,io,memory,pids\n"));
+
+    // Array in json
+    let json = print::dump_json(&fields, &ctx, &model.cgroup.data, false);
+    assert_eq!(
+        json["Controllers"],
+        serde_json::json!(["cpuset", "cpu", "io", "memory", "pids"])
+    );
+}
+
+#[test]
+fn test_dump_truncate() {
+    let model = model::get_sample_model();
+    let mut spm = model.process.processes[&1].clone();
+    spm.comm = Some("worker_pool_scheduler_thread_42".to_owned());
+    let fields = vec![
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Comm),
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Pid),
+    ];
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    let lines = |width, side| {
+        let truncate = command::Truncate { width, side };
+        print::dump_raw(
+            &fields,
+            &ctx,
+            &spm,
+            0,
+            &print::RawOptions {
+                truncate,
+                ..Default::default()
+            },
+        )
+        .lines()
+        .map(|line| line.to_owned())
+        .collect::<Vec<_>>()
+    };
+
+    // Cut to the column width of comm by default
+    let default = lines(None, None);
+    assert!(default[1].starts_with("worker_pool_scheduler_thread_4 1 "));
+    // Title and values are cut to the same width, numbers are not
+    let narrow = lines(Some(12), None);
+    assert_eq!(narrow[0], "Comm         Pid        ");
+    assert_eq!(narrow[1], "worker_pool_ 1          ");
+    assert_eq!(
+        lines(Some(12), Some(command::TruncateSide::Left))[1],
+        "er_thread_42 1          "
+    );
+    // Whole values with 0
+    assert!(lines(Some(0), None)[1].starts_with("worker_pool_scheduler_thread_42 1 "));
+
+    // Csv is never truncated
+    let csv = print::dump_csv(&fields, &ctx, &spm, 1, true, false, None);
+    assert_eq!(csv, "worker_pool_scheduler_thread_42,1,\n");
+}
+
+#[test]
+fn test_dump_color_threshold() {
+    let model = model::get_sample_model();
+    let spm = &model.process.processes[&1];
+    let fields = vec![
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Comm),
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Cpu(
+            model::ProcessCpuModelFieldId::UsagePct,
+        )),
+    ];
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    let dump_with_threshold = |threshold: &str| {
+        let thresholds =
+            vec![command::ColorThreshold::from_str(threshold).expect("Failed to parse threshold")];
+        print::dump_raw(
+            &fields,
+            &ctx,
+            spm,
+            1,
+            &print::RawOptions {
+                disable_title: true,
+                color_thresholds: &thresholds,
+                ..Default::default()
+            },
+        )
+    };
+
+    // pid 1 uses 1.0% cpu in the sample model
+    let hot = dump_with_threshold("cpu.usage_pct=0.5");
+    assert_eq!(hot.matches("\x1b[31m").count(), 1);
+    assert!(hot.contains("\x1b[0m"));
+    assert!(!dump_with_threshold("cpu.usage_pct=80").contains('\x1b'));
+    // Non numeric fields are never highlighted
+    assert!(!dump_with_threshold("comm=0").contains('\x1b'));
+    // Nothing is highlighted unless --color resolves to always
+    let mut opts = GeneralOpt {
+        color_threshold: vec![command::ColorThreshold::from_str("cpu.usage_pct=0.5").unwrap()],
+        ..Default::default()
+    };
+    assert!(opts.active_color_thresholds().is_empty());
+    opts.color = Some(command::ColorWhen::Always);
+    assert_eq!(opts.active_color_thresholds().len(), 1);
+
+    assert!(command::ColorThreshold::from_str("cpu.usage_pct").is_err());
+    assert!(command::ColorThreshold::from_str("cpu.usage_pct=hot").is_err());
+}
+
+#[test]
+fn test_dump_color_opts_parse() {
+    let opts = parse_opts(&[
+        "process",
+        "-b",
+        "10:00",
+        "--color",
+        "always",
+        "--color-threshold",
+        "cpu.usage_pct=80",
+        "--color-threshold",
+        "mem.rss_bytes=1000000",
+    ])
+    .expect("Failed to parse dump command");
+    assert_eq!(opts.color, Some(command::ColorWhen::Always));
+    assert_eq!(opts.color_threshold.len(), 2);
+    assert_eq!(opts.color_threshold[1].field, "mem.rss_bytes");
+}
+
+#[test]
+fn test_dump_select_aggregated_field() {
+    let parse_select = |subcommand: &str, select: &str| {
+        parse_dump(&[subcommand, "-b", "10:00", "--select", select])
+    };
+
+    match parse_select("cgroup", "cpu").expect("Failed to parse dump command") {
+        DumpCommand::Cgroup { select, .. } => assert_eq!(
+            select,
+            Some(model::SingleCgroupModelFieldId::Cpu(
+                model::CgroupCpuModelFieldId::UsagePct
+            ))
+        ),
+        _ => panic!("Expected cgroup command"),
+    }
+    match parse_select("process", "mem").expect("Failed to parse dump command") {
+        DumpCommand::Process { select, .. } => assert_eq!(
+            select,
+            Some(model::SingleProcessModelFieldId::Mem(
+                model::ProcessMemoryModelFieldId::RssBytes
+            ))
+        ),
+        _ => panic!("Expected process command"),
+    }
+    // Plain fields still work
+    match parse_select("process", "io.rbytes_per_sec").expect("Failed to parse dump command") {
+        DumpCommand::Process { select, .. } => assert_eq!(
+            select,
+            Some(model::SingleProcessModelFieldId::Io(
+                model::ProcessIoModelFieldId::RbytesPerSec
+            ))
+        ),
+        _ => panic!("Expected process command"),
+    }
+    // Pressure has no obvious representative
+    assert!(parse_select("cgroup", "pressure").is_err());
+}
+
+#[test]
+fn test_dump_split_by_slice() {
+    let model = model::get_sample_model();
+    let tempdir = TempDir::new("below_dump_split").expect("Failed to create temp dir");
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::Json),
+        ..Default::default()
+    };
+    let fields = vec![DumpField::FieldId(model::SystemModelFieldId::Hostname)];
+    let mut target = tmain::DumpTarget::new_split_by_slice(
+        Box::new(system::System::new(&opts, fields)),
+        tempdir.path().to_path_buf(),
+        opts.output_format,
+    );
+
+    // Slices sharing a timestamp don't overwrite each other
+    for timestamp in [100, 100, 200] {
+        let ctx = CommonFieldContext {
+            timestamp,
+            rank: None,
+        };
+        target
+            .dump_slice(&ctx, &model)
+            .expect("Failed to dump slice");
+    }
+    let mut files: Vec<String> = std::fs::read_dir(tempdir.path())
+        .expect("Failed to read temp dir")
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(files, vec!["100-1.json", "100.json", "200.json"]);
+
+    // Each file is a json document on its own
+    let content = std::fs::read_to_string(tempdir.path().join("200.json")).unwrap();
+    let value: Value = serde_json::from_str(&content).expect("Failed to parse slice file");
+    assert_eq!(value.as_array().map(|arr| arr.len()), Some(1));
+
+    // --output and --limit-rows don't apply to a directory of files
+    let split = |args: &[&str]| {
+        parse_dump(
+            &[
+                &["system", "-b", "10:00", "--split-by-slice", "slices"],
+                args,
+            ]
+            .concat(),
+        )
+    };
+    assert!(split(&[]).is_ok());
+    assert!(split(&["--output", "out.json"]).is_err());
+    assert!(split(&["--limit-rows", "10"]).is_err());
+}
+
+#[test]
+fn test_dump_with_meta() {
+    let model = model::get_sample_model();
+    let tempdir = TempDir::new("below_dump_with_meta").expect("Failed to create temp dir");
+    let dump = |output_format| {
+        let opts = GeneralOpt {
+            output_format: Some(output_format),
+            ..Default::default()
+        };
+        let fields = vec![
+            DumpField::Common(CommonField::Timestamp),
+            DumpField::FieldId(model::SystemModelFieldId::Hostname),
+        ];
+        let mut target = tmain::DumpTarget::new_split_by_slice(
+            Box::new(system::System::new(&opts, fields)),
+            tempdir.path().to_path_buf(),
+            opts.output_format,
+        )
+        .with_meta()
+        .expect("Failed to add meta");
+        target
+            .dump_slice(
+                &CommonFieldContext {
+                    timestamp: 100,
+                    rank: None,
+                },
+                &model,
+            )
+            .expect("Failed to dump slice");
+        std::fs::read_to_string(tempdir.path().join(format!("100.{}", output_format)))
+            .expect("Failed to read slice file")
+    };
+
+    let value: Value = serde_json::from_str(&dump(OutputFormat::Json)).expect("Invalid json");
+    let meta = &value[0]["_meta"];
+    assert_eq!(meta["version"], json!(env!("CARGO_PKG_VERSION")));
+    assert_eq!(meta["fields"], json!(["timestamp", "hostname"]));
+    assert!(meta["generated_at"].as_u64().is_some());
+    assert_eq!(value[1]["Timestamp"], json!("100"));
+
+    let csv = dump(OutputFormat::Csv);
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[0],
+        format!("# version: {}", env!("CARGO_PKG_VERSION"))
+    );
+    assert_eq!(lines[1], "# fields: timestamp,hostname");
+    assert!(lines[2].starts_with("# generated_at: "));
+    assert_eq!(lines[3], "Timestamp,Hostname,");
+
+    let opts: GeneralOpt = Default::default();
+    let raw = tmain::DumpTarget::new(
+        Box::new(system::System::new(&opts, vec![])),
+        Box::new(io::sink()),
+        None,
+    );
+    assert!(raw.with_meta().is_err());
+}
+
+#[test]
+fn test_dump_histogram() {
+    let buckets = histogram::Buckets::new(&[0.0, 1.0, 2.5, 9.0, 10.0], 2, None);
+    assert_eq!((buckets.lo, buckets.hi), (0.0, 10.0));
+    assert_eq!(buckets.counts, vec![3, 2]);
+    assert_eq!((buckets.below, buckets.above), (0, 0));
+
+    let range = command::HistRange::from_str("1:5").expect("Failed to parse range");
+    let buckets = histogram::Buckets::new(&[0.0, 1.0, 2.5, 5.0, 9.0], 4, Some(range));
+    assert_eq!(buckets.counts, vec![1, 1, 0, 1]);
+    assert_eq!((buckets.below, buckets.above), (1, 1));
+    assert!(command::HistRange::from_str("5:1").is_err());
+    assert!(command::HistRange::from_str("5").is_err());
+
+    let model = model::get_sample_model();
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    let hist = |field: &str| {
+        let opts = GeneralOpt {
+            output_format: Some(OutputFormat::Json),
+            raw: true,
+            ..Default::default()
+        };
+        let fields = vec![get_histogram_field::<
+            model::SingleProcessModelFieldId,
+            command::ProcessAggField,
+        >(field)?];
+        let dumper = histogram::Histogram::new(
+            Box::new(process::Process::new(
+                &opts, None, None, false, None, fields,
+            )),
+            field.to_owned(),
+            histogram::DEFAULT_BINS,
+            None,
+        );
+        let mut round = 0;
+        let mut output: Vec<u8> = Vec::new();
+        let res = dumper.dump_model(&ctx, &model, &mut output, &mut round, false)?;
+        // Nothing is written before the end
+        assert!(res == IterExecResult::Skip);
+        assert!(output.is_empty());
+        dumper.finish(&mut output)?;
+        Ok::<_, Error>(String::from_utf8(output).unwrap())
+    };
+
+    let res = hist("cpu.usage_pct").expect("Failed to dump histogram");
+    let mut lines = res.lines();
+    assert_eq!(
+        lines.next(),
+        Some(
+            format!(
+                "Histogram of cpu.usage_pct ({} values)",
+                model.process.processes.len()
+            )
+            .as_str()
+        )
+    );
+    assert_eq!(lines.count(), histogram::DEFAULT_BINS);
+    // Aggregated fields are counted by their representative field
+    assert_eq!(hist("cpu").unwrap(), res.replace("cpu.usage_pct", "cpu"));
+    assert!(hist("comm").is_err());
+}
+
+#[test]
+fn test_dump_fields_all_and_groups() {
+    let fields: Vec<command::ProcessOptionField> = ["comm", "cpu", "mem.rss_bytes"]
+        .iter()
+        .map(|s| command::ProcessOptionField::from_str(s).expect("Failed to parse field"))
+        .collect();
+    // Aggregated fields are expanded in place
+    assert_eq!(
+        expand_fields(&fields, false),
+        vec![
+            ProcessField::FieldId(model::SingleProcessModelFieldId::Comm),
+            ProcessField::FieldId(model::SingleProcessModelFieldId::Cpu(
+                model::ProcessCpuModelFieldId::UsagePct
+            )),
+            ProcessField::FieldId(model::SingleProcessModelFieldId::Mem(
+                model::ProcessMemoryModelFieldId::RssBytes
+            )),
+        ]
+    );
+    assert!(!command::has_all_field(Some(fields.as_slice())));
+    assert!(!command::has_all_field::<_, command::ProcessAggField>(None));
+
+    let all = command::ProcessOptionField::from_str("all").expect("Failed to parse all");
+    assert_eq!(all, DumpOptionField::All);
+    assert_eq!(all.to_string(), "all");
+    assert!(command::has_all_field(Some(&[all][..])));
+}
+
+#[test]
+fn test_dump_fields_glob() {
+    let names = |pattern: &str| {
+        let field = command::CgroupOptionField::from_str(pattern)?;
+        assert_eq!(field.to_string(), pattern);
+        Ok::<_, Error>(
+            expand_fields(&[field], false)
+                .iter()
+                .map(|field| field.to_string())
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    let pg = names("mem.pg*").expect("Failed to parse glob");
+    assert!(pg.len() > 5);
+    assert!(pg.iter().all(|name| name.starts_with("mem.pg")));
+    assert!(pg.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(pg.contains(&"mem.pgfault".to_owned()));
+    assert_eq!(
+        names("mem.pg*fault").unwrap(),
+        vec!["mem.pgfault", "mem.pgmajfault"]
+    );
+    // Common fields and fields outside of aggregated fields are matched too
+    assert_eq!(names("date*").unwrap(), vec!["datetime"]);
+    assert_eq!(names("*_path").unwrap(), vec!["full_path"]);
+    assert!(names("mem.no_such_*").is_err());
+}
+
+#[test]
+fn test_dump_empty_result() {
+    let logger = get_logger();
+    let dir = TempDir::new("below_dump_empty").expect("Failed to create temp dir");
+    let mut store = store::StoreWriter::new(
+        logger.clone(),
+        &dir,
+        store::CompressionMode::None,
+        store::Format::Cbor,
+    )
+    .expect("Failed to create store");
+    let time_begin = SystemTime::now() - Duration::from_secs(60);
+    for secs in [0, 5] {
+        store
+            .put(
+                time_begin + Duration::from_secs(secs),
+                &store::DataFrame {
+                    sample: Default::default(),
+                },
+            )
+            .expect("Failed to store sample");
+    }
+    let time_end = time_begin + Duration::from_secs(5);
+
+    // There are no processes in an empty sample
+    let dump = |fail_on_empty| {
+        let opts: GeneralOpt = Default::default();
+        let fields = command::expand_fields(command::DEFAULT_PROCESS_FIELDS, false);
+        let mut advance =
+            store::advance::new_advance_local(logger.clone(), dir.path().to_path_buf(), time_begin);
+        advance.initialize();
+        let target = tmain::DumpTarget::new(
+            Box::new(process::Process::new(
+                &opts, None, None, false, None, fields,
+            )),
+            Box::new(io::sink()),
+            None,
+        );
+        let (_err_sender, errs) = std::sync::mpsc::channel();
+        tmain::dump_timeseries(
+            advance,
+            time_begin,
+            time_end,
+            vec![target],
+            &tmain::TimeseriesOpts {
+                fail_on_empty,
+                ..Default::default()
+            },
+            &errs,
+        )
+    };
+    let err = dump(false).expect_err("Expected empty result");
+    assert!(err.is::<EmptyResult>());
+    let err = dump(true).expect_err("Expected empty result to fail");
+    assert!(!err.is::<EmptyResult>());
+    assert!(err.to_string().contains("--fail-on-empty"));
+}
+
+#[test]
+fn test_dump_move_field() {
+    let parse = |args: &[&str]| {
+        parse_opts(&[&["process", "-b", "10:00"], args].concat()).expect("Failed to parse args")
+    };
+    let fields = expand_fields(command::DEFAULT_PROCESS_FIELDS, false);
+    let move_fields =
+        |opts: &GeneralOpt| move_fields::<_, command::ProcessAggField>(fields.clone(), opts, false);
+
+    let opts = parse(&[
+        "--move-field",
+        "comm",
+        "first",
+        "--move-field",
+        "datetime",
+        "last",
+        "--move-field",
+        "pid",
+        "first",
+    ]);
+    let moved = move_fields(&opts).expect("Failed to move fields");
+    assert_eq!(moved.len(), fields.len());
+    assert_eq!(
+        &moved[..2],
+        &[
+            ProcessField::FieldId(model::SingleProcessModelFieldId::Comm),
+            ProcessField::FieldId(model::SingleProcessModelFieldId::Pid),
+        ]
+    );
+    assert_eq!(
+        moved.last(),
+        Some(&ProcessField::Common(CommonField::Datetime))
+    );
+
+    // Aggregated fields move everything they expand to
+    let opts = parse(&["--move-field", "io", "first"]);
+    let moved = move_fields(&opts).expect("Failed to move fields");
+    assert_eq!(
+        &moved[..2],
+        &[
+            ProcessField::FieldId(model::SingleProcessModelFieldId::Io(
+                model::ProcessIoModelFieldId::RbytesPerSec
+            )),
+            ProcessField::FieldId(model::SingleProcessModelFieldId::Io(
+                model::ProcessIoModelFieldId::WbytesPerSec
+            )),
+        ]
+    );
+
+    // Fields must be in the resolved set
+    assert!(move_fields(&parse(&["--move-field", "mem_some_pressure", "first"])).is_err());
+    assert!(move_fields(&parse(&["--move-field", "comm", "middle"])).is_err());
+}
+
+#[test]
+fn test_dump_expr() {
+    let parse = |args: &[&str]| parse_opts(&[&["process", "-b", "10:00"], args].concat());
+    let model = model::get_sample_model();
+    let spm = &model.process.processes[&1];
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    let fields = vec![ProcessField::FieldId(model::SingleProcessModelFieldId::Pid)];
+
+    let opts = parse(&[
+        "--expr",
+        "rss_per_cpu=mem.rss_bytes / cpu.usage_pct",
+        "--expr",
+        "anon_file = (mem.anon + mem.file) * 2 - -1",
+        "--expr",
+        "per_io=mem.anon/io.rbytes_per_sec",
+        "--move-field",
+        "anon_file",
+        "first",
+    ])
+    .expect("Failed to parse args");
+    let fields = add_expr_fields(fields.clone(), &opts).expect("Failed to add expr fields");
+    let fields = move_fields::<_, command::ProcessAggField>(fields, &opts, false)
+        .expect("Failed to move fields");
+    assert_eq!(
+        fields.iter().map(|f| f.to_string()).collect::<Vec<_>>(),
+        vec!["anon_file", "pid", "rss_per_cpu", "per_io"]
+    );
+    // Division by zero gives no value, empty in csv and null in json
+    assert_eq!(
+        print::dump_csv(&fields, &ctx, spm, 0, false, true, None),
+        "anon_file,Pid,rss_per_cpu,per_io,\n26000001,1,10000000,,\n"
+    );
+    assert_eq!(
+        print::dump_csv(&fields, &ctx, spm, 1, false, false, None),
+        "26000001.00,1,10000000.00,,\n"
+    );
+    assert_eq!(
+        print::dump_csv(&fields, &ctx, spm, 1, false, false, Some("N/A")),
+        "26000001.00,1,10000000.00,N/A,\n"
+    );
+    let value = print::dump_json(&fields, &ctx, spm, true);
+    assert_eq!(value["per_io"], Value::Null);
+    assert_eq!(value["rss_per_cpu"], json!("10000000"));
+
+    // Operator precedence and unary minus
+    let expr = expr::Expr::<String>::from_str("1 + 2 * 3 - -(4 - 6) / 2")
+        .expect("Failed to parse expression")
+        .resolve::<model::SingleProcessModelFieldId>()
+        .expect("Failed to resolve expression");
+    assert_eq!(expr.eval(spm), Some(6.0));
+    // Missing or non numeric fields give no value
+    let expr = expr::Expr::<String>::from_str("comm + 1")
+        .unwrap()
+        .resolve::<model::SingleProcessModelFieldId>()
+        .unwrap();
+    assert_eq!(expr.eval(spm), None);
+
+    assert!(parse(&["--expr", "mem.anon"]).is_err());
+    assert!(parse(&["--expr", "x=(mem.anon"]).is_err());
+    assert!(parse(&["--expr", "x=mem.anon +"]).is_err());
+    assert!(parse(&["--expr", "x=mem.anon % 2"]).is_err());
+    let opts = parse(&["--expr", "x=no_such_field"]).expect("Failed to parse args");
+    assert!(add_expr_fields::<model::SingleProcessModelFieldId>(vec![], &opts).is_err());
+}
+
+#[test]
+fn test_dump_transpose() {
+    let model = model::get_sample_model();
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::KeyVal),
+        ..Default::default()
+    };
+    let fields = vec![
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Pid),
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Comm),
+    ];
+    let dumper = transpose::Transpose::new(Box::new(process::Process::new(
+        &opts, None, None, false, None, fields,
+    )));
+    let mut round = 0;
+    let mut output: Vec<u8> = Vec::new();
+    let res = dumper
+        .dump_model(&ctx, &model, &mut output, &mut round, false)
+        .expect("Failed to dump model");
+    // Nothing is written before the end
+    assert!(res == IterExecResult::Skip);
+    assert!(output.is_empty());
+    assert_eq!(round, 1);
+    dumper.finish(&mut output).expect("Failed to finish");
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Pid   1\nComm  systemd\n"
+    );
+    // A second time slice can't be transposed
+    assert!(dumper
+        .dump_model(&ctx, &model, &mut Vec::new(), &mut round, false)
+        .is_err());
+
+    let rows = vec![
+        vec![
+            ("Name".to_owned(), "a".to_owned()),
+            ("Value".to_owned(), "100".to_owned()),
+        ],
+        vec![
+            ("Name".to_owned(), "bbbb".to_owned()),
+            ("Value".to_owned(), "".to_owned()),
+        ],
+    ];
+    let mut output: Vec<u8> = Vec::new();
+    transpose::print_transposed(&rows, &mut output).expect("Failed to print");
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Name   a    bbbb\nValue  100\n"
+    );
+
+    let opts = GeneralOpt {
+        transpose: true,
+        output_format: Some(OutputFormat::Csv),
+        ..Default::default()
+    };
+    assert!(get_dump_targets(&opts, |opts| system::System::new(opts, vec![])).is_err());
+}
+
+#[test]
+fn test_dump_na_string() {
+    let model = model::get_sample_model();
+    let spm = &model.process.processes[&1];
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    let fields = vec![
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Pid),
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Cpu(
+            model::ProcessCpuModelFieldId::UsagePct,
+        )),
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Io(
+            model::ProcessIoModelFieldId::RbytesPerSec,
+        )),
+    ];
+    // Cpu fields are missing without cpu stats
+    let mut spm = spm.clone();
+    spm.cpu = None;
+
+    assert_eq!(
+        print::dump_csv(&fields, &ctx, &spm, 1, false, false, None),
+        "1,?,0.0 B/s,\n"
+    );
+    assert_eq!(
+        print::dump_csv(&fields, &ctx, &spm, 1, false, false, Some("")),
+        "1,,0.0 B/s,\n"
+    );
+    assert_eq!(
+        print::dump_kv(&fields, &ctx, &spm, false, Some("NaN")),
+        "Pid: 1\nCPU: NaN\nReads: 0.0 B/s\n\n"
+    );
+    // Padded to the field width in raw output
+    let raw = print::dump_raw(
+        &fields,
+        &ctx,
+        &spm,
+        1,
+        &print::RawOptions {
+            disable_title: true,
+            na_string: Some("-"),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        raw,
+        print::dump_raw(
+            &fields,
+            &ctx,
+            &spm,
+            1,
+            &print::RawOptions {
+                disable_title: true,
+                ..Default::default()
+            },
+        )
+        .replace('?', "-")
+    );
+    // Json is not affected
+    assert_eq!(
+        print::dump_json(&fields, &ctx, &spm, false)["CPU"],
+        json!("?")
+    );
+}
+
+#[test]
+fn test_dump_watch_opts() {
+    let watch = |args: &[&str]| {
+        let opts = parse_opts(&[&["system", "-b", "1m ago"], args].concat())
+            .expect("Failed to parse args");
+        let dir = TempDir::new("below_dump_watch").expect("tempdir failed");
+        let (_err_sender, errs) = std::sync::mpsc::channel();
+        let src = DumpSource {
+            logger: get_logger(),
+            dir: dir.path().to_path_buf(),
+            host: None,
+            port: None,
+            errs,
+        };
+        run_dump(&src, &opts, |opts| system::System::new(opts, vec![]))
+    };
+    let err = |args: &[&str]| {
+        watch(args)
+            .expect_err("Expected --watch to fail")
+            .to_string()
+    };
+
+    assert!(err(&["--watch", "0"]).contains("at least 1 second"));
+    assert!(err(&["--watch", "1", "-O", "csv"]).contains("raw output"));
+    assert!(err(&["--watch", "1", "--output-json", "out.json"]).contains("raw output"));
+    assert!(err(&["--watch", "1", "--transpose"]).contains("raw output"));
+}
+
+#[test]
+fn test_dump_raw_model() {
+    let model = model::get_sample_model();
+    let ctx = CommonFieldContext {
+        timestamp: 1000,
+        rank: None,
+    };
+    let dump = |kind: command::RawModelKind| {
+        let dumper = raw_model::RawModel::new(kind);
+        let mut round = 0;
+        let mut output: Vec<u8> = Vec::new();
+        dumper
+            .dump_model(&ctx, &model, &mut output, &mut round, false)
+            .expect("Failed to dump model");
+        dumper
+            .dump_model(&ctx, &model, &mut output, &mut round, true)
+            .expect("Failed to dump model");
+        assert_eq!(round, 2);
+        serde_json::from_str::<Value>(&format!("[{}]", String::from_utf8(output).unwrap()))
+            .expect("Failed to parse json")
+    };
+
+    let value = dump(command::RawModelKind::Cgroup);
+    assert_eq!(value.as_array().unwrap().len(), 2);
+    assert_eq!(value[0]["timestamp"], 1000);
+    assert_eq!(value[0]["model"]["data"]["name"], model.cgroup.data.name);
+    assert_eq!(
+        value[0]["model"]["data"]["cpu_weight"],
+        serde_json::to_value(model.cgroup.data.cpu_weight).unwrap()
+    );
+    let value = dump(command::RawModelKind::Process);
+    assert_eq!(value[1]["model"]["processes"]["1"]["comm"], "systemd");
+    let value = dump(command::RawModelKind::System);
+    assert_eq!(value[0]["model"]["hostname"], model.system.hostname);
+
+    let parse = |args: &[&str]| {
+        parse_opts(&[&["raw-model", "system", "-b", "10:00"], args].concat())
+            .expect("Failed to parse args")
+    };
+    assert!(raw_model::check_opts(&parse(&[])).is_ok());
+    assert!(raw_model::check_opts(&parse(&["-O", "json"])).is_ok());
+    assert!(raw_model::check_opts(&parse(&["-O", "csv"])).is_err());
+    assert!(raw_model::check_opts(&parse(&["--output-kv", "out.kv"])).is_err());
+    assert!(raw_model::check_opts(&parse(&["--watch", "1"])).is_err());
+    assert!(parse_dump(&["raw-model", "disk", "-b", "10:00"]).is_err());
+}
+
+#[test]
+fn test_dump_counters() {
+    use model::CgroupCpuModelFieldId as Cpu;
+    use model::CgroupMemoryModelFieldId as Mem;
+    use model::SingleCgroupModelFieldId as FieldId;
+
+    let fields = vec![
+        CgroupField::FieldId(FieldId::Name),
+        CgroupField::FieldId(FieldId::Mem(Mem::EventsOomKill)),
+        CgroupField::FieldId(FieldId::Mem(Mem::EventsHigh)),
+        CgroupField::FieldId(FieldId::Mem(Mem::EventsHighPerSec)),
+        CgroupField::FieldId(FieldId::Mem(Mem::Anon)),
+    ];
+    let mut opts: GeneralOpt = Default::default();
+    assert_eq!(apply_counters(fields.clone(), &opts), fields);
+    opts.counters = Some(command::CounterMode::Cumulative);
+    assert_eq!(apply_counters(fields.clone(), &opts), fields);
+    opts.counters = Some(command::CounterMode::Rate);
+    assert_eq!(
+        apply_counters(fields, &opts),
+        vec![
+            CgroupField::FieldId(FieldId::Name),
+            CgroupField::FieldId(FieldId::Mem(Mem::EventsOomKillPerSec)),
+            CgroupField::FieldId(FieldId::Mem(Mem::EventsHighPerSec)),
+            CgroupField::FieldId(FieldId::Mem(Mem::Anon)),
+        ]
+    );
+
+    // Microsecond counters have a percentage instead of a per second rate
+    assert_eq!(
+        apply_counters(
+            vec![
+                CgroupField::FieldId(FieldId::Cpu(Cpu::UsageUsec)),
+                CgroupField::FieldId(FieldId::Cpu(Cpu::NrThrottled)),
+            ],
+            &opts
+        ),
+        vec![
+            CgroupField::FieldId(FieldId::Cpu(Cpu::UsagePct)),
+            CgroupField::FieldId(FieldId::Cpu(Cpu::NrThrottledPerSec)),
+        ]
+    );
+
+    let model = model::get_sample_model();
+    assert_eq!(
+        model
+            .cgroup
+            .data
+            .query(&FieldId::Mem(Mem::EventsHighPerSec))
+            .map(f64::from),
+        Some(12.5)
+    );
+}
+
+#[test]
+fn test_dump_rename() {
+    let parse = |args: &[&str]| {
+        parse_opts(&[&["process", "-b", "10:00"], args].concat()).expect("Failed to parse args")
+    };
+    let fields = vec![
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Pid),
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Comm),
+        ProcessField::Common(CommonField::Timestamp),
+    ];
+    let rename = |args: &[&str]| rename_fields(fields.clone(), &parse(args));
+
+    let model = model::get_sample_model();
+    let spm = &model.process.processes[&1];
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    let renamed = rename(&["--rename", "comm=command", "--rename", "timestamp=ts"])
+        .expect("Failed to rename fields");
+    assert_eq!(renamed.len(), fields.len());
+    // Names used for selection are unchanged
+    assert_eq!(renamed[1].to_string(), "comm");
+    assert_eq!(
+        print::dump_csv(&renamed, &ctx, spm, 0, false, false, None),
+        "Pid,command,ts,\n1,systemd,0,\n"
+    );
+    assert_eq!(
+        print::dump_kv(&renamed, &ctx, spm, false, None),
+        "Pid: 1\ncommand: systemd\nts: 0\n\n"
+    );
+    let json = print::dump_json(&renamed, &ctx, spm, false);
+    assert_eq!(json["command"], json!("systemd"));
+    assert!(json.get("Comm").is_none());
+
+    // Not in the selected fields
+    assert!(rename(&["--rename", "cpu.usage_pct=cpu"]).is_err());
+    // Renamed twice
+    assert!(rename(&["--rename", "comm=a", "--rename", "comm=b"]).is_err());
+    // Collides with the title of another field or another new name
+    assert!(rename(&["--rename", "comm=Pid"]).is_err());
+    assert!(rename(&["--rename", "comm=x", "--rename", "pid=x"]).is_err());
+    assert!(parse_dump(&["process", "-b", "10:00", "--rename", "comm"]).is_err());
+}
+
+#[test]
+fn test_dump_begin_from_env() {
+    let parse =
+        |args: &[&str]| parse_opts(&[&["system"], args].concat()).expect("Failed to parse args");
+    // Look up a fake environment instead of the process-wide one, which is
+    // shared with the tests running in parallel
+    let time_args = |args: &[&str], env: &[(&str, &str)]| {
+        get_time_args(&parse(args), |var| {
+            env.iter()
+                .find(|(name, _)| *name == var)
+                .map(|(_, value)| value.to_string())
+        })
+    };
+    let env = [
+        ("BELOW_DUMP_BEGIN", "10m ago"),
+        ("BELOW_DUMP_END", "5m ago"),
+    ];
+    assert_eq!(
+        time_args(&[], &env).unwrap(),
+        ("10m ago".to_owned(), Some("5m ago".to_owned()))
+    );
+    // The command line takes precedence, each of begin and end on its own
+    assert_eq!(
+        time_args(&["-b", "10:00"], &env).unwrap(),
+        ("10:00".to_owned(), Some("5m ago".to_owned()))
+    );
+    assert_eq!(
+        time_args(&["-e", "10:05"], &env).unwrap(),
+        ("10m ago".to_owned(), Some("10:05".to_owned()))
+    );
+    assert_eq!(
+        time_args(&["-b", "10:00"], &[]).unwrap(),
+        ("10:00".to_owned(), None)
+    );
+    assert_eq!(
+        time_args(&[], &[]).unwrap_err().to_string(),
+        "--begin or --range is required"
+    );
+}
+
+#[test]
+fn test_dump_group_digits() {
+    let model = model::get_sample_model();
+    let spm = &model.process.processes[&1];
+    let ctx = CommonFieldContext {
+        timestamp: 1234567,
+        rank: None,
+    };
+    let fields = vec![
+        ProcessField::Common(CommonField::Timestamp),
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Pid),
+        ProcessField::FieldId(model::SingleProcessModelFieldId::Mem(
+            model::ProcessMemoryModelFieldId::RssBytes,
+        )),
+    ];
+    let raw = |raw, group| {
+        print::dump_raw(
+            &fields,
+            &ctx,
+            spm,
+            1,
+            &print::RawOptions {
+                disable_title: true,
+                raw,
+                group,
+                ..Default::default()
+            },
+        )
+        .split_whitespace()
+        .map(|v| v.to_owned())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(raw(true, false), vec!["1234567", "1", "10000000"]);
+    assert_eq!(raw(true, true), vec!["1234567", "1", "10,000,000"]);
+    // Formatted values are not grouped
+    assert_eq!(raw(false, true), vec!["1234567", "1", "9.5", "MB"]);
+    // Other formats are not affected
+    assert_eq!(
+        print::dump_csv(&fields, &ctx, spm, 1, false, true, None),
+        "1234567,1,10000000,\n"
+    );
+
+    assert_eq!(print::group_digits("0"), "0");
+    assert_eq!(print::group_digits("123"), "123");
+    assert_eq!(print::group_digits("1234"), "1,234");
+    assert_eq!(print::group_digits("-123456"), "-123,456");
+}
+
+#[test]
+fn test_dump_store_dir() {
+    let store_dirs = |args: &[&str]| {
+        parse_opts(&[&["system", "-b", "10:00"], args].concat())
+            .expect("Failed to parse args")
+            .store_dir
+    };
+    assert!(store_dirs(&[]).is_empty());
+    // Kept in the given order, which is the order of preference
+    assert_eq!(
+        store_dirs(&["--store-dir", "/b", "--store-dir", "/a"]),
+        vec![
+            std::path::PathBuf::from("/b"),
+            std::path::PathBuf::from("/a")
+        ]
+    );
+}
+
+#[test]
+fn test_dump_filter_field() {
+    let mut model = model::get_sample_model();
+    let spm = model.process.processes[&1].clone();
+    for (pid, comm) in [(2, "bash"), (3, "systemd-journal"), (4, "systemd")] {
+        let mut spm = spm.clone();
+        spm.pid = Some(pid);
+        spm.comm = Some(comm.to_owned());
+        model.process.processes.insert(pid, spm);
+    }
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::Csv),
+        disable_title: true,
+        filter: Some(regex::Regex::new("^systemd").expect("Failed to parse regex")),
+        rsort: true,
+        ..Default::default()
+    };
+    let fields = vec![ProcessField::FieldId(model::SingleProcessModelFieldId::Pid)];
+    let dump = |select, filter_field| {
+        let process_dumper =
+            process::Process::new(&opts, select, filter_field, false, None, fields.clone());
+        let mut content: Vec<u8> = Vec::new();
+        let mut round = 0;
+        let ctx = CommonFieldContext {
+            timestamp: 0,
+            rank: None,
+        };
+        process_dumper
+            .dump_model(&ctx, &model, &mut content, &mut round, false)
+            .expect("Failed to dump process model");
+        String::from_utf8(content).expect("Fail to decode dump output")
+    };
+
+    // Filter on comm, sort by pid
+    assert_eq!(
+        dump(
+            Some(model::SingleProcessModelFieldId::Pid),
+            Some(model::SingleProcessModelFieldId::Comm)
+        ),
+        "4,\n3,\n1,\n\n"
+    );
+    // Without --filter-field the --select field is filtered
+    assert_eq!(
+        dump(Some(model::SingleProcessModelFieldId::Comm), None),
+        "3,\n1,\n4,\n\n"
+    );
+}
+
+/// Writes into a buffer the test can read after the target is gone.
+struct SharedBuf(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_dump_json_errors() {
+    /// Dumps the timestamp of each slice and fails on the third one.
+    struct FailingDumper;
+    impl Dumper for FailingDumper {
+        fn dump_model(
+            &self,
+            ctx: &CommonFieldContext,
+            _model: &model::Model,
+            output: &mut dyn Write,
+            round: &mut usize,
+            comma_flag: bool,
+        ) -> Result<tmain::IterExecResult> {
+            if *round == 2 {
+                bail!("corrupt frame");
+            }
+            let comma = if comma_flag { "," } else { "" };
+            write!(output, "{}{}", comma, json!({ "ts": ctx.timestamp }))?;
+            *round += 1;
+            Ok(tmain::IterExecResult::Success)
+        }
+    }
+
+    let logger = get_logger();
+    let dir = TempDir::new("below_dump_json_errors").expect("Failed to create temp dir");
+    let mut store = store::StoreWriter::new(
+        logger.clone(),
+        &dir,
+        store::CompressionMode::None,
+        store::Format::Cbor,
+    )
+    .expect("Failed to create store");
+    let time_begin = SystemTime::now() - Duration::from_secs(60);
+    for secs in [0, 5, 10, 15] {
+        store
+            .put(
+                time_begin + Duration::from_secs(secs),
+                &store::DataFrame {
+                    sample: Default::default(),
+                },
+            )
+            .expect("Failed to store sample");
+    }
+    let begin_ts = common::util::get_unix_timestamp(time_begin) as i64;
+
+    let dump = |json_errors| {
+        let mut advance =
+            store::advance::new_advance_local(logger.clone(), dir.path().to_path_buf(), time_begin);
+        advance.initialize();
+        let buf = Rc::new(RefCell::new(Vec::new()));
+        let target = tmain::DumpTarget::new(
+            Box::new(FailingDumper),
+            Box::new(SharedBuf(buf.clone())),
+            Some(OutputFormat::Json),
+        );
+        let (_err_sender, errs) = std::sync::mpsc::channel();
+        let res = tmain::dump_timeseries(
+            advance,
+            time_begin,
+            time_begin + Duration::from_secs(15),
+            vec![target],
+            &tmain::TimeseriesOpts {
+                json_errors,
+                ..Default::default()
+            },
+            &errs,
+        );
+        assert!(res.is_err());
+        let content = buf.borrow().clone();
+        String::from_utf8(content).expect("Fail to decode output")
+    };
+
+    // Left truncated by default
+    assert!(serde_json::from_str::<Value>(&dump(false)).is_err());
+    let value: Value = serde_json::from_str(&dump(true)).expect("Expected valid json");
+    assert_eq!(
+        value,
+        json!([
+            { "ts": begin_ts },
+            { "ts": begin_ts + 5 },
+            { "error": "corrupt frame", "last_timestamp": begin_ts + 5 },
+        ])
+    );
+}
+
+#[test]
+fn test_dump_every_nth() {
+    let logger = get_logger();
+    let dir = TempDir::new("below_dump_every_nth").expect("Failed to create temp dir");
+    let mut store = store::StoreWriter::new(
+        logger.clone(),
+        &dir,
+        store::CompressionMode::None,
+        store::Format::Cbor,
+    )
+    .expect("Failed to create store");
+    let time_begin = SystemTime::now() - Duration::from_secs(60);
+    for secs in [0, 5, 10, 15, 20] {
+        store
+            .put(
+                time_begin + Duration::from_secs(secs),
+                &store::DataFrame {
+                    sample: Default::default(),
+                },
+            )
+            .expect("Failed to store sample");
+    }
+    let begin_ts = common::util::get_unix_timestamp(time_begin) as i64;
+
+    let dump = |every_nth| {
+        let opts = GeneralOpt {
+            output_format: Some(OutputFormat::Json),
+            ..Default::default()
+        };
+        let fields = vec![DumpField::Common(CommonField::Timestamp)];
+        let mut advance =
+            store::advance::new_advance_local(logger.clone(), dir.path().to_path_buf(), time_begin);
+        advance.initialize();
+        let buf = Rc::new(RefCell::new(Vec::new()));
+        let target = tmain::DumpTarget::new(
+            Box::new(system::System::new(&opts, fields)),
+            Box::new(SharedBuf(buf.clone())),
+            opts.output_format,
+        );
+        let (_err_sender, errs) = std::sync::mpsc::channel();
+        tmain::dump_timeseries(
+            advance,
+            time_begin,
+            time_begin + Duration::from_secs(20),
+            vec![target],
+            &tmain::TimeseriesOpts {
+                every_nth,
+                ..Default::default()
+            },
+            &errs,
+        )
+        .expect("Failed to dump");
+        let value: Value = serde_json::from_slice(&buf.borrow()).expect("Invalid json");
+        value
+            .as_array()
+            .expect("Expected json array")
+            .iter()
+            .map(|row| row["Timestamp"].as_str().unwrap().parse::<i64>().unwrap() - begin_ts)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(dump(None), vec![0, 5, 10, 15, 20]);
+    assert_eq!(dump(Some(1)), vec![0, 5, 10, 15, 20]);
+    assert_eq!(dump(Some(2)), vec![0, 10, 20]);
+    assert_eq!(dump(Some(3)), vec![0, 15]);
+    assert_eq!(dump(Some(10)), vec![0]);
+}
+
+#[test]
+fn test_dump_progress() {
+    let parse = |args: &[&str]| {
+        parse_opts(&[&["system", "-b", "10:00"], args].concat())
+            .expect("Failed to parse args")
+            .progress
+    };
+    assert_eq!(parse(&[]), None);
+    assert_eq!(parse(&["--progress"]), Some(command::ProgressWhen::Auto));
+    assert_eq!(
+        parse(&["--progress=always"]),
+        Some(command::ProgressWhen::Always)
+    );
+    // Doesn't take the next argument as its value
+    assert_eq!(
+        parse(&["--progress", "--detail"]),
+        Some(command::ProgressWhen::Auto)
+    );
+
+    let begin = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+    let progress = tmain::Progress::new(begin, begin + Duration::from_secs(100));
+    assert_eq!(progress.fraction(1000), 0.0);
+    assert_eq!(progress.fraction(1025), 0.25);
+    assert_eq!(progress.fraction(2000), 1.0);
+    assert_eq!(
+        tmain::progress_line(0.25, 10, Duration::from_secs(60)),
+        "25.0% (10 slices), ETA 3m 0s"
+    );
+    assert_eq!(
+        tmain::progress_line(0.0, 0, Duration::from_secs(1)),
+        "0.0% (0 slices), ETA ?"
+    );
+}
+
+#[test]
+fn test_dump_threads() {
+    use model::SingleProcessModelFieldId as FieldId;
+
+    let fields = vec![
+        ProcessField::FieldId(FieldId::Pid),
+        ProcessField::FieldId(FieldId::Comm),
+    ];
+    assert_eq!(
+        add_tid_field(fields.clone()),
+        vec![
+            ProcessField::FieldId(FieldId::Pid),
+            ProcessField::FieldId(FieldId::Tid),
+            ProcessField::FieldId(FieldId::Comm),
+        ]
+    );
+    // Already dumped or no pid to put it after
+    let with_tid = vec![
+        ProcessField::FieldId(FieldId::Tid),
+        ProcessField::FieldId(FieldId::Pid),
+    ];
+    assert_eq!(add_tid_field(with_tid.clone()), with_tid);
+    let comm = vec![ProcessField::FieldId(FieldId::Comm)];
+    assert_eq!(add_tid_field(comm.clone()), comm);
+
+    let mut model = model::get_sample_model();
+    let mut thread = model.process.threads[&1].clone();
+    thread.tid = Some(2);
+    thread.comm = Some("worker".to_owned());
+    model.process.threads.insert(2, thread);
+
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::Csv),
+        disable_title: true,
+        ..Default::default()
+    };
+    let dump = |threads: bool| {
+        let dumper = process::Process::new(
+            &opts,
+            None,
+            None,
+            threads,
+            None,
+            add_tid_field(fields.clone()),
+        );
+        let mut content: Vec<u8> = Vec::new();
+        let mut round = 0;
+        let ctx = CommonFieldContext {
+            timestamp: 0,
+            rank: None,
+        };
+        dumper
+            .dump_model(&ctx, &model, &mut content, &mut round, false)
+            .expect("Failed to dump process model");
+        String::from_utf8(content).expect("Fail to decode dump output")
+    };
+    assert_eq!(dump(true), "1,1,systemd,\n1,2,worker,\n\n");
+    // Processes have no tid
+    assert_eq!(dump(false), "1,?,systemd,\n\n");
+}
+
+#[test]
+fn test_dump_fields_preset() {
+    use model::SingleCgroupModelFieldId as FieldId;
+
+    // Every field of every preset must be valid for its command
+    fn check<T: FromStr<Err = anyhow::Error> + PartialEq>(presets: &[command::FieldsPreset]) {
+        let mut opts: GeneralOpt = Default::default();
+        for (name, fields) in presets {
+            opts.fields_preset = Some(name.to_string());
+            let res = apply_fields_preset::<T>(None, &opts, presets)
+                .unwrap_or_else(|e| panic!("Invalid preset {}: {:#}", name, e));
+            assert_eq!(res.map(|fields| fields.len()), Some(fields.len()));
+        }
+    }
+    check::<command::SystemOptionField>(command::SYSTEM_PRESETS);
+    check::<command::DiskOptionField>(command::DISK_PRESETS);
+    check::<command::ProcessOptionField>(command::PROCESS_PRESETS);
+    check::<command::CgroupOptionField>(command::CGROUP_PRESETS);
+
+    let field = |name: &str| command::CgroupOptionField::from_str(name).unwrap();
+    let mut opts: GeneralOpt = Default::default();
+    let fields = Some(vec![field("name"), field("mem.anon")]);
+    assert_eq!(
+        apply_fields_preset(fields.clone(), &opts, command::CGROUP_PRESETS).unwrap(),
+        fields
+    );
+
+    // Extra fields go after the preset, without repeating its fields
+    opts.fields_preset = Some("throttling".to_owned());
+    let res = apply_fields_preset(fields, &opts, command::CGROUP_PRESETS)
+        .unwrap()
+        .unwrap();
+    assert_eq!(res[1], field("name"));
+    assert_eq!(res.iter().filter(|f| **f == field("name")).count(), 1);
+    assert_eq!(
+        res.last(),
+        Some(&DumpOptionField::Unit(CgroupField::FieldId(FieldId::Mem(
+            model::CgroupMemoryModelFieldId::Anon
+        ))))
+    );
+
+    opts.fields_preset = Some("no-such-preset".to_owned());
+    let err =
+        apply_fields_preset::<command::CgroupOptionField>(None, &opts, command::CGROUP_PRESETS)
+            .expect_err("Expected unknown preset");
+    assert!(err.to_string().contains("mem-debug, io-debug, throttling"));
+    assert!(apply_fields_preset::<command::IfaceOptionField>(None, &opts, &[]).is_err());
+
+    // Listed in the help of the command
+    let help = parse_dump(&["cgroup", "--help"])
+        .expect_err("Expected help")
+        .to_string();
+    assert!(help.contains("--fields-preset throttling: [datetime, name, cpu_weight"));
+}
+
+/// Sample model with only the processes of `usage`, by pid and cpu usage.
+fn model_with_cpu_usage(usage: &[(i32, f64)]) -> model::Model {
+    let mut model = model::get_sample_model();
+    let spm = model.process.processes[&1].clone();
+    model.process.processes.clear();
+    for (pid, usage_pct) in usage {
+        let mut spm = spm.clone();
+        spm.pid = Some(*pid);
+        spm.cpu.as_mut().unwrap().usage_pct = Some(*usage_pct);
+        model.process.processes.insert(*pid, spm);
+    }
+    model
+}
+
+#[test]
+fn test_dump_sort_by_delta() {
+    use model::ProcessCpuModelFieldId as Cpu;
+    use model::SingleProcessModelFieldId as FieldId;
+
+    let slices = [
+        model_with_cpu_usage(&[(1, 100.0), (2, 10.0), (3, 60.0)]),
+        model_with_cpu_usage(&[(1, 110.0), (2, 50.0), (3, 0.0)]),
+        // 3 is gone and 4 is new
+        model_with_cpu_usage(&[(1, 100.0), (2, 50.0), (4, 5.0)]),
+    ];
+    let dump = |rsort: bool, sort_by: Option<command::SortBy>| {
+        let opts = GeneralOpt {
+            output_format: Some(OutputFormat::Csv),
+            disable_title: true,
+            sort: !rsort,
+            rsort,
+            sort_by,
+            ..Default::default()
+        };
+        let dumper = process::Process::new(
+            &opts,
+            Some(FieldId::Cpu(Cpu::UsagePct)),
+            None,
+            false,
+            None,
+            vec![ProcessField::FieldId(FieldId::Pid)],
+        );
+        let ctx = CommonFieldContext {
+            timestamp: 0,
+            rank: None,
+        };
+        let mut round = 0;
+        slices
+            .iter()
+            .map(|model| {
+                let mut content: Vec<u8> = Vec::new();
+                dumper
+                    .dump_model(&ctx, model, &mut content, &mut round, false)
+                    .expect("Failed to dump process model");
+                String::from_utf8(content)
+                    .expect("Fail to decode dump output")
+                    .split_whitespace()
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(dump(true, None), vec!["1,3,2,", "1,2,3,", "1,2,4,"]);
+    assert_eq!(dump(true, Some(command::SortBy::Value)), dump(true, None));
+    // Nothing to compare with in the first slice, so the order is kept, and
+    // new entities come last
+    assert_eq!(
+        dump(true, Some(command::SortBy::Delta)),
+        vec!["1,2,3,", "2,1,3,", "2,1,4,"]
+    );
+    assert_eq!(
+        dump(true, Some(command::SortBy::AbsDelta)),
+        vec!["1,2,3,", "3,2,1,", "1,2,4,"]
+    );
+    // New entities come last with --sort too
+    assert_eq!(
+        dump(false, Some(command::SortBy::Delta)),
+        vec!["1,2,3,", "3,1,2,", "1,2,4,"]
+    );
+    assert_eq!(
+        dump(false, Some(command::SortBy::AbsDelta)),
+        vec!["1,2,3,", "1,2,3,", "2,1,4,"]
+    );
+}
+
+#[test]
+fn test_dump_sort_by_delta_skipped_slice() {
+    use model::ProcessCpuModelFieldId as Cpu;
+    use model::SingleProcessModelFieldId as FieldId;
+
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::Csv),
+        disable_title: true,
+        sort: true,
+        sort_by: Some(command::SortBy::Delta),
+        ..Default::default()
+    };
+    let dumper = process::Process::new(
+        &opts,
+        Some(FieldId::Cpu(Cpu::UsagePct)),
+        None,
+        false,
+        None,
+        vec![ProcessField::FieldId(FieldId::Pid)],
+    );
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    let mut round = 0;
+    let output = [
+        model_with_cpu_usage(&[(1, 10.0), (2, 50.0)]),
+        // 2 skips a slice
+        model_with_cpu_usage(&[(1, 20.0)]),
+        model_with_cpu_usage(&[(1, 30.0), (2, 0.0)]),
+    ]
+    .iter()
+    .map(|model| {
+        let mut content: Vec<u8> = Vec::new();
+        dumper
+            .dump_model(&ctx, model, &mut content, &mut round, false)
+            .expect("Failed to dump process model");
+        String::from_utf8(content)
+            .expect("Fail to decode dump output")
+            .split_whitespace()
+            .collect::<String>()
+    })
+    .collect::<Vec<_>>();
+    // 2 isn't compared with the slice before the one it skipped, so it has
+    // no delta and comes last
+    assert_eq!(output, vec!["1,2,", "1,", "1,2,"]);
+}
+
+#[test]
+fn test_dump_quantize_fields() {
+    use model::CgroupCpuModelFieldId as CgroupCpu;
+    use model::ProcessCpuModelFieldId as Cpu;
+    use model::SingleCgroupModelFieldId as CgroupFieldId;
+    use model::SingleProcessModelFieldId as FieldId;
+    use quantize::Agg;
+
+    let aggs = quantize::quantize_fields(
+        &[
+            CgroupField::Common(CommonField::Timestamp),
+            CgroupField::FieldId(CgroupFieldId::FullPath),
+            CgroupField::FieldId(CgroupFieldId::Cpu(CgroupCpu::UsagePct)),
+            CgroupField::FieldId(CgroupFieldId::Cpu(CgroupCpu::UsageUsec)),
+            CgroupField::FieldId(CgroupFieldId::Cpu(CgroupCpu::NrThrottled)),
+        ],
+        &[CgroupFieldId::FullPath],
+    )
+    .expect("Failed to get aggs")
+    .into_iter()
+    .map(|field| field.agg)
+    .collect::<Vec<_>>();
+    assert_eq!(
+        aggs,
+        vec![
+            Agg::Time(CommonField::Timestamp),
+            Agg::Key,
+            Agg::Mean,
+            Agg::Sum,
+            Agg::Sum,
+        ]
+    );
+    // Rows can't be told apart without a key
+    assert!(quantize::quantize_fields(
+        &[ProcessField::FieldId(FieldId::Cpu(Cpu::UsagePct))],
+        &[FieldId::Pid],
+    )
+    .is_err());
+}
+
+/// Dump processes quantized into minutes, from time slices 30s apart.
+fn dump_quantized_processes(
+    output_format: OutputFormat,
+    align: Option<command::QuantizeAlign>,
+) -> (String, usize) {
+    use model::ProcessCpuModelFieldId as Cpu;
+    use model::ProcessMemoryModelFieldId as Mem;
+    use model::SingleProcessModelFieldId as FieldId;
+
+    let model_with = |procs: &[(i32, f64, u64)]| {
+        let mut model = model::get_sample_model();
+        let spm = model.process.processes[&1].clone();
+        model.process.processes.clear();
+        for (pid, usage_pct, rss_bytes) in procs {
+            let mut spm = spm.clone();
+            spm.pid = Some(*pid);
+            spm.cpu.as_mut().unwrap().usage_pct = Some(*usage_pct);
+            spm.mem.as_mut().unwrap().rss_bytes = Some(*rss_bytes);
+            model.process.processes.insert(*pid, spm);
+        }
+        model
+    };
+    let slices = [
+        (0, model_with(&[(1, 10.0, 100), (2, 5.0, 50)])),
+        (30, model_with(&[(1, 20.0, 200)])),
+        (60, model_with(&[(1, 30.0, 300)])),
+        (90, model_with(&[(1, 50.0, 400)])),
+    ];
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::Json),
+        raw: true,
+        ..Default::default()
+    };
+    let fields = vec![
+        ProcessField::Common(CommonField::Timestamp),
+        ProcessField::FieldId(FieldId::Pid),
+        ProcessField::FieldId(FieldId::Cpu(Cpu::UsagePct)),
+        ProcessField::FieldId(FieldId::Mem(Mem::RssBytes)),
+    ];
+    let dumper = quantize::Quantize::new(
+        Box::new(process::Process::new(
+            &opts, None, None, false, None, fields,
+        )),
+        Duration::from_secs(60),
+        align,
+        Some(output_format),
+        false,
+    )
+    .expect("Failed to create quantize dumper");
+    let mut content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    for (timestamp, model) in slices.iter() {
+        let ctx = CommonFieldContext {
+            timestamp: 60045 + timestamp,
+            rank: None,
+        };
+        let comma_flag = round != 0;
+        dumper
+            .dump_model(&ctx, model, &mut content, &mut round, comma_flag)
+            .expect("Failed to dump process model");
+    }
+    dumper.finish(&mut content).expect("Failed to finish");
+    (
+        String::from_utf8(content).expect("Fail to decode dump output"),
+        round,
+    )
+}
+
+#[test]
+fn test_dump_quantize_csv() {
+    let (csv, rows) = dump_quantized_processes(OutputFormat::Csv, None);
+    assert_eq!(
+        csv,
+        "Timestamp,Pid,CPU,RSS,Samples,\n\
+        60000,1,10.00,100,1,\n\
+        60000,2,5.00,50,1,\n\
+        60060,1,25.00,300,2,\n\
+        60120,1,50.00,400,1,\n"
+    );
+    assert_eq!(rows, 4);
+}
+
+#[test]
+fn test_dump_quantize_align_first() {
+    let (csv, rows) =
+        dump_quantized_processes(OutputFormat::Csv, Some(command::QuantizeAlign::First));
+    assert_eq!(
+        csv,
+        "Timestamp,Pid,CPU,RSS,Samples,\n\
+        60045,1,15.00,200,2,\n\
+        60045,2,5.00,50,1,\n\
+        60105,1,40.00,400,2,\n"
+    );
+    assert_eq!(rows, 3);
+}
+
+#[test]
+fn test_dump_quantize_json() {
+    let (json, _) = dump_quantized_processes(OutputFormat::Json, None);
+    let value: Value = serde_json::from_str(&format!("[{}]", json)).expect("Invalid json");
+    assert_eq!(value.as_array().map(|arr| arr.len()), Some(3));
+    assert_eq!(value[1][0]["CPU"], json!("25.00"));
+    assert_eq!(value[1][0]["Samples"], json!("2"));
+}
+
+#[test]
+fn test_dump_quantize_counter_reset() {
+    use model::CgroupCpuModelFieldId as CgroupCpu;
+    use model::SingleCgroupModelFieldId as CgroupFieldId;
+
+    // Counters are summed as their increase, a reset starts from zero
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::Json),
+        raw: true,
+        ..Default::default()
+    };
+    let fields = vec![
+        CgroupField::FieldId(CgroupFieldId::FullPath),
+        CgroupField::FieldId(CgroupFieldId::Cpu(CgroupCpu::UsageUsec)),
+    ];
+    let dumper = quantize::Quantize::new(
+        Box::new(cgroup::Cgroup::new(&opts, None, None, false, false, fields)),
+        Duration::from_secs(60),
+        None,
+        Some(OutputFormat::Json),
+        false,
+    )
+    .expect("Failed to create quantize dumper");
+    let mut content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    for (timestamp, usage_usec) in [(0, 1000), (10, 1500), (20, 1200)] {
+        let mut model = model::get_sample_model();
+        model.cgroup.data.cpu = Some(model::CgroupCpuModel {
+            usage_usec: Some(usage_usec),
+            ..Default::default()
+        });
+        dumper
+            .dump_model(
+                &CommonFieldContext {
+                    timestamp,
+                    rank: None,
+                },
+                &model,
+                &mut content,
+                &mut round,
+                false,
+            )
+            .expect("Failed to dump cgroup model");
+    }
+    dumper.finish(&mut content).expect("Failed to finish");
+    let value: Value = serde_json::from_slice(&content).expect("Invalid json");
+    assert_eq!(value[0]["CPU Usage(usec)"], json!("1700"));
+    assert_eq!(value[0]["Samples"], json!("3"));
+}
+
+#[test]
+fn test_dumprc() {
+    let belowrc = r#"
+[dump.options]
+output_format = "json"
+repeat_title = 10
+
+[dump.cgroup]
+my_pattern = ["name"]
+
+[dump.cgroup.options]
+output_format = "csv"
+truncate_side = "left"
+"#
+    .parse::<TValue>()
+    .expect("Failed to parse belowrc");
+
+    // [dump.options] applies to every subcommand
+    let mut opts: GeneralOpt = Default::default();
+    dumprc::DumpRc::from_belowrc(&belowrc, "system")
+        .expect("Failed to read dumprc")
+        .apply(&mut opts)
+        .expect("Failed to apply dumprc");
+    assert_eq!(opts.output_format, Some(OutputFormat::Json));
+    assert_eq!(opts.repeat_title, Some(10));
+    assert_eq!(opts.truncate_side, None);
+
+    // [dump.SUBCOMMAND.options] overrides it, and the command line wins
+    let mut opts = GeneralOpt {
+        repeat_title: Some(5),
+        ..Default::default()
+    };
+    dumprc::DumpRc::from_belowrc(&belowrc, "cgroup")
+        .expect("Failed to read dumprc")
+        .apply(&mut opts)
+        .expect("Failed to apply dumprc");
+    assert_eq!(opts.output_format, Some(OutputFormat::Csv));
+    assert_eq!(opts.repeat_title, Some(5));
+    assert_eq!(opts.truncate_side, Some(command::TruncateSide::Left));
+
+    // No sections, no defaults
+    let empty = "".parse::<TValue>().expect("Failed to parse belowrc");
+    assert_eq!(
+        dumprc::DumpRc::from_belowrc(&empty, "system").expect("Failed to read dumprc"),
+        Default::default()
+    );
+
+    let invalid = |belowrc: &str| {
+        let belowrc = belowrc.parse::<TValue>().expect("Failed to parse belowrc");
+        dumprc::DumpRc::from_belowrc(&belowrc, "system")
+            .and_then(|dumprc| dumprc.apply(&mut Default::default()))
+            .is_err()
+    };
+    assert!(invalid("[dump.options]\nbegin = \"10:00\"\n"));
+    assert!(invalid("[dump.options]\noutput_format = \"xml\"\n"));
+    assert!(invalid("[dump.options]\ntruncate = true\n"));
+}
+
+#[test]
+fn test_dump_dumprc_load() {
+    let dir = TempDir::new("below_dumprc_test").expect("Failed to create temp dir");
+    let path = |name: &str| dir.path().join(name).to_string_lossy().into_owned();
+
+    // No belowrc, no defaults
+    assert_eq!(
+        dumprc::DumpRc::load(&path("missing"), "system").expect("Failed to load dumprc"),
+        Default::default()
+    );
+
+    std::fs::write(path("belowrc"), "[dump.options]\nrepeat_title = 10\n")
+        .expect("Failed to write belowrc");
+    let mut opts: GeneralOpt = Default::default();
+    dumprc::DumpRc::load(&path("belowrc"), "system")
+        .expect("Failed to load dumprc")
+        .apply(&mut opts)
+        .expect("Failed to apply dumprc");
+    assert_eq!(opts.repeat_title, Some(10));
+
+    // A belowrc that can't be parsed or read is reported
+    std::fs::write(path("invalid"), "[dump.options\n").expect("Failed to write belowrc");
+    assert!(dumprc::DumpRc::load(&path("invalid"), "system").is_err());
+    std::fs::create_dir(path("dir")).expect("Failed to create dir");
+    assert!(dumprc::DumpRc::load(&path("dir"), "system").is_err());
+}
+
+#[test]
+fn test_dump_pids() {
+    use model::SingleProcessModelFieldId as FieldId;
+
+    let mut model = model::get_sample_model();
+    for (pid, comm) in [(2, "kthreadd"), (3, "systemd-journal")] {
+        let mut process = model.process.processes[&1].clone();
+        process.pid = Some(pid);
+        process.comm = Some(comm.to_owned());
+        model.process.processes.insert(pid, process);
+    }
+
+    let dump = |pids: Vec<i32>, filter: Option<&str>| {
+        let opts = GeneralOpt {
+            output_format: Some(OutputFormat::Csv),
+            disable_title: true,
+            filter: filter.map(|f| regex::Regex::new(f).expect("Fail to construct regex")),
+            ..Default::default()
+        };
+        let dumper = process::Process::new(
+            &opts,
+            filter.map(|_| FieldId::Comm),
+            None,
+            false,
+            Some(pids),
+            vec![
+                ProcessField::FieldId(FieldId::Pid),
+                ProcessField::FieldId(FieldId::Comm),
+            ],
+        );
+        let mut content: Vec<u8> = Vec::new();
+        let mut round = 0;
+        let ctx = CommonFieldContext {
+            timestamp: 0,
+            rank: None,
+        };
+        dumper
+            .dump_model(&ctx, &model, &mut content, &mut round, false)
+            .expect("Failed to dump process model");
+        String::from_utf8(content).expect("Fail to decode dump output")
+    };
+    // Missing pids are left out
+    assert_eq!(
+        dump(vec![3, 1, 99], None),
+        "1,systemd,\n3,systemd-journal,\n\n"
+    );
+    // Applies together with --filter
+    assert_eq!(
+        dump(vec![1, 2, 3], Some("^systemd")),
+        "1,systemd,\n3,systemd-journal,\n\n"
+    );
+    assert_eq!(dump(vec![2], Some("^systemd")), "");
+    assert_eq!(dump(vec![99], None), "");
+
+    let cmd =
+        parse_dump(&["process", "-b", "now", "--pids", "1,42"]).expect("Failed to parse --pids");
+    assert!(matches!(cmd, DumpCommand::Process { pids: Some(pids), .. } if pids == vec![1, 42]));
+}
+
+#[test]
+fn test_dump_with_rank() {
+    use model::SingleCgroupModelFieldId as CgroupFieldId;
+    use model::SingleProcessModelFieldId as ProcessFieldId;
+
+    let rank = ProcessField::Common(CommonField::Rank);
+    let datetime = ProcessField::Common(CommonField::Datetime);
+    let pid = ProcessField::FieldId(ProcessFieldId::Pid);
+    let mut opts = GeneralOpt {
+        with_rank: true,
+        ..Default::default()
+    };
+    assert!(add_rank_field(vec![pid.clone()], &opts).is_err());
+    opts.rsort = true;
+    assert_eq!(
+        add_rank_field(vec![datetime.clone(), pid.clone()], &opts).unwrap(),
+        vec![datetime.clone(), rank.clone(), pid.clone()]
+    );
+    let with_rank = vec![pid.clone(), rank.clone()];
+    assert_eq!(add_rank_field(with_rank.clone(), &opts).unwrap(), with_rank);
+
+    let mut model = model::get_sample_model();
+    for pid in [2, 3] {
+        let mut process = model.process.processes[&1].clone();
+        process.pid = Some(pid);
+        model.process.processes.insert(pid, process);
+    }
+    opts.output_format = Some(OutputFormat::Csv);
+    opts.disable_title = true;
+    let ctx = CommonFieldContext {
+        timestamp: 0,
+        rank: None,
+    };
+    let dumper = process::Process::new(
+        &opts,
+        Some(ProcessFieldId::Pid),
+        None,
+        false,
+        None,
+        add_rank_field(vec![pid], &opts).unwrap(),
+    );
+    let mut content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    dumper
+        .dump_model(&ctx, &model, &mut content, &mut round, false)
+        .expect("Failed to dump process model");
+    assert_eq!(
+        String::from_utf8(content).expect("Fail to decode dump output"),
+        "1,3,\n2,2,\n3,1,\n\n"
+    );
+
+    // Cgroups are ranked among their siblings, the root has no rank
+    let dumper = cgroup::Cgroup::new(
+        &opts,
+        Some(CgroupFieldId::Name),
+        None,
+        false,
+        false,
+        add_rank_field(vec![CgroupField::FieldId(CgroupFieldId::Name)], &opts).unwrap(),
+    );
+    let mut content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    dumper
+        .dump_model(&ctx, &model, &mut content, &mut round, false)
+        .expect("Failed to dump cgroup model");
+    assert_eq!(
+        String::from_utf8(content).expect("Fail to decode dump output"),
+        "?,<root>,\n1,init.scope,\n2,child_b.slice,\n1,something.service,\n3,child_a.slice,\n"
+    );
+}
+
+#[test]
+fn test_dump_diff() {
+    use model::CgroupCpuModelFieldId as Cpu;
+    use model::CgroupMemoryModelFieldId as Mem;
+    use model::SingleCgroupModelFieldId as FieldId;
+
+    let cgroup = |full_path: &str, usage_usec: u64, total: u64| model::CgroupModel {
+        data: model::SingleCgroupModel {
+            name: full_path.rsplit('/').next().unwrap_or_default().to_owned(),
+            full_path: full_path.to_owned(),
+            cpu: Some(model::CgroupCpuModel {
+                usage_usec: Some(usage_usec),
+                ..Default::default()
+            }),
+            memory: Some(model::CgroupMemoryModel {
+                total: Some(total),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let model_with = |root: model::CgroupModel, children: Vec<model::CgroupModel>| {
+        let mut model = model::get_sample_model();
+        model.cgroup = root;
+        model.cgroup.children = children.into_iter().collect();
+        model
+    };
+    let before = model_with(
+        cgroup("", 1000, 300),
+        vec![cgroup("/a.slice", 500, 100), cgroup("/b.slice", 100, 200)],
+    );
+    let after = model_with(
+        cgroup("", 4000, 250),
+        vec![cgroup("/a.slice", 100, 150), cgroup("/c.slice", 50, 100)],
+    );
+    let dump = |output_format, na_string: Option<&str>| {
+        let opts = GeneralOpt {
+            output_format: Some(OutputFormat::Json),
+            raw: true,
+            ..Default::default()
+        };
+        let fields = vec![
+            CgroupField::Common(CommonField::Timestamp),
+            CgroupField::FieldId(FieldId::FullPath),
+            CgroupField::FieldId(FieldId::Cpu(Cpu::UsageUsec)),
+            CgroupField::FieldId(FieldId::Mem(Mem::Total)),
+        ];
+        let dumper = diff::Diff::new(
+            Box::new(cgroup::Cgroup::new(&opts, None, None, false, false, fields)),
+            Some(output_format),
+            false,
+            na_string.map(str::to_owned),
+        )
+        .expect("Failed to create diff dumper");
+        let ctx = |timestamp| CommonFieldContext {
+            timestamp,
+            rank: None,
+        };
+        let mut content: Vec<u8> = Vec::new();
+        let rows = dumper
+            .dump((&ctx(0), &before), (&ctx(60), &after), &mut content)
+            .expect("Failed to dump diff");
+        assert_eq!(rows, 4);
+        String::from_utf8(content).expect("Fail to decode dump output")
+    };
+    // a.slice's counter went down, i.e. was reset
+    assert_eq!(
+        dump(OutputFormat::Csv, None),
+        "Status,Full Path,CPU Usage(usec) Diff,Mem Total Before,Mem Total After,Mem Total Diff,\n\
+        kept,,3000,300,250,-50,\n\
+        kept,/a.slice,100,100,150,50,\n\
+        removed,/b.slice,?,200,?,?,\n\
+        added,/c.slice,?,?,100,?,\n"
+    );
+    // --na-string applies to the missing values of the csv
+    let csv = dump(OutputFormat::Csv, Some("N/A"));
+    assert_eq!(
+        csv.lines().skip(3).collect::<Vec<_>>(),
+        vec![
+            "removed,/b.slice,N/A,200,N/A,N/A,",
+            "added,/c.slice,N/A,N/A,100,N/A,"
+        ]
+    );
+    let value: Value = serde_json::from_str(&dump(OutputFormat::Json, None)).expect("Invalid json");
+    assert_eq!(value[2]["Status"], json!("removed"));
+    assert_eq!(value[2]["Full Path"], json!("/b.slice"));
+    assert_eq!(value[3]["Mem Total After"], json!("100"));
+
+    // --begin is not needed
+    let cmd = parse_dump(&["cgroup", "--diff", "10:00", "10:05"]).expect("Failed to parse --diff");
+    assert!(matches!(cmd, DumpCommand::Cgroup { opts, .. } if opts.diff == vec!["10:00", "10:05"]));
+    // Raw output is not supported
+    assert!(diff::Diff::new(
+        Box::new(system::System::new(&Default::default(), Vec::new())),
+        None,
+        false,
+        None
+    )
+    .is_err());
+}
+
+#[test]
+fn test_dump_graphite() {
+    use model::CgroupMemoryModelFieldId as Mem;
+    use model::SingleCgroupModelFieldId as CgroupFieldId;
+    use model::SingleProcessModelFieldId as ProcessFieldId;
+
+    assert_eq!(print::graphite_component("foo.service"), "foo_service");
+    assert_eq!(print::graphite_component("a b:c/d-e_f"), "a_b_c_d-e_f");
+
+    let model = model::get_sample_model();
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::Graphite),
+        ..Default::default()
+    };
+    let ctx = CommonFieldContext {
+        timestamp: 1600000000,
+        rank: None,
+    };
+    let dump = |dumper: &dyn Dumper| {
+        let mut content: Vec<u8> = Vec::new();
+        let mut round = 0;
+        dumper
+            .dump_model(&ctx, &model, &mut content, &mut round, false)
+            .expect("Failed to dump model");
+        String::from_utf8(content).expect("Fail to decode dump output")
+    };
+
+    // Strings and the timestamp are left out
+    let cgroup_dumper = cgroup::Cgroup::new(
+        &opts,
+        None,
+        None,
+        false,
+        false,
+        vec![
+            CgroupField::Common(CommonField::Timestamp),
+            CgroupField::FieldId(CgroupFieldId::FullPath),
+            CgroupField::FieldId(CgroupFieldId::Mem(Mem::Total)),
+        ],
+    );
+    assert_eq!(
+        dump(&cgroup_dumper),
+        "below.cgroup.root.mem_total 5000000000 1600000000\n\
+        below.cgroup.child_a_slice.mem_total 8000 1600000000\n\
+        below.cgroup.child_b_slice.mem_total 30000000 1600000000\n\
+        below.cgroup.child_b_slice.something_service.mem_total 500000 1600000000\n\
+        below.cgroup.init_scope.mem_total 20000000 1600000000\n"
+    );
+
+    let process_dumper = process::Process::new(
+        &opts,
+        None,
+        None,
+        false,
+        None,
+        vec![
+            ProcessField::FieldId(ProcessFieldId::Comm),
+            ProcessField::FieldId(ProcessFieldId::Pid),
+        ],
+    );
+    assert_eq!(dump(&process_dumper), "below.process.1.pid 1 1600000000\n");
+}
+
+#[test]
+fn test_dump_order() {
+    use model::CgroupCpuModelFieldId as Cpu;
+    use model::CgroupIoModelFieldId as Io;
+    use model::CgroupMemoryModelFieldId as Mem;
+    use model::SingleCgroupModelFieldId as FieldId;
+
+    let fields = vec![
+        CgroupField::Common(CommonField::Datetime),
+        CgroupField::FieldId(FieldId::Name),
+        CgroupField::FieldId(FieldId::Io(Io::RbytesPerSec)),
+        CgroupField::FieldId(FieldId::Mem(Mem::Total)),
+        CgroupField::FieldId(FieldId::Cpu(Cpu::UsagePct)),
+        CgroupField::FieldId(FieldId::Mem(Mem::Anon)),
+        CgroupField::FieldId(FieldId::FullPath),
+    ];
+    let order = |order| {
+        let opts = GeneralOpt {
+            order,
+            ..Default::default()
+        };
+        order_fields::<_, command::CgroupAggField>(fields.clone(), &opts)
+            .iter()
+            .map(|field| field.to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(order(None), order(Some(FieldOrder::Input)));
+    assert_eq!(
+        order(Some(FieldOrder::Input)),
+        vec![
+            "datetime",
+            "name",
+            "io.rbytes_per_sec",
+            "mem.total",
+            "cpu.usage_pct",
+            "mem.anon",
+            "full_path"
+        ]
+    );
+    assert_eq!(
+        order(Some(FieldOrder::Alpha)),
+        vec![
+            "datetime",
+            "name",
+            "full_path",
+            "cpu.usage_pct",
+            "io.rbytes_per_sec",
+            "mem.anon",
+            "mem.total"
+        ]
+    );
+    assert_eq!(
+        order(Some(FieldOrder::Grouped)),
+        vec![
+            "datetime",
+            "name",
+            "full_path",
+            "cpu.usage_pct",
+            "mem.total",
+            "mem.anon",
+            "io.rbytes_per_sec"
+        ]
+    );
+}
+
+/// Dump the anomalies of cpu.usage_pct over three time slices of cgroups.
+fn dump_cgroup_anomalies(threshold: Option<f64>) -> (String, usize) {
+    use model::CgroupCpuModelFieldId as Cpu;
+    use model::SingleCgroupModelFieldId as FieldId;
+
+    let cgroup = |full_path: &str, usage_pct: f64| model::CgroupModel {
+        data: model::SingleCgroupModel {
+            name: full_path.rsplit('/').next().unwrap_or_default().to_owned(),
+            full_path: full_path.to_owned(),
+            cpu: Some(model::CgroupCpuModel {
+                usage_pct: Some(usage_pct),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let model_with = |children: Vec<model::CgroupModel>| {
+        let mut model = model::get_sample_model();
+        model.cgroup = cgroup("", 100.0);
+        model.cgroup.children = children.into_iter().collect();
+        model
+    };
+    let slices = [
+        model_with(vec![cgroup("/a.slice", 10.0), cgroup("/b.slice", 50.0)]),
+        model_with(vec![
+            cgroup("/a.slice", 50.0),
+            cgroup("/b.slice", 45.0),
+            cgroup("/c.slice", 90.0),
+        ]),
+        model_with(vec![cgroup("/a.slice", 52.0), cgroup("/c.slice", 10.0)]),
+    ];
+    let opts = GeneralOpt {
+        output_format: Some(OutputFormat::Json),
+        raw: true,
+        ..Default::default()
+    };
+    let fields = vec![CgroupField::FieldId(FieldId::FullPath)];
+    let mut anomaly_opts = opts.clone();
+    anomaly_opts.anomalies = Some("cpu.usage_pct".to_owned());
+    let fields = add_anomaly_field(fields, &anomaly_opts).expect("Failed to add field");
+    assert_eq!(
+        fields.last(),
+        Some(&CgroupField::FieldId(FieldId::Cpu(Cpu::UsagePct)))
+    );
+    let dumper = anomalies::Anomalies::new(
+        Box::new(cgroup::Cgroup::new(&opts, None, None, false, false, fields)),
+        "cpu.usage_pct",
+        threshold,
+    )
+    .expect("Failed to create anomalies dumper");
+    let mut content: Vec<u8> = Vec::new();
+    let mut round = 0;
+    for (idx, model) in slices.iter().enumerate() {
+        let ctx = CommonFieldContext {
+            timestamp: 60 * idx as i64,
+            rank: None,
+        };
+        dumper
+            .dump_model(&ctx, model, &mut content, &mut round, false)
+            .expect("Failed to dump anomalies");
+    }
+    (
+        String::from_utf8(content).expect("Output is not utf8"),
+        round,
+    )
+}
+
+#[test]
+fn test_dump_anomalies() {
+    // Rows only in one of two slices, like /c.slice at first, are skipped
+    let (output, round) = dump_cgroup_anomalies(None);
+    assert_eq!(round, 4);
+    assert_eq!(
+        output,
+        format!(
+            "at {}: full_path=/a.slice cpu.usage_pct jumped +40 (10 -> 50)\n\
+            at {}: full_path=/b.slice cpu.usage_pct dropped -5 (50 -> 45)\n\
+            at {}: full_path=/a.slice cpu.usage_pct jumped +2 (50 -> 52)\n\
+            at {}: full_path=/c.slice cpu.usage_pct dropped -80 (90 -> 10)\n",
+            timestamp_to_datetime(&60),
+            timestamp_to_datetime(&60),
+            timestamp_to_datetime(&120),
+            timestamp_to_datetime(&120),
+        )
+    );
+}
+
+#[test]
+fn test_dump_anomalies_threshold() {
+    let (output, round) = dump_cgroup_anomalies(Some(10.0));
+    assert_eq!(round, 2);
+    assert_eq!(
+        output,
+        format!(
+            "at {}: full_path=/a.slice cpu.usage_pct jumped +40 (10 -> 50)\n\
+            at {}: full_path=/c.slice cpu.usage_pct dropped -80 (90 -> 10)\n",
+            timestamp_to_datetime(&60),
+            timestamp_to_datetime(&120),
+        )
+    );
+}
+
+#[test]
+fn test_dump_anomalies_invalid_field() {
+    use model::SingleCgroupModelFieldId as FieldId;
+
+    // The field must be dumped and must not tell rows apart
+    let opts: GeneralOpt = Default::default();
+    let new_cgroup = || {
+        Box::new(cgroup::Cgroup::new(
+            &opts,
+            None,
+            None,
+            false,
+            false,
+            vec![CgroupField::FieldId(FieldId::FullPath)],
+        ))
+    };
+    assert!(anomalies::Anomalies::new(new_cgroup(), "cpu.usage_pct", None).is_err());
+    assert!(anomalies::Anomalies::new(new_cgroup(), "full_path", None).is_err());
+}
+
+#[test]
+fn test_dump_range() {
+    let range = |s: &str| command::TimeRange::from_str(s).map(|r| (r.begin, r.end));
+    assert_eq!(
+        range("08:30:00..08:35:00").unwrap(),
+        ("08:30:00".to_owned(), Some("08:35:00".to_owned()))
+    );
+    assert_eq!(
+        range("2h ago .. 1h ago").unwrap(),
+        ("2h ago".to_owned(), Some("1h ago".to_owned()))
+    );
+    assert_eq!(range("08:30:00..").unwrap(), ("08:30:00".to_owned(), None));
+    assert_eq!(range("last 1h").unwrap(), ("1h ago".to_owned(), None));
+    assert!(range("08:30:00").is_err());
+    assert!(range("..08:30:00").is_err());
+    assert!(range("last ").is_err());
+
+    let parse = |args: &[&str]| parse_opts(&[&["system"], args].concat());
+    let opts = parse(&["--range", "last 10m"]).expect("Failed to parse args");
+    let (begin, end) = get_time_range(&opts).expect("Failed to get time range");
+    assert!(end.duration_since(begin).unwrap() >= Duration::from_secs(600));
+    for args in [["-b", "10:00"], ["-e", "10:00"]] {
+        let mut args = args.to_vec();
+        args.extend_from_slice(&["--range", "last 10m"]);
+        let opts = parse(&args).expect("Failed to parse args");
+        assert_eq!(
+            get_time_range(&opts).unwrap_err().to_string(),
+            "--range can't be used with --begin or --end"
+        );
+    }
+
+    // --range overrides the env vars, but still conflicts with -b given on
+    // the command line, even with the same value as the env var
+    let env = |var: &str| (var == "BELOW_DUMP_BEGIN").then(|| "10:00".to_owned());
+    let opts = parse(&["--range", "last 10m"]).expect("Failed to parse args");
+    assert_eq!(
+        get_time_args(&opts, env).unwrap(),
+        ("10m ago".to_owned(), None)
+    );
+    let opts = parse(&["-b", "10:00", "--range", "last 10m"]).expect("Failed to parse args");
+    assert_eq!(
+        get_time_args(&opts, env).unwrap_err().to_string(),
+        "--range can't be used with --begin or --end"
+    );
+}
+
+#[test]
+fn test_dump_discard() {
+    assert_eq!(OutputPath::parse("/dev/null"), OutputPath::Discard);
+    let mut opts = GeneralOpt {
+        discard: true,
+        output_csv: Some("out.csv".to_owned()),
+        ..Default::default()
+    };
+    assert_eq!(
+        get_output_targets(&opts),
+        vec![
+            (OutputPath::Discard, None),
+            (OutputPath::File("out.csv"), Some(OutputFormat::Csv)),
+        ]
+    );
+
+    // Everything is formatted as usual before it's thrown away
+    let model = model::get_sample_model();
+    let ctx = CommonFieldContext {
+        timestamp: 100,
+        rank: None,
+    };
+    opts.output_format = Some(OutputFormat::Csv);
+    let fields = vec![
+        DumpField::Common(CommonField::Timestamp),
+        DumpField::FieldId(model::SystemModelFieldId::Hostname),
+    ];
+    let mut expected: Vec<u8> = Vec::new();
+    system::System::new(&opts, fields.clone())
+        .dump_model(&ctx, &model, &mut expected, &mut 0, false)
+        .expect("Failed to dump model");
+    let mut target = tmain::DumpTarget::new_discard(
+        Box::new(system::System::new(&opts, fields)),
+        opts.output_format,
+    );
+    target
+        .dump_slice(&ctx, &model)
+        .expect("Failed to dump slice");
+    assert_eq!(target.discarded_bytes(), Some(expected.len() as u64));
+
+    assert_eq!(
+        tmain::throughput_line(500, 3_000_000, Duration::from_secs(2)),
+        "250.0 slices/s, 1.50 MB/s"
+    );
+}
+
+#[test]
+fn test_dump_drop_zero_columns() {
+    use model::CgroupCpuModelFieldId as Cpu;
+    use model::SingleCgroupModelFieldId as FieldId;
+
+    assert!(nonzero::is_zero(&json!("0")));
+    assert!(nonzero::is_zero(&json!("0.00")));
+    assert!(nonzero::is_zero(&json!("?")));
+    assert!(nonzero::is_zero(&Value::Null));
+    assert!(!nonzero::is_zero(&json!("0.01")));
+    assert!(!nonzero::is_zero(&json!("/a.slice")));
+
+    let cgroup = |full_path: &str, usage_pct: f64, throttled_pct: f64| model::CgroupModel {
+        data: model::SingleCgroupModel {
+            full_path: full_path.to_owned(),
+            cpu: Some(model::CgroupCpuModel {
+                usage_pct: Some(usage_pct),
+                throttled_pct: Some(throttled_pct),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let model_with = |children: Vec<model::CgroupModel>| {
+        let mut model = model::get_sample_model();
+        model.cgroup = cgroup("", 0.0, 0.0);
+        model.cgroup.children = children.into_iter().collect();
+        model
+    };
+    let slices = [
+        model_with(vec![cgroup("/a.slice", 0.0, 0.0)]),
+        // Only the nested row of the second slice has a non-zero value
+        model_with(vec![cgroup("/a.slice", 0.0, 2.5)]),
+    ];
+
+    let fields = vec![
+        CgroupField::Common(CommonField::Timestamp),
+        CgroupField::FieldId(FieldId::FullPath),
+        CgroupField::FieldId(FieldId::Cpu(Cpu::UsagePct)),
+        CgroupField::FieldId(FieldId::Cpu(Cpu::ThrottledPct)),
+        CgroupField::FieldId(FieldId::Cpu(Cpu::NrThrottled)),
+    ];
+    let titles = fields
+        .iter()
+        .map(|field| field.get_render_config().render_title(false))
+        .collect::<Vec<_>>();
+    let dump = |output_format: OutputFormat| {
+        let new_cgroup = |raw: bool| {
+            let opts = GeneralOpt {
+                output_format: Some(OutputFormat::Json),
+                raw,
+                ..Default::default()
+            };
+            Box::new(cgroup::Cgroup::new(
+                &opts,
+                None,
+                None,
+                false,
+                false,
+                fields.clone(),
+            ))
+        };
+        let dumper = nonzero::NonZero::new(
+            new_cgroup(true),
+            new_cgroup(false),
+            Some(output_format),
+            false,
+            None,
+        )
+        .expect("Failed to create dumper");
+        let mut output: Vec<u8> = Vec::new();
+        let mut round = 0;
+        for (idx, model) in slices.iter().enumerate() {
+            let ctx = CommonFieldContext {
+                timestamp: 100 + idx as i64,
+                rank: None,
+            };
+            assert!(
+                dumper
+                    .dump_model(&ctx, model, &mut output, &mut round, false)
+                    .expect("Failed to dump model")
+                    == tmain::IterExecResult::Skip
+            );
+        }
+        // Nothing is written before the end of the dump
+        assert!(output.is_empty());
+        dumper.finish(&mut output).expect("Failed to finish dump");
+        String::from_utf8(output).expect("Failed to convert output")
+    };
+
+    let json_output = dump(OutputFormat::Json);
+    let value: Value =
+        serde_json::from_str(&format!("[{}]", json_output)).expect("Failed to parse json");
+    let dumped = value.as_array().expect("Expected an array of slices");
+    assert_eq!(dumped.len(), 2);
+    let mut rows = Vec::new();
+    for slice in dumped {
+        quantize::Quantize::collect_rows(slice, &mut rows);
+    }
+    // Root and a.slice in both slices
+    assert_eq!(rows.len(), 4);
+    for row in rows {
+        let present = titles
+            .iter()
+            .map(|title| row.contains_key(title))
+            .collect::<Vec<_>>();
+        assert_eq!(present, [true, true, false, true, false]);
+    }
+
+    let csv_output = dump(OutputFormat::Csv);
+    let header = csv_output.lines().next().expect("Expected a title line");
+    assert_eq!(
+        header,
+        format!("{},{},{},", titles[0], titles[1], titles[3])
+    );
+    assert_eq!(csv_output.lines().count(), 5);
+}
+
+#[test]
+fn test_dump_explain_fields() {
+    let explain = |args: &[&str]| {
+        // No time range needed
+        let cmd = parse_dump(&[&["process", "--explain-fields"], args].concat());
+        let (fields, opts) = match cmd.expect("Failed to parse args") {
+            DumpCommand::Process { fields, opts, .. } => (fields, opts),
+            _ => panic!("Expected process command"),
+        };
+        let args = FieldArgs {
+            fields,
+            pattern: None,
+            section: "process",
+            presets: command::PROCESS_PRESETS,
+            default_fields: command::DEFAULT_PROCESS_FIELDS,
+            rank: true,
+            extra_field: None,
+        };
+        let (fields, sources) =
+            resolve_fields(args, String::new(), &opts).expect("Failed to resolve fields");
+        let mut output: Vec<u8> = Vec::new();
+        print_field_sources(&mut output, &opts, &fields, &sources)
+            .expect("Failed to explain fields");
+        String::from_utf8(output).expect("Fail to decode output")
+    };
+
+    let lines = explain(&[
+        "--fields-preset",
+        "mem-debug",
+        "-f",
+        "pid",
+        "-f",
+        "cpu",
+        "-f",
+        "io.r*",
+        "--with-rank",
+        "--sort",
+        "--rename",
+        "comm=command",
+    ]);
+    let lines = lines.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "datetime: preset mem-debug");
+    assert_eq!(lines[1], "rank: --with-rank");
+    assert_eq!(lines[2], "pid: preset mem-debug");
+    assert_eq!(
+        lines[3],
+        "comm: preset mem-debug, renamed to command by --rename"
+    );
+    assert!(lines.contains(&"cpu.usage_pct: --fields, group cpu"));
+    assert!(lines.contains(&"io.rbytes_per_sec: --fields, glob io.r*"));
+    assert!(lines.contains(&"io.rwbytes_per_sec: --fields, glob io.r*"));
+    // Given twice, explained by the first source
+    assert_eq!(
+        lines.iter().filter(|line| line.starts_with("pid:")).count(),
+        1
+    );
+
+    let lines = explain(&[]);
+    assert!(lines.starts_with("datetime: default fields\n"));
+    assert!(lines.contains("cpu.usage_pct: default fields, group cpu\n"));
+    let lines = explain(&["--detail", "-f", "cpu"]);
+    assert!(lines.contains("cpu.usage_pct: --fields, group cpu\n"));
+    assert!(lines.contains("cpu.num_threads: --fields, group cpu with detail\n"));
+}
@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Instant;
+
 use super::*;
 
 #[derive(PartialEq)]
@@ -20,6 +24,162 @@ pub enum IterExecResult {
     Skip,
 }
 
+/// Exit code of `below dump` when it succeeded but dumped no rows.
+pub const EMPTY_RESULT_EXIT_CODE: i32 = 3;
+
+/// Returned by dump commands when the time range and filters matched no rows,
+/// so callers can exit with `EMPTY_RESULT_EXIT_CODE`. With --fail-on-empty a
+/// regular error is returned instead.
+#[derive(Debug)]
+pub struct EmptyResult;
+
+impl std::error::Error for EmptyResult {}
+
+impl std::fmt::Display for EmptyResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "No rows matched the time range and filters")
+    }
+}
+
+/// Minimum time between two --progress lines.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Progress of a dump for --progress, written to stderr at most once every
+/// PROGRESS_INTERVAL. On a terminal the line is rewritten in place and
+/// cleared at the end, otherwise each update is a line of its own.
+pub struct Progress {
+    begin: i64,
+    end: i64,
+    slices: usize,
+    started: Instant,
+    last_update: Instant,
+    printed: bool,
+    in_place: bool,
+}
+
+impl Progress {
+    pub fn new(time_begin: SystemTime, time_end: SystemTime) -> Self {
+        let now = Instant::now();
+        Self {
+            begin: to_epoch_secs(time_begin),
+            end: to_epoch_secs(time_end),
+            slices: 0,
+            started: now,
+            last_update: now,
+            printed: false,
+            in_place: atty::is(atty::Stream::Stderr),
+        }
+    }
+
+    /// Fraction of the time range before `timestamp`, between 0 and 1.
+    pub fn fraction(&self, timestamp: i64) -> f64 {
+        if self.end <= self.begin {
+            return 1.0;
+        }
+        ((timestamp - self.begin) as f64 / (self.end - self.begin) as f64).clamp(0.0, 1.0)
+    }
+
+    /// Count a dumped time slice and print the progress if it's due.
+    /// `discarded` is the number of bytes formatted for --discard so far, if
+    /// any target discards its output.
+    fn update(&mut self, timestamp: i64, discarded: Option<u64>) {
+        self.slices += 1;
+        let now = Instant::now();
+        if now.duration_since(self.last_update) < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last_update = now;
+        let elapsed = now.duration_since(self.started);
+        let mut line = progress_line(self.fraction(timestamp), self.slices, elapsed);
+        if let Some(bytes) = discarded {
+            line = format!("{}, {}", line, throughput_line(self.slices, bytes, elapsed));
+        }
+        if self.in_place {
+            eprint!("\r\x1b[K{}", line);
+        } else {
+            eprintln!("{}", line);
+        }
+        self.printed = true;
+    }
+
+    /// Print the throughput of the whole dump for --discard in place of the
+    /// progress line.
+    fn print_summary(&mut self, discarded: u64) {
+        if self.in_place && self.printed {
+            eprint!("\r\x1b[K");
+            self.printed = false;
+        }
+        let elapsed = self.started.elapsed();
+        eprintln!(
+            "Dumped {} slices in {:.1}s, {}",
+            self.slices,
+            elapsed.as_secs_f64(),
+            throughput_line(self.slices, discarded, elapsed)
+        );
+    }
+}
+
+impl Drop for Progress {
+    /// Clear the progress line, also when the dump fails midway.
+    fn drop(&mut self) {
+        if self.in_place && self.printed {
+            eprint!("\r\x1b[K");
+        }
+    }
+}
+
+fn to_epoch_secs(time: SystemTime) -> i64 {
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as i64,
+        Err(_) => 0,
+    }
+}
+
+/// Format a progress line, e.g. "42.0% (1234 slices), ETA 3m 20s". The ETA
+/// assumes the rest of the time range is dumped at the same speed.
+pub fn progress_line(fraction: f64, slices: usize, elapsed: Duration) -> String {
+    let eta = if fraction > 0.0 {
+        let remaining = elapsed.as_secs_f64() * (1.0 - fraction) / fraction;
+        common::util::convert_duration(remaining.round() as u64)
+    } else {
+        "?".to_owned()
+    };
+    format!("{:.1}% ({} slices), ETA {}", fraction * 100.0, slices, eta)
+}
+
+/// Format the throughput of a dump, e.g. "250.0 slices/s, 1.50 MB/s". Bytes
+/// are those formatted by the dumpers, in MB of 1000000 bytes.
+pub fn throughput_line(slices: usize, bytes: u64, elapsed: Duration) -> String {
+    let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+    format!(
+        "{:.1} slices/s, {:.2} MB/s",
+        slices as f64 / secs,
+        bytes as f64 / 1_000_000.0 / secs
+    )
+}
+
+/// Output of --discard. What the dumpers write is thrown away after it was
+/// fully formatted, only the number of bytes is kept for --progress.
+#[derive(Clone, Default)]
+pub struct DiscardOutput(Rc<Cell<u64>>);
+
+impl DiscardOutput {
+    pub fn bytes(&self) -> u64 {
+        self.0.get()
+    }
+}
+
+impl Write for DiscardOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.set(self.0.get() + buf.len() as u64);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 /// Dumps (a portion of) the Model to some output in specific format.
 pub trait Dumper {
     fn dump_model(
@@ -33,6 +193,264 @@ pub trait Dumper {
         // value is the LAST value.
         comma_flag: bool,
     ) -> Result<IterExecResult>;
+
+    /// Called once after the last time slice, for dumpers that summarize
+    /// the whole time range.
+    fn finish(&self, _output: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+
+    /// Names of the dumped fields, in order, for --with-meta.
+    fn field_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// How each dumped field is aggregated by --quantize. Empty if the
+    /// dumper doesn't support it.
+    fn quantize_fields(&self) -> Result<Vec<quantize::QuantizeField>> {
+        Ok(Vec::new())
+    }
+
+    /// The dumped fields for --drop-zero-columns. Empty if the dumper
+    /// doesn't support it.
+    fn nonzero_columns(&self) -> Vec<nonzero::Column> {
+        Vec::new()
+    }
+}
+
+/// A Dumper together with the output it writes to and the format it writes
+/// in. All targets of a dump share the Models retrieved from the store, so
+/// the store is only read once no matter how many targets there are.
+pub struct DumpTarget {
+    dumper: Box<dyn Dumper>,
+    output: Box<dyn Write>,
+    output_format: Option<OutputFormat>,
+    round: usize,
+    last_res: IterExecResult,
+    slice_files: Option<SliceFiles>,
+    meta: Option<Value>,
+    discarded: Option<DiscardOutput>,
+}
+
+/// Output of --split-by-slice. Each time slice is written to its own file in
+/// `dir`, named by the slice timestamp.
+struct SliceFiles {
+    dir: PathBuf,
+    last_timestamp: Option<i64>,
+    duplicates: usize,
+}
+
+impl SliceFiles {
+    /// Get the file path for a slice. Slices sharing the same timestamp get
+    /// a "-N" suffix so earlier ones are not overwritten.
+    fn next_path(&mut self, timestamp: i64, output_format: Option<OutputFormat>) -> PathBuf {
+        if self.last_timestamp == Some(timestamp) {
+            self.duplicates += 1;
+        } else {
+            self.last_timestamp = Some(timestamp);
+            self.duplicates = 0;
+        }
+        let extension = output_format.unwrap_or(OutputFormat::Raw);
+        match self.duplicates {
+            0 => self.dir.join(format!("{}.{}", timestamp, extension)),
+            n => self.dir.join(format!("{}-{}.{}", timestamp, n, extension)),
+        }
+    }
+}
+
+impl DumpTarget {
+    pub fn new(
+        dumper: Box<dyn Dumper>,
+        output: Box<dyn Write>,
+        output_format: Option<OutputFormat>,
+    ) -> Self {
+        Self {
+            dumper,
+            output,
+            output_format,
+            round: 0,
+            last_res: IterExecResult::Skip,
+            slice_files: None,
+            meta: None,
+            discarded: None,
+        }
+    }
+
+    /// Target that formats everything but throws the output away, see
+    /// --discard.
+    pub fn new_discard(dumper: Box<dyn Dumper>, output_format: Option<OutputFormat>) -> Self {
+        let output = DiscardOutput::default();
+        Self {
+            discarded: Some(output.clone()),
+            ..Self::new(dumper, Box::new(output), output_format)
+        }
+    }
+
+    /// Number of bytes formatted so far if the output is discarded.
+    pub fn discarded_bytes(&self) -> Option<u64> {
+        self.discarded.as_ref().map(DiscardOutput::bytes)
+    }
+
+    /// Target that writes each time slice to a separate file in `dir`.
+    pub fn new_split_by_slice(
+        dumper: Box<dyn Dumper>,
+        dir: PathBuf,
+        output_format: Option<OutputFormat>,
+    ) -> Self {
+        Self {
+            slice_files: Some(SliceFiles {
+                dir,
+                last_timestamp: None,
+                duplicates: 0,
+            }),
+            ..Self::new(dumper, Box::new(io::sink()), output_format)
+        }
+    }
+
+    /// Start the output with a --with-meta record. Fails for output formats
+    /// other than json and csv.
+    pub fn with_meta(mut self) -> Result<Self> {
+        if !matches!(
+            self.output_format,
+            Some(OutputFormat::Json) | Some(OutputFormat::Csv)
+        ) {
+            bail!("--with-meta only works with json or csv output");
+        }
+        let generated_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs();
+        self.meta = Some(json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "fields": self.dumper.field_names(),
+            "generated_at": generated_at,
+        }));
+        Ok(self)
+    }
+
+    fn is_json(&self) -> bool {
+        self.output_format == Some(OutputFormat::Json)
+    }
+
+    /// Write the --with-meta record, if any. For json this is the first
+    /// element of the array, so the slices that follow need a comma.
+    fn write_meta(&self, output: &mut dyn Write) -> Result<()> {
+        let meta = match self.meta.as_ref() {
+            Some(meta) => meta,
+            None => return Ok(()),
+        };
+        if self.is_json() {
+            writeln!(output, "{}", json!({ "_meta": meta }))?;
+        } else {
+            writeln!(
+                output,
+                "# version: {}",
+                meta["version"].as_str().unwrap_or("")
+            )?;
+            let fields = meta["fields"]
+                .as_array()
+                .map(|fields| {
+                    fields
+                        .iter()
+                        .filter_map(|f| f.as_str())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .unwrap_or_default();
+            writeln!(output, "# fields: {}", fields)?;
+            writeln!(output, "# generated_at: {}", meta["generated_at"])?;
+        }
+        Ok(())
+    }
+
+    /// Whether a json element was already written before the next slice.
+    fn needs_comma(&self, round: usize) -> bool {
+        round != 0 || (self.is_json() && self.meta.is_some())
+    }
+
+    /// End the json array of a failed dump with an error object, so readers
+    /// can tell partial results from complete ones. `last_timestamp` is the
+    /// last time slice dumped in full, if any. Other targets are left as is.
+    fn write_json_error(&mut self, e: &Error, last_timestamp: Option<i64>) {
+        if !self.is_json() || self.slice_files.is_some() {
+            return;
+        }
+        let error = json!({
+            "error": format!("{:#}", e),
+            "last_timestamp": last_timestamp,
+        });
+        let comma = if self.needs_comma(self.round) {
+            ","
+        } else {
+            ""
+        };
+        // Already failing, there is nothing more to do if this fails too
+        let _ = write!(self.output, "{}{}]", comma, error).and_then(|_| self.output.flush());
+    }
+
+    /// Dump one time slice to this target.
+    pub(crate) fn dump_slice(
+        &mut self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+    ) -> Result<()> {
+        if self.slice_files.is_none() {
+            // Base on the exec result, we will determine if we need to generate the line breaker, etc
+            let comma_flag = self.needs_comma(self.round);
+            self.last_res = self.dumper.dump_model(
+                ctx,
+                model,
+                self.output.as_mut(),
+                &mut self.round,
+                comma_flag,
+            )?;
+            // Readers of a socket or pipe get each time slice as it's dumped
+            self.output.flush()?;
+            return Ok(());
+        }
+
+        // Every file is a complete document in the chosen format, so titles
+        // are rendered again and json is wrapped in its own array
+        let mut content: Vec<u8> = Vec::new();
+        let json = self.output_format == Some(OutputFormat::Json);
+        if json {
+            write!(content, "[")?;
+        }
+        self.write_meta(&mut content)?;
+        let mut round = 0;
+        let comma_flag = self.needs_comma(round);
+        self.last_res = self
+            .dumper
+            .dump_model(ctx, model, &mut content, &mut round, comma_flag)?;
+        if self.last_res == IterExecResult::Skip {
+            // No file for slices with nothing to dump
+            return Ok(());
+        }
+        if json {
+            write!(content, "]")?;
+        }
+        let output_format = self.output_format;
+        let path = match self.slice_files.as_mut() {
+            Some(slice_files) => slice_files.next_path(ctx.timestamp, output_format),
+            None => return Ok(()),
+        };
+        File::create(&path)
+            .and_then(|mut file| file.write_all(&content))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        self.round += round;
+        Ok(())
+    }
+}
+
+/// Options of dump_timeseries that apply to all targets.
+#[derive(Default)]
+pub struct TimeseriesOpts {
+    /// Line written between time slices
+    pub br: Option<String>,
+    pub limit_rows: Option<usize>,
+    pub every_nth: Option<usize>,
+    pub fail_on_empty: bool,
+    pub json_errors: bool,
+    pub progress: bool,
 }
 
 /// Called by dump commands to dump Models in continuous time steps. The actual
@@ -43,11 +461,9 @@ pub fn dump_timeseries(
     mut advance: Advance,
     time_begin: SystemTime,
     time_end: SystemTime,
-    dumper: &dyn Dumper,
-    output: &mut dyn Write,
-    output_format: Option<OutputFormat>,
-    br: Option<String>,
-    errs: Receiver<Error>,
+    mut targets: Vec<DumpTarget>,
+    opts: &TimeseriesOpts,
+    errs: &Receiver<Error>,
 ) -> Result<()> {
     let mut model = match advance.jump_sample_to(time_begin) {
         Some(m) => m,
@@ -61,42 +477,96 @@ pub fn dump_timeseries(
 
     cliutil::check_initial_sample_time_in_time_range(model.timestamp, time_begin, time_end)?;
 
-    let json = output_format == Some(OutputFormat::Json);
-    let csv = output_format == Some(OutputFormat::Csv);
+    for target in targets.iter_mut() {
+        if target.slice_files.is_none() {
+            if target.is_json() {
+                write!(target.output, "[")?;
+            }
+            let mut meta: Vec<u8> = Vec::new();
+            target.write_meta(&mut meta)?;
+            target.output.write_all(&meta)?;
+        }
+    }
 
-    let mut round = 0;
+    let mut progress = if opts.progress {
+        Some(Progress::new(time_begin, time_end))
+    } else {
+        None
+    };
 
-    if json {
-        write!(output, "[")?;
-    }
+    let limit_reached = |targets: &[DumpTarget]| matches!(opts.limit_rows, Some(limit) if targets.iter().all(|target| target.round >= limit));
+    // Timestamp of the last time slice dumped to all targets
+    let mut last_timestamp = None;
+    let fail = |targets: &mut [DumpTarget], e: Error, last_timestamp: Option<i64>| {
+        if opts.json_errors {
+            for target in targets.iter_mut() {
+                target.write_json_error(&e, last_timestamp);
+            }
+        }
+        Err(e)
+    };
+
+    // Bytes formatted by targets of --discard, for the --progress throughput
+    let discarded_bytes = |targets: &[DumpTarget]| {
+        targets
+            .iter()
+            .filter_map(DumpTarget::discarded_bytes)
+            .reduce(|acc, bytes| acc + bytes)
+    };
 
+    // Index of the time slice within the time range, for --every-nth
+    let mut slice_idx: usize = 0;
     loop {
         // Received external error, e.g. stop signal
         if let Ok(e) = errs.try_recv() {
-            bail!(e);
+            return fail(&mut targets, e, last_timestamp);
         }
         let ctx = CommonFieldContext {
             timestamp: model
                 .timestamp
                 .duration_since(SystemTime::UNIX_EPOCH)?
                 .as_secs() as i64,
+            rank: None,
         };
-        // Base on the exec result, we will determine if we need to generate the line breaker, etc
-        let comma_flag = round != 0;
-        let res = match dumper.dump_model(&ctx, &model, output, &mut round, comma_flag) {
-            Ok(res) => res,
-            Err(e) => {
-                // Swallow BrokenPipe error for write. Rust runtime will ignore SIGPIPE by default and
-                // propagating EPIPE upwards to the application in the form of an IoError::BrokenPipe.
-                if e.downcast_ref::<std::io::Error>()
-                    .map_or(false, |e| e.kind() == std::io::ErrorKind::BrokenPipe)
-                {
-                    return Ok(());
-                } else {
-                    return Err(e);
-                }
+        let skip = matches!(opts.every_nth, Some(n) if !slice_idx.is_multiple_of(n));
+        slice_idx += 1;
+        if skip {
+            // Skipped slices are not dumped, nor separated from the next one
+            if let Some(progress) = progress.as_mut() {
+                progress.update(ctx.timestamp, discarded_bytes(&targets));
             }
-        };
+            if advance.get_next_ts() > time_end {
+                break;
+            }
+            model = match advance.advance(Direction::Forward) {
+                Some(m) => m,
+                None => break,
+            };
+            continue;
+        }
+        if let Err(e) = targets
+            .iter_mut()
+            .try_for_each(|target| target.dump_slice(&ctx, &model))
+        {
+            // Swallow BrokenPipe error for write. Rust runtime will ignore SIGPIPE by default and
+            // propagating EPIPE upwards to the application in the form of an IoError::BrokenPipe.
+            if e.downcast_ref::<std::io::Error>()
+                .is_some_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe)
+            {
+                return Ok(());
+            } else {
+                return fail(&mut targets, e, last_timestamp);
+            }
+        }
+        last_timestamp = Some(ctx.timestamp);
+        if let Some(progress) = progress.as_mut() {
+            progress.update(ctx.timestamp, discarded_bytes(&targets));
+        }
+
+        // Reached the global row cap, stop here instead of reading more samples
+        if limit_reached(&targets) {
+            break;
+        }
 
         if advance.get_next_ts() > time_end {
             break;
@@ -107,22 +577,46 @@ pub fn dump_timeseries(
             None => break,
         };
 
-        if res == IterExecResult::Skip {
-            continue;
+        for target in targets.iter_mut() {
+            if target.last_res == IterExecResult::Skip || target.slice_files.is_some() {
+                continue;
+            }
+
+            match target.output_format {
+                Some(OutputFormat::Json) => writeln!(target.output)?,
+                Some(OutputFormat::Csv) => {}
+                _ => {
+                    if let Some(br) = opts.br.as_ref() {
+                        writeln!(target.output, "{}", br)?;
+                    }
+                }
+            }
         }
+    }
 
-        if json {
-            write!(output, "\n")?;
-        } else if br.is_some() && !csv {
-            write!(output, "{}\n", br.as_ref().unwrap())?;
+    for target in targets.iter_mut() {
+        target.dumper.finish(target.output.as_mut())?;
+        if target.is_json() && target.slice_files.is_none() {
+            write!(target.output, "]")?;
         }
+        target.output.flush()?;
     }
 
-    if json {
-        write!(output, "]")?;
+    if let (Some(progress), Some(bytes)) = (progress.as_mut(), discarded_bytes(&targets)) {
+        progress.print_summary(bytes);
     }
 
-    cliutil::check_final_sample_time_with_requested_time(model.timestamp, time_end);
+    // Stopping early due to --limit-rows is expected, don't warn about it
+    if !limit_reached(&targets) {
+        cliutil::check_final_sample_time_with_requested_time(model.timestamp, time_end);
+    }
+
+    if targets.iter().all(|target| target.round == 0) {
+        if opts.fail_on_empty {
+            bail!("{} (--fail-on-empty)", EmptyResult);
+        }
+        return Err(EmptyResult.into());
+    }
 
     Ok(())
 }
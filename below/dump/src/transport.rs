@@ -46,9 +46,7 @@ impl Dumper for Transport {
                     ctx,
                     &model.network,
                     *round,
-                    self.opts.repeat_title,
-                    self.opts.disable_title,
-                    self.opts.raw
+                    &print::RawOptions::new(&self.opts),
                 )
             )?,
             Some(OutputFormat::Csv) => write!(
@@ -60,13 +58,30 @@ impl Dumper for Transport {
                     &model.network,
                     *round,
                     self.opts.disable_title,
-                    self.opts.raw
+                    self.opts.raw,
+                    self.opts.na_string.as_deref()
                 )
             )?,
             Some(OutputFormat::KeyVal) => write!(
                 output,
                 "{}",
-                print::dump_kv(&self.fields, ctx, &model.network, self.opts.raw)
+                print::dump_kv(
+                    &self.fields,
+                    ctx,
+                    &model.network,
+                    self.opts.raw,
+                    self.opts.na_string.as_deref()
+                )
+            )?,
+            Some(OutputFormat::Graphite) => write!(
+                output,
+                "{}",
+                print::dump_graphite(
+                    &self.fields,
+                    ctx,
+                    &model.network,
+                    &format!("{}.transport", print::GRAPHITE_ROOT)
+                )
             )?,
             Some(OutputFormat::Json) => {
                 let json_output =
@@ -80,10 +95,26 @@ impl Dumper for Transport {
         };
         *round += 1;
 
-        if self.opts.output_format != Some(OutputFormat::Json) {
+        // Graphite lines are not separated by time slice
+        if !matches!(
+            self.opts.output_format,
+            Some(OutputFormat::Json) | Some(OutputFormat::Graphite)
+        ) {
             write!(output, "\n")?;
         }
 
         Ok(IterExecResult::Success)
     }
+
+    fn field_names(&self) -> Vec<String> {
+        self.fields.iter().map(ToString::to_string).collect()
+    }
+
+    fn quantize_fields(&self) -> Result<Vec<quantize::QuantizeField>> {
+        quantize::quantize_fields(&self.fields, &[])
+    }
+
+    fn nonzero_columns(&self) -> Vec<nonzero::Column> {
+        nonzero::columns(&self.fields)
+    }
 }
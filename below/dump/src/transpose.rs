@@ -0,0 +1,124 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+
+use super::*;
+
+/// A dumped row as (title, value) pairs in field order.
+type Row = Vec<(String, String)>;
+
+/// Dumper for --transpose. The wrapped dumper must dump kv output, which is
+/// collected for a single time slice and printed by `finish` with one line
+/// per field and one column per row.
+pub struct Transpose {
+    dumper: Box<dyn Dumper>,
+    rows: RefCell<Option<Vec<Row>>>,
+}
+
+impl Transpose {
+    pub fn new(dumper: Box<dyn Dumper>) -> Self {
+        Self {
+            dumper,
+            rows: RefCell::new(None),
+        }
+    }
+}
+
+/// Split kv output into rows. Each field is a "<title>: <value>" line and
+/// rows are separated by empty lines.
+fn parse_kv(content: &str) -> Vec<Row> {
+    content
+        .split("\n\n")
+        .map(|block| {
+            block
+                .lines()
+                .filter_map(|line| line.split_once(": "))
+                .map(|(title, value)| (title.to_owned(), value.to_owned()))
+                .collect::<Row>()
+        })
+        .filter(|row| !row.is_empty())
+        .collect()
+}
+
+/// Print rows with fields as lines, i.e. the title column followed by one
+/// column of values per row.
+pub fn print_transposed(rows: &[Row], output: &mut dyn Write) -> Result<()> {
+    let titles = match rows.first() {
+        Some(row) => row
+            .iter()
+            .map(|(title, _)| title.as_str())
+            .collect::<Vec<_>>(),
+        None => return Ok(()),
+    };
+    let title_width = titles.iter().map(|t| t.chars().count()).max().unwrap_or(0);
+    let value_widths = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|(_, v)| v.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    for (idx, title) in titles.iter().enumerate() {
+        let mut line = format!("{:width$}", title, width = title_width);
+        for (row, width) in rows.iter().zip(value_widths.iter()) {
+            let value = row.get(idx).map_or("", |(_, v)| v.as_str());
+            line.push_str(&format!("  {:width$}", value, width = width));
+        }
+        writeln!(output, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
+
+impl Dumper for Transpose {
+    fn dump_model(
+        &self,
+        ctx: &CommonFieldContext,
+        model: &model::Model,
+        _output: &mut dyn Write,
+        round: &mut usize,
+        _comma_flag: bool,
+    ) -> Result<IterExecResult> {
+        let mut content: Vec<u8> = Vec::new();
+        if self
+            .dumper
+            .dump_model(ctx, model, &mut content, round, false)?
+            == IterExecResult::Success
+        {
+            let rows = parse_kv(&String::from_utf8_lossy(&content));
+            if rows.is_empty() {
+                return Ok(IterExecResult::Skip);
+            }
+            let mut transposed = self.rows.borrow_mut();
+            if transposed.is_some() {
+                bail!(
+                    "--transpose only works with a single time slice, \
+                    use a shorter time range or --limit-rows"
+                );
+            }
+            *transposed = Some(rows);
+        }
+        // Nothing is written until the end
+        Ok(IterExecResult::Skip)
+    }
+
+    fn finish(&self, output: &mut dyn Write) -> Result<()> {
+        match self.rows.borrow().as_ref() {
+            Some(rows) => print_transposed(rows, output),
+            None => Ok(()),
+        }
+    }
+}
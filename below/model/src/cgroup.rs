@@ -21,6 +21,28 @@ pub struct SingleCgroupModel {
     pub name: String,
     pub full_path: String,
     pub inode_number: Option<u64>,
+    /// Whether the counters were reset since the last sample, e.g. because
+    /// the cgroup was recreated. Cpu and io rates are empty then. None
+    /// without a last sample.
+    pub reset: Option<bool>,
+    pub controllers: Option<Vec<String>>,
+    /// cpu.weight. Cgroups without one, e.g. the root cgroup or cgroups
+    /// without the cpu controller, get the default weight.
+    pub cpu_weight: Option<u32>,
+    /// Default weight of io.weight, same as cpu_weight if missing.
+    pub io_weight: Option<u32>,
+    /// Per device overrides of io.weight, as "<major>:<minor> <weight>".
+    pub io_weight_devices: Option<Vec<String>>,
+    /// cgroup.freeze. None for the root cgroup, which can't be frozen.
+    pub frozen: Option<bool>,
+    /// Live descendant cgroups, from cgroup.stat
+    pub nr_descendants: Option<u64>,
+    /// Removed descendant cgroups the kernel still holds on to, e.g. for
+    /// memory charged to them. A growing count points to a cgroup leak.
+    pub nr_dying_descendants: Option<u64>,
+    /// Device of the io fields, only set on the per device rows of
+    /// `below dump cgroup --per-device`.
+    pub device: Option<String>,
     #[queriable(ignore)]
     pub depth: u32,
     #[queriable(subquery)]
@@ -38,6 +60,9 @@ pub struct SingleCgroupModel {
     pub pressure: Option<CgroupPressureModel>,
 }
 
+/// Weight of cpu.weight and io.weight unless configured otherwise.
+pub const DEFAULT_CGROUP_WEIGHT: u32 = 100;
+
 /// A model that represents a cgroup subtree. Each instance is a node that uses
 /// the "data" field to represent local data. Otherwise mixing hierarchy and
 /// data makes it hard to define a Field Id type that queries nested cgroups.
@@ -142,6 +167,12 @@ impl core::borrow::Borrow<String> for CgroupModel {
     }
 }
 
+impl core::borrow::Borrow<str> for CgroupModel {
+    fn borrow(&self) -> &str {
+        &self.data.name
+    }
+}
+
 // We implement equality and ordering based on the cgroup name only so
 // CgroupModel can be stored in a BTreeSet
 impl Ord for CgroupModel {
@@ -171,6 +202,19 @@ impl CgroupModel {
         depth: u32,
         sample: &CgroupSample,
         last: Option<(&CgroupSample, Duration)>,
+    ) -> CgroupModel {
+        Self::new_under(name, full_path, depth, sample, last, None)
+    }
+
+    /// Same as `new` for a cgroup whose ancestors limit its memory to
+    /// `parent_limit` bytes, with None meaning no limit.
+    fn new_under(
+        name: String,
+        full_path: String,
+        depth: u32,
+        sample: &CgroupSample,
+        last: Option<(&CgroupSample, Duration)>,
+        parent_limit: Option<u64>,
     ) -> CgroupModel {
         let last_if_inode_matches =
             last.and_then(|(s, d)| match (s.inode_number, sample.inode_number) {
@@ -180,8 +224,33 @@ impl CgroupModel {
                 (None, None) => Some((s, d)),
                 _ => None,
             });
-        let (cpu, io, io_total, recreate_flag) = if let Some((last, delta)) = last_if_inode_matches
-        {
+        let last_if_no_reset = last_if_inode_matches.filter(|(s, _)| {
+            let cpu_reset = match (s.cpu_stat.as_ref(), sample.cpu_stat.as_ref()) {
+                (Some(last), Some(curr)) => {
+                    counter_decreased(last.usage_usec, curr.usage_usec)
+                        || counter_decreased(last.nr_periods, curr.nr_periods)
+                }
+                _ => false,
+            };
+            // Devices only in one of the samples have no rates, so they
+            // can't be reset
+            let io_reset = match (s.io_stat.as_ref(), sample.io_stat.as_ref()) {
+                (Some(last), Some(curr)) => curr.iter().any(|(device_name, curr)| {
+                    last.get(device_name).is_some_and(|last| {
+                        counter_decreased(last.rbytes, curr.rbytes)
+                            || counter_decreased(last.wbytes, curr.wbytes)
+                            || counter_decreased(last.rios, curr.rios)
+                            || counter_decreased(last.wios, curr.wios)
+                            || counter_decreased(last.dbytes, curr.dbytes)
+                            || counter_decreased(last.dios, curr.dios)
+                    })
+                }),
+                _ => false,
+            };
+            !cpu_reset && !io_reset
+        });
+        let reset = last.map(|_| last_if_no_reset.is_none());
+        let (cpu, io, io_total) = if let Some((last, delta)) = last_if_no_reset {
             // We have cumulative data, create cpu, io models
             let cpu = match (last.cpu_stat.as_ref(), sample.cpu_stat.as_ref()) {
                 (Some(begin), Some(end)) => Some(CgroupCpuModel::new(begin, end, delta)),
@@ -208,13 +277,25 @@ impl CgroupModel {
                     .fold(CgroupIoModel::empty(), |acc, (_, model)| acc + model)
             });
 
-            (cpu, io, io_total, false)
+            (cpu, io, io_total)
         } else {
-            // No cumulative data or inode number is different
-            (None, None, None, last.is_some())
+            // No cumulative data, inode number is different or the counters
+            // were reset
+            (None, None, None)
         };
+        let recreate_flag = last.is_some() && last_if_inode_matches.is_none();
 
-        let memory = Some(CgroupMemoryModel::new(sample, last));
+        // Memory rates are left empty by counter when it went down, so an
+        // unrelated reset doesn't clear them
+        let mut memory = CgroupMemoryModel::new(sample, last);
+        // The effective limit is the lowest memory.max of the cgroup and its
+        // ancestors, ignoring "max"
+        let limit = match (parent_limit, memory.memory_max.filter(|v| *v >= 0)) {
+            (Some(parent), Some(own)) => Some(parent.min(own as u64)),
+            (parent, own) => parent.or(own.map(|v| v as u64)),
+        };
+        memory.headroom = limit.and_then(|limit| memory.total.map(|v| limit.saturating_sub(v)));
+        let memory = Some(memory);
 
         let pressure = sample
             .pressure
@@ -230,7 +311,7 @@ impl CgroupModel {
             .unwrap_or(&empty)
             .iter()
             .map(|(child_name, child_sample)| {
-                CgroupModel::new(
+                CgroupModel::new_under(
                     child_name.clone(),
                     format!("{}/{}", full_path, child_name),
                     depth + 1,
@@ -242,6 +323,7 @@ impl CgroupModel {
                             .get(child_name)
                             .map(|child_last| (child_last, delta))
                     }),
+                    limit,
                 )
             })
             .collect::<BTreeSet<CgroupModel>>();
@@ -251,6 +333,33 @@ impl CgroupModel {
                 name,
                 full_path,
                 inode_number: sample.inode_number.map(|ino| ino as u64),
+                reset,
+                controllers: sample.cgroup_controllers.clone(),
+                cpu_weight: Some(sample.cpu_weight.unwrap_or(DEFAULT_CGROUP_WEIGHT)),
+                io_weight: Some(
+                    sample
+                        .io_weight
+                        .as_ref()
+                        .and_then(|io_weight| io_weight.default)
+                        .unwrap_or(DEFAULT_CGROUP_WEIGHT),
+                ),
+                io_weight_devices: sample.io_weight.as_ref().map(|io_weight| {
+                    io_weight
+                        .devices
+                        .iter()
+                        .map(|(device, weight)| format!("{} {}", device, weight))
+                        .collect()
+                }),
+                frozen: sample.frozen,
+                nr_descendants: sample
+                    .cgroup_stat
+                    .as_ref()
+                    .and_then(|stat| stat.nr_descendants),
+                nr_dying_descendants: sample
+                    .cgroup_stat
+                    .as_ref()
+                    .and_then(|stat| stat.nr_dying_descendants),
+                device: None,
                 cpu,
                 memory,
                 io,
@@ -264,6 +373,16 @@ impl CgroupModel {
         }
     }
 
+    /// Get the cgroup at `path`, e.g. /system.slice/foo.service, relative to
+    /// this cgroup.
+    pub fn get_by_path(&self, path: &str) -> Option<&CgroupModel> {
+        let mut model = self;
+        for part in path.split('/').filter(|part| !part.is_empty()) {
+            model = model.children.get(part)?;
+        }
+        Some(model)
+    }
+
     pub fn aggr_top_level_val(mut self) -> Self {
         self.data.memory = self.children.iter().fold(Default::default(), |acc, model| {
             opt_add(acc, model.data.memory.clone())
@@ -294,6 +413,12 @@ pub struct CgroupCpuModel {
     pub nr_periods_per_sec: Option<f64>,
     pub nr_throttled_per_sec: Option<f64>,
     pub throttled_pct: Option<f64>,
+    pub usage_usec: Option<u64>,
+    pub user_usec: Option<u64>,
+    pub system_usec: Option<u64>,
+    pub nr_periods: Option<u64>,
+    pub nr_throttled: Option<u64>,
+    pub throttled_usec: Option<u64>,
 }
 
 impl CgroupCpuModel {
@@ -309,6 +434,33 @@ impl CgroupCpuModel {
             nr_periods_per_sec: count_per_sec!(begin.nr_periods, end.nr_periods, delta),
             nr_throttled_per_sec: count_per_sec!(begin.nr_throttled, end.nr_throttled, delta),
             throttled_pct: usec_pct!(begin.throttled_usec, end.throttled_usec, delta),
+            usage_usec: end.usage_usec,
+            user_usec: end.user_usec,
+            system_usec: end.system_usec,
+            nr_periods: end.nr_periods,
+            nr_throttled: end.nr_throttled,
+            throttled_usec: end.throttled_usec,
+        }
+    }
+}
+
+impl std::ops::Add for CgroupCpuModel {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            usage_pct: opt_add(self.usage_pct, other.usage_pct),
+            user_pct: opt_add(self.user_pct, other.user_pct),
+            system_pct: opt_add(self.system_pct, other.system_pct),
+            nr_periods_per_sec: opt_add(self.nr_periods_per_sec, other.nr_periods_per_sec),
+            nr_throttled_per_sec: opt_add(self.nr_throttled_per_sec, other.nr_throttled_per_sec),
+            throttled_pct: opt_add(self.throttled_pct, other.throttled_pct),
+            usage_usec: opt_add(self.usage_usec, other.usage_usec),
+            user_usec: opt_add(self.user_usec, other.user_usec),
+            system_usec: opt_add(self.system_usec, other.system_usec),
+            nr_periods: opt_add(self.nr_periods, other.nr_periods),
+            nr_throttled: opt_add(self.nr_throttled, other.nr_throttled),
+            throttled_usec: opt_add(self.throttled_usec, other.throttled_usec),
         }
     }
 }
@@ -330,6 +482,15 @@ pub struct CgroupIoModel {
     pub dbytes_per_sec: Option<f64>,
     pub dios_per_sec: Option<f64>,
     pub rwbytes_per_sec: Option<f64>,
+    /// Share of the time the cgroup was using the device as priced by the
+    /// io.cost controller, from cost.usage in io.stat. None without io.cost.
+    pub cost_usage_pct: Option<f64>,
+    /// Virtual rate of the device relative to the configured cost model,
+    /// from cost.vrate in io.stat. Only reported for the root cgroup.
+    pub cost_vrate: Option<f64>,
+    /// Share of the time the cgroup was in debt to io.cost, from cost.indebt
+    /// in io.stat. Only reported with debug stats.
+    pub cost_indebt_pct: Option<f64>,
 }
 
 impl CgroupIoModel {
@@ -345,6 +506,9 @@ impl CgroupIoModel {
             dbytes_per_sec: count_per_sec!(begin.dbytes, end.dbytes, delta),
             dios_per_sec: count_per_sec!(begin.dios, end.dios, delta),
             rwbytes_per_sec,
+            cost_usage_pct: usec_pct!(begin.cost_usage, end.cost_usage, delta),
+            cost_vrate: end.cost_vrate,
+            cost_indebt_pct: usec_pct!(begin.cost_indebt, end.cost_indebt, delta),
         }
     }
 
@@ -359,6 +523,10 @@ impl CgroupIoModel {
             dbytes_per_sec: Some(0.0),
             dios_per_sec: Some(0.0),
             rwbytes_per_sec: Some(0.0),
+            // Not using io.cost isn't the same as no I/O
+            cost_usage_pct: None,
+            cost_vrate: None,
+            cost_indebt_pct: None,
         }
     }
 }
@@ -375,6 +543,13 @@ impl std::ops::Add<&CgroupIoModel> for CgroupIoModel {
             dbytes_per_sec: opt_add(self.dbytes_per_sec, other.dbytes_per_sec),
             dios_per_sec: opt_add(self.dios_per_sec, other.dios_per_sec),
             rwbytes_per_sec: opt_add(self.rwbytes_per_sec, other.rwbytes_per_sec),
+            cost_usage_pct: opt_add(self.cost_usage_pct, other.cost_usage_pct),
+            // Rates of different devices don't add up, keep the highest
+            cost_vrate: match (self.cost_vrate, other.cost_vrate) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            },
+            cost_indebt_pct: opt_add(self.cost_indebt_pct, other.cost_indebt_pct),
         }
     }
 }
@@ -391,6 +566,8 @@ impl std::ops::Add<&CgroupIoModel> for CgroupIoModel {
 pub struct CgroupMemoryModel {
     pub total: Option<u64>,
     pub swap: Option<u64>,
+    /// Compressed size of memory in zswap, from memory.stat
+    pub zswap: Option<u64>,
     pub anon: Option<u64>,
     pub file: Option<u64>,
     pub kernel_stack: Option<u64>,
@@ -416,6 +593,12 @@ pub struct CgroupMemoryModel {
     pub pgrefill: Option<u64>,
     pub pgscan: Option<u64>,
     pub pgsteal: Option<u64>,
+    /// pgscan and pgsteal split by background reclaim of kswapd and direct
+    /// reclaim of allocating tasks. Missing on kernels without the split.
+    pub pgscan_kswapd: Option<u64>,
+    pub pgscan_direct: Option<u64>,
+    pub pgsteal_kswapd: Option<u64>,
+    pub pgsteal_direct: Option<u64>,
     pub pgactivate: Option<u64>,
     pub pgdeactivate: Option<u64>,
     pub pglazyfree: Option<u64>,
@@ -423,11 +606,21 @@ pub struct CgroupMemoryModel {
     pub thp_fault_alloc: Option<u64>,
     pub thp_collapse_alloc: Option<u64>,
     pub memory_high: Option<i64>,
+    /// memory.max in bytes, -1 if there is no limit
+    pub memory_max: Option<i64>,
+    /// Bytes left before reaching the lowest memory.max of the cgroup and its
+    /// ancestors. None if none of them has a limit.
+    pub headroom: Option<u64>,
     pub events_low: Option<u64>,
     pub events_high: Option<u64>,
     pub events_max: Option<u64>,
     pub events_oom: Option<u64>,
     pub events_oom_kill: Option<u64>,
+    pub events_low_per_sec: Option<f64>,
+    pub events_high_per_sec: Option<f64>,
+    pub events_max_per_sec: Option<f64>,
+    pub events_oom_per_sec: Option<f64>,
+    pub events_oom_kill_per_sec: Option<f64>,
 }
 
 impl std::ops::Add for CgroupMemoryModel {
@@ -437,6 +630,7 @@ impl std::ops::Add for CgroupMemoryModel {
         Self {
             total: opt_add(self.total, other.total),
             swap: opt_add(self.swap, other.swap),
+            zswap: opt_add(self.zswap, other.zswap),
             anon: opt_add(self.anon, other.anon),
             file: opt_add(self.file, other.file),
             kernel_stack: opt_add(self.kernel_stack, other.kernel_stack),
@@ -465,6 +659,10 @@ impl std::ops::Add for CgroupMemoryModel {
             pgrefill: opt_add(self.pgrefill, other.pgrefill),
             pgscan: opt_add(self.pgscan, other.pgscan),
             pgsteal: opt_add(self.pgsteal, other.pgsteal),
+            pgscan_kswapd: opt_add(self.pgscan_kswapd, other.pgscan_kswapd),
+            pgscan_direct: opt_add(self.pgscan_direct, other.pgscan_direct),
+            pgsteal_kswapd: opt_add(self.pgsteal_kswapd, other.pgsteal_kswapd),
+            pgsteal_direct: opt_add(self.pgsteal_direct, other.pgsteal_direct),
             pgactivate: opt_add(self.pgactivate, other.pgactivate),
             pgdeactivate: opt_add(self.pgdeactivate, other.pgdeactivate),
             pglazyfree: opt_add(self.pglazyfree, other.pglazyfree),
@@ -472,11 +670,21 @@ impl std::ops::Add for CgroupMemoryModel {
             thp_fault_alloc: opt_add(self.thp_fault_alloc, other.thp_fault_alloc),
             thp_collapse_alloc: opt_add(self.thp_collapse_alloc, other.thp_collapse_alloc),
             memory_high: None,
+            memory_max: None,
+            headroom: None,
             events_low: opt_add(self.events_low, other.events_low),
             events_high: opt_add(self.events_high, other.events_high),
             events_max: opt_add(self.events_max, other.events_max),
             events_oom: opt_add(self.events_oom, other.events_oom),
             events_oom_kill: opt_add(self.events_oom_kill, other.events_oom_kill),
+            events_low_per_sec: opt_add(self.events_low_per_sec, other.events_low_per_sec),
+            events_high_per_sec: opt_add(self.events_high_per_sec, other.events_high_per_sec),
+            events_max_per_sec: opt_add(self.events_max_per_sec, other.events_max_per_sec),
+            events_oom_per_sec: opt_add(self.events_oom_per_sec, other.events_oom_per_sec),
+            events_oom_kill_per_sec: opt_add(
+                self.events_oom_kill_per_sec,
+                other.events_oom_kill_per_sec,
+            ),
         }
     }
 }
@@ -490,6 +698,7 @@ impl CgroupMemoryModel {
             total: sample.memory_current.map(|v| v as u64),
             swap: sample.memory_swap_current.map(|v| v as u64),
             memory_high: sample.memory_high,
+            memory_max: sample.memory_max,
             ..Default::default()
         };
         if let Some(events) = &sample.memory_events {
@@ -498,6 +707,22 @@ impl CgroupMemoryModel {
             model.events_max = events.max.map(|v| v as u64);
             model.events_oom = events.oom.map(|v| v as u64);
             model.events_oom_kill = events.oom_kill.map(|v| v as u64);
+
+            if let Some((
+                CgroupSample {
+                    memory_events: Some(last_events),
+                    ..
+                },
+                delta,
+            )) = last
+            {
+                model.events_low_per_sec = count_per_sec!(last_events.low, events.low, delta);
+                model.events_high_per_sec = count_per_sec!(last_events.high, events.high, delta);
+                model.events_max_per_sec = count_per_sec!(last_events.max, events.max, delta);
+                model.events_oom_per_sec = count_per_sec!(last_events.oom, events.oom, delta);
+                model.events_oom_kill_per_sec =
+                    count_per_sec!(last_events.oom_kill, events.oom_kill, delta);
+            }
         }
         if let Some(stat) = &sample.memory_stat {
             model.anon = stat.anon.map(|v| v as u64);
@@ -517,6 +742,7 @@ impl CgroupMemoryModel {
             model.unevictable = stat.unevictable.map(|v| v as u64);
             model.slab_reclaimable = stat.slab_reclaimable.map(|v| v as u64);
             model.slab_unreclaimable = stat.slab_unreclaimable.map(|v| v as u64);
+            model.zswap = stat.zswap.map(|v| v as u64);
 
             if let Some((
                 CgroupSample {
@@ -550,6 +776,14 @@ impl CgroupMemoryModel {
                 model.pgrefill = count_per_sec!(last_stat.pgrefill, stat.pgrefill, delta, u64);
                 model.pgscan = count_per_sec!(last_stat.pgscan, stat.pgscan, delta, u64);
                 model.pgsteal = count_per_sec!(last_stat.pgsteal, stat.pgsteal, delta, u64);
+                model.pgscan_kswapd =
+                    count_per_sec!(last_stat.pgscan_kswapd, stat.pgscan_kswapd, delta, u64);
+                model.pgscan_direct =
+                    count_per_sec!(last_stat.pgscan_direct, stat.pgscan_direct, delta, u64);
+                model.pgsteal_kswapd =
+                    count_per_sec!(last_stat.pgsteal_kswapd, stat.pgsteal_kswapd, delta, u64);
+                model.pgsteal_direct =
+                    count_per_sec!(last_stat.pgsteal_direct, stat.pgsteal_direct, delta, u64);
                 model.pgactivate =
                     count_per_sec!(last_stat.pgactivate, stat.pgactivate, delta, u64);
                 model.pgdeactivate =
@@ -588,6 +822,21 @@ pub struct CgroupPressureModel {
     pub io_full_pct: Option<f64>,
     pub memory_some_pct: Option<f64>,
     pub memory_full_pct: Option<f64>,
+    pub cpu_some_avg10: Option<f64>,
+    pub cpu_some_avg60: Option<f64>,
+    pub cpu_some_avg300: Option<f64>,
+    pub io_some_avg10: Option<f64>,
+    pub io_some_avg60: Option<f64>,
+    pub io_some_avg300: Option<f64>,
+    pub io_full_avg10: Option<f64>,
+    pub io_full_avg60: Option<f64>,
+    pub io_full_avg300: Option<f64>,
+    pub memory_some_avg10: Option<f64>,
+    pub memory_some_avg60: Option<f64>,
+    pub memory_some_avg300: Option<f64>,
+    pub memory_full_avg10: Option<f64>,
+    pub memory_full_avg60: Option<f64>,
+    pub memory_full_avg300: Option<f64>,
 }
 
 impl CgroupPressureModel {
@@ -601,6 +850,21 @@ impl CgroupPressureModel {
             io_full_pct: pressure.io.full.avg10,
             memory_some_pct: pressure.memory.some.avg10,
             memory_full_pct: pressure.memory.full.avg10,
+            cpu_some_avg10: pressure.cpu.some.avg10,
+            cpu_some_avg60: pressure.cpu.some.avg60,
+            cpu_some_avg300: pressure.cpu.some.avg300,
+            io_some_avg10: pressure.io.some.avg10,
+            io_some_avg60: pressure.io.some.avg60,
+            io_some_avg300: pressure.io.some.avg300,
+            io_full_avg10: pressure.io.full.avg10,
+            io_full_avg60: pressure.io.full.avg60,
+            io_full_avg300: pressure.io.full.avg300,
+            memory_some_avg10: pressure.memory.some.avg10,
+            memory_some_avg60: pressure.memory.some.avg60,
+            memory_some_avg300: pressure.memory.some.avg300,
+            memory_full_avg10: pressure.memory.full.avg10,
+            memory_full_avg60: pressure.memory.full.avg60,
+            memory_full_avg300: pressure.memory.full.avg300,
         }
     }
 }
@@ -691,4 +955,298 @@ mod tests {
             Some(Field::F64(42.0))
         );
     }
+
+    #[test]
+    fn cgroup_weights() {
+        let mut sample = CgroupSample::default();
+        let model = CgroupModel::new("<root>".to_owned(), "".to_owned(), 0, &sample, None);
+        // The root cgroup has no weights, which means the default
+        assert_eq!(model.data.cpu_weight, Some(DEFAULT_CGROUP_WEIGHT));
+        assert_eq!(model.data.io_weight, Some(DEFAULT_CGROUP_WEIGHT));
+        assert_eq!(model.data.io_weight_devices, None);
+
+        sample.cpu_weight = Some(250);
+        sample.io_weight = Some(cgroupfs::IoWeight {
+            default: Some(50),
+            devices: [("8:0".to_owned(), 200), ("8:16".to_owned(), 10)]
+                .into_iter()
+                .collect(),
+        });
+        let model = CgroupModel::new("foo".to_owned(), "/foo".to_owned(), 1, &sample, None);
+        assert_eq!(model.data.cpu_weight, Some(250));
+        assert_eq!(model.data.io_weight, Some(50));
+        assert_eq!(
+            model
+                .data
+                .query(&SingleCgroupModelFieldId::from_str("io_weight_devices").unwrap()),
+            Some(Field::StrList(vec![
+                "8:0 200".to_owned(),
+                "8:16 10".to_owned()
+            ]))
+        );
+    }
+
+    #[test]
+    fn cgroup_memory_headroom() {
+        let cgroup =
+            |memory_current: i64, memory_max: Option<i64>, children: Vec<(&str, CgroupSample)>| {
+                CgroupSample {
+                    memory_current: Some(memory_current),
+                    memory_max,
+                    children: Some(
+                        children
+                            .into_iter()
+                            .map(|(name, child)| (name.to_owned(), child))
+                            .collect(),
+                    ),
+                    ..Default::default()
+                }
+            };
+        let sample = cgroup(
+            3000,
+            None,
+            vec![(
+                "parent",
+                cgroup(
+                    2000,
+                    Some(5000),
+                    vec![
+                        ("unlimited", cgroup(1000, Some(-1), vec![])),
+                        ("lower", cgroup(1000, Some(1500), vec![])),
+                        ("higher", cgroup(1000, Some(10000), vec![])),
+                        ("over", cgroup(6000, Some(10000), vec![])),
+                    ],
+                ),
+            )],
+        );
+        let model = CgroupModel::new("<root>".to_owned(), "".to_owned(), 0, &sample, None);
+        let headroom = |path: &str| {
+            model
+                .get_by_path(path)
+                .and_then(|cgroup| cgroup.data.memory.as_ref())
+                .and_then(|memory| memory.headroom)
+        };
+        assert_eq!(headroom(""), None);
+        assert_eq!(headroom("/parent"), Some(3000));
+        assert_eq!(headroom("/parent/unlimited"), Some(4000));
+        assert_eq!(headroom("/parent/lower"), Some(500));
+        assert_eq!(headroom("/parent/higher"), Some(4000));
+        assert_eq!(headroom("/parent/over"), Some(0));
+    }
+
+    #[test]
+    fn cgroup_freeze_and_stat() {
+        let mut sample = CgroupSample::default();
+        let model = CgroupModel::new("<root>".to_owned(), "".to_owned(), 0, &sample, None);
+        assert_eq!(model.data.frozen, None);
+        assert_eq!(model.data.nr_dying_descendants, None);
+
+        sample.frozen = Some(true);
+        sample.cgroup_stat = Some(cgroupfs::CgroupStat {
+            nr_descendants: Some(5),
+            nr_dying_descendants: Some(2),
+        });
+        let model = CgroupModel::new("foo".to_owned(), "/foo".to_owned(), 1, &sample, None);
+        assert_eq!(
+            model
+                .data
+                .query(&SingleCgroupModelFieldId::from_str("frozen").unwrap()),
+            Some(Field::Bool(true))
+        );
+        assert_eq!(model.data.nr_descendants, Some(5));
+        assert_eq!(model.data.nr_dying_descendants, Some(2));
+    }
+
+    #[test]
+    fn cpu_raw_counters() {
+        let begin = cgroupfs::CpuStat {
+            usage_usec: Some(1_000_000),
+            nr_throttled: Some(3),
+            ..Default::default()
+        };
+        let end = cgroupfs::CpuStat {
+            usage_usec: Some(1_500_000),
+            nr_throttled: Some(5),
+            ..Default::default()
+        };
+        let model = CgroupCpuModel::new(&begin, &end, Duration::from_secs(1));
+        assert_eq!(model.usage_pct, Some(50.0));
+        assert_eq!(model.usage_usec, Some(1_500_000));
+        assert_eq!(model.nr_throttled_per_sec, Some(2.0));
+        assert_eq!(model.nr_throttled, Some(5));
+        assert_eq!(model.user_usec, None);
+    }
+
+    #[test]
+    fn io_cost() {
+        let begin = cgroupfs::IoStat {
+            cost_usage: Some(1_000_000),
+            cost_indebt: Some(0),
+            ..Default::default()
+        };
+        let end = cgroupfs::IoStat {
+            cost_vrate: Some(87.5),
+            cost_usage: Some(1_250_000),
+            cost_indebt: Some(100_000),
+            ..Default::default()
+        };
+        let model = CgroupIoModel::new(&begin, &end, Duration::from_secs(1));
+        assert_eq!(model.cost_usage_pct, Some(25.0));
+        assert_eq!(model.cost_vrate, Some(87.5));
+        assert_eq!(model.cost_indebt_pct, Some(10.0));
+
+        // Devices without io.cost leave the fields empty
+        let other = CgroupIoModel::new(
+            &Default::default(),
+            &Default::default(),
+            Duration::from_secs(1),
+        );
+        assert_eq!(other.cost_usage_pct, None);
+        let total = CgroupIoModel::empty() + &model + &other;
+        assert_eq!(total.cost_usage_pct, Some(25.0));
+        assert_eq!(total.cost_vrate, Some(87.5));
+        assert_eq!(CgroupIoModel::empty().cost_vrate, None);
+    }
+
+    #[test]
+    fn pressure_avg_windows() {
+        let metrics = |avg10, avg60, avg300| cgroupfs::PressureMetrics {
+            avg10: Some(avg10),
+            avg60: Some(avg60),
+            avg300: Some(avg300),
+            total: None,
+        };
+        let pressure = cgroupfs::Pressure {
+            io: cgroupfs::IoPressure {
+                some: metrics(5.0, 3.0, 1.0),
+                full: metrics(4.0, 2.0, 0.5),
+            },
+            ..Default::default()
+        };
+        let model = CgroupPressureModel::new(&pressure);
+        assert_eq!(model.io_full_pct, Some(4.0));
+        assert_eq!(model.io_full_avg10, Some(4.0));
+        assert_eq!(model.io_full_avg60, Some(2.0));
+        assert_eq!(model.io_full_avg300, Some(0.5));
+        assert_eq!(model.io_some_avg300, Some(1.0));
+        assert_eq!(model.cpu_some_avg60, None);
+
+        let value = serde_json::to_value(&model).expect("Failed to serialize");
+        assert_eq!(value["io_full_avg60"], serde_json::json!(2.0));
+    }
+
+    #[test]
+    fn cgroup_counter_reset() {
+        let sample = |inode_number, usage_usec| CgroupSample {
+            inode_number: Some(inode_number),
+            cpu_stat: Some(cgroupfs::CpuStat {
+                usage_usec: Some(usage_usec),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let model = |last: &CgroupSample, curr: &CgroupSample| {
+            CgroupModel::new(
+                "foo".to_owned(),
+                "/foo".to_owned(),
+                1,
+                curr,
+                Some((last, Duration::from_secs(1))),
+            )
+        };
+
+        let ok = model(&sample(1, 0), &sample(1, 500_000));
+        assert_eq!(ok.data.reset, Some(false));
+        assert_eq!(ok.data.cpu.and_then(|cpu| cpu.usage_pct), Some(50.0));
+        // Recreated
+        let recreated = model(&sample(1, 0), &sample(2, 500_000));
+        assert_eq!(recreated.data.reset, Some(true));
+        assert!(recreated.recreate_flag);
+        assert!(recreated.data.cpu.is_none());
+        // A counter went down without the inode changing
+        let decreased = model(&sample(1, 500_000), &sample(1, 0));
+        assert_eq!(decreased.data.reset, Some(true));
+        assert!(!decreased.recreate_flag);
+        assert!(decreased.data.cpu.is_none());
+        // Memory rates don't depend on the cpu counters
+        let with_events = |sample: CgroupSample, high| CgroupSample {
+            memory_events: Some(cgroupfs::MemoryEvents {
+                high: Some(high),
+                ..Default::default()
+            }),
+            ..sample
+        };
+        let decreased = model(
+            &with_events(sample(1, 500_000), 10),
+            &with_events(sample(1, 0), 30),
+        );
+        assert_eq!(decreased.data.reset, Some(true));
+        assert_eq!(
+            decreased
+                .data
+                .memory
+                .and_then(|memory| memory.events_high_per_sec),
+            Some(20.0)
+        );
+
+        let first = CgroupModel::new("foo".to_owned(), "/foo".to_owned(), 1, &sample(1, 0), None);
+        assert_eq!(first.data.reset, None);
+    }
+
+    #[test]
+    fn cgroup_io_counter_reset() {
+        let sample = |rbytes| CgroupSample {
+            inode_number: Some(1),
+            cpu_stat: Some(cgroupfs::CpuStat {
+                usage_usec: Some(1000),
+                ..Default::default()
+            }),
+            io_stat: Some(BTreeMap::from([
+                (
+                    "sda".to_owned(),
+                    cgroupfs::IoStat {
+                        rbytes: Some(rbytes),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "sdb".to_owned(),
+                    cgroupfs::IoStat {
+                        rbytes: Some(1000),
+                        ..Default::default()
+                    },
+                ),
+            ])),
+            ..Default::default()
+        };
+        let model = |last: &CgroupSample, curr: &CgroupSample| {
+            CgroupModel::new(
+                "foo".to_owned(),
+                "/foo".to_owned(),
+                1,
+                curr,
+                Some((last, Duration::from_secs(1))),
+            )
+        };
+
+        let ok = model(&sample(0), &sample(100));
+        assert_eq!(ok.data.reset, Some(false));
+        assert_eq!(
+            ok.data.io_total.and_then(|io| io.rbytes_per_sec),
+            Some(100.0)
+        );
+        // The read bytes of one device went down
+        let decreased = model(&sample(100), &sample(0));
+        assert_eq!(decreased.data.reset, Some(true));
+        assert!(!decreased.recreate_flag);
+        assert!(decreased.data.io.is_none());
+        assert!(decreased.data.io_total.is_none());
+        assert!(decreased.data.cpu.is_none());
+        // A device that's new in the current sample isn't a reset
+        let mut added = sample(100);
+        if let Some(io_stat) = added.io_stat.as_mut() {
+            io_stat.insert("sdc".to_owned(), Default::default());
+        }
+        assert_eq!(model(&sample(0), &added).data.reset, Some(false));
+    }
 }
@@ -25,6 +25,16 @@ pub struct CollectorOptions {
     pub collect_io_stat: bool,
     pub disable_disk_stat: bool,
     pub cgroup_re: Option<Regex>,
+    /// Open hardware perf counters to collect system wide IPC, cache and
+    /// branch misses.
+    pub collect_perf: bool,
+    /// Read the stats of each thread from /proc/<pid>/task.
+    pub collect_threads: bool,
+    /// Read the kernel function each process sleeps in from
+    /// /proc/<pid>/wchan.
+    pub collect_wchan: bool,
+    /// Count the TCP sockets in CLOSE_WAIT state from /proc/net/tcp{,6}.
+    pub collect_tcp_close_wait: bool,
 }
 
 impl Default for CollectorOptions {
@@ -35,6 +45,10 @@ impl Default for CollectorOptions {
             collect_io_stat: true,
             disable_disk_stat: false,
             cgroup_re: None,
+            collect_perf: false,
+            collect_threads: false,
+            collect_wchan: false,
+            collect_tcp_close_wait: false,
         }
     }
 }
@@ -44,19 +58,33 @@ pub struct Collector {
     logger: slog::Logger,
     prev_sample: Option<(Sample, Instant)>,
     collector_options: CollectorOptions,
+    perf: Option<perf::PerfCounters>,
 }
 
 impl Collector {
     pub fn new(logger: slog::Logger, collector_options: CollectorOptions) -> Self {
+        // Perf fields are left empty rather than failing the collector
+        let perf = if collector_options.collect_perf {
+            match perf::PerfCounters::new() {
+                Ok(perf) => Some(perf),
+                Err(e) => {
+                    error!(logger, "{:#}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
         Self {
             logger,
             prev_sample: None,
             collector_options,
+            perf,
         }
     }
 
     pub fn collect_sample(&self) -> Result<Sample> {
-        collect_sample(&self.logger, &self.collector_options)
+        collect_sample(&self.logger, &self.collector_options, self.perf.as_ref())
     }
 
     /// Collect a new `Sample`, returning an updated Model
@@ -154,7 +182,22 @@ fn is_all_zero_disk_stats(disk_stats: &procfs::DiskStat) -> bool {
         && disk_stats.time_spend_discard_ms == Some(0)
 }
 
-fn collect_sample(logger: &slog::Logger, options: &CollectorOptions) -> Result<Sample> {
+fn read_netstat(collect_tcp_close_wait: bool) -> procfs::Result<procfs::NetStat> {
+    let reader = procfs::NetReader::new()?;
+    let mut netstat = reader.read_netstat()?;
+    if collect_tcp_close_wait {
+        // Left out if the connection tables can't be read, like the other
+        // socket counts
+        netstat.tcp_close_wait = reader.read_tcp_close_wait().ok();
+    }
+    Ok(netstat)
+}
+
+fn collect_sample(
+    logger: &slog::Logger,
+    options: &CollectorOptions,
+    perf: Option<&perf::PerfCounters>,
+) -> Result<Sample> {
     let mut reader = procfs::ProcReader::new();
 
     // Take mutex, then take all values out of shared map and replace with default map
@@ -167,6 +210,19 @@ fn collect_sample(logger: &slog::Logger, options: &CollectorOptions) -> Result<S
             .expect("tried to acquire poisoned lock"),
     );
 
+    let mut pidmap = reader.read_all_pids()?;
+    for (pid, pidinfo) in pidmap.iter_mut() {
+        if options.collect_threads {
+            // Left out if the process exited since it was read
+            pidinfo.threads = reader.read_pid_threads(*pid as u32).ok();
+        }
+        if options.collect_wchan {
+            // Left out if the process exited or wchan isn't readable,
+            // e.g. without ptrace access to the process
+            pidinfo.wchan = reader.read_pid_wchan(*pid as u32).ok();
+        }
+    }
+
     Ok(Sample {
         cgroup: collect_cgroup_sample(
             &cgroupfs::CgroupReader::new(options.cgroup_root.to_owned())?,
@@ -175,14 +231,10 @@ fn collect_sample(logger: &slog::Logger, options: &CollectorOptions) -> Result<S
             &options.cgroup_re,
         )?,
         processes: merge_procfs_and_exit_data(
-            reader
-                .read_all_pids()?
-                .into_iter()
-                .map(|(k, v)| (k, v.into()))
-                .collect(),
+            pidmap.into_iter().map(|(k, v)| (k, v.into())).collect(),
             exit_pidmap,
         ),
-        netstats: match procfs::NetReader::new().and_then(|v| v.read_netstat()) {
+        netstats: match read_netstat(options.collect_tcp_close_wait) {
             Ok(ns) => ns.into(),
             Err(e) => {
                 error!(logger, "{:#}", e);
@@ -208,6 +260,7 @@ fn collect_sample(logger: &slog::Logger, options: &CollectorOptions) -> Result<S
                     None
                 }
             },
+            perf: perf.map(perf::PerfCounters::read),
             disks: if options.disable_disk_stat {
                 Default::default()
             } else {
@@ -326,7 +379,13 @@ fn collect_cgroup_sample(
             .transpose()?,
         memory_swap_current: wrap(reader.read_memory_swap_current().map(|v| v as i64))?,
         memory_high: reader.read_memory_high()?.map(Into::into),
+        memory_max: reader.read_memory_max()?,
         memory_events: wrap(reader.read_memory_events())?.map(Into::into),
+        cgroup_controllers: wrap(reader.read_cgroup_controllers())?,
+        cpu_weight: wrap(reader.read_cpu_weight())?,
+        io_weight: wrap(reader.read_io_weight())?,
+        frozen: wrap(reader.read_freeze())?,
+        cgroup_stat: wrap(reader.read_cgroup_stat())?,
         inode_number: match reader.read_inode_number() {
             Ok(st_ino) => Some(st_ino as i64),
             Err(e) => {
@@ -337,6 +396,13 @@ fn collect_cgroup_sample(
     })
 }
 
+/// Whether a cumulative counter went down between two samples. Monotonic
+/// counters only do when they were reset, e.g. the pid was reused or the
+/// cgroup was recreated, so rates across them are meaningless.
+pub(crate) fn counter_decreased<T: PartialOrd>(last: Option<T>, curr: Option<T>) -> bool {
+    matches!((last, curr), (Some(last), Some(curr)) if curr < last)
+}
+
 macro_rules! usec_pct {
     ($a_opt:expr, $b_opt:expr, $delta:expr) => {{
         let mut ret = None;
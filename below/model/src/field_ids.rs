@@ -60,6 +60,7 @@ pub const MODEL_FIELD_IDS: &[&'static str] = &[
     "system.mem.total",
     "system.mem.free",
     "system.mem.available",
+    "system.mem.headroom_pct",
     "system.mem.buffers",
     "system.mem.cached",
     "system.mem.swap_cached",
@@ -71,6 +72,9 @@ pub const MODEL_FIELD_IDS: &[&'static str] = &[
     "system.mem.mlocked",
     "system.mem.swap_total",
     "system.mem.swap_free",
+    "system.mem.zswap_total",
+    "system.mem.zswap_compressed",
+    "system.mem.zswap_pool_pct",
     "system.mem.dirty",
     "system.mem.writeback",
     "system.mem.anon_pages",
@@ -103,6 +107,9 @@ pub const MODEL_FIELD_IDS: &[&'static str] = &[
     "system.vm.pgscan_kswapd",
     "system.vm.pgscan_direct",
     "system.vm.oom_kill",
+    "system.perf.ipc",
+    "system.perf.cache_misses_per_sec",
+    "system.perf.branch_misses_per_sec",
     "system.disks.<key>.name",
     "system.disks.<key>.disk_usage",
     "system.disks.<key>.partition_size",
@@ -128,14 +135,30 @@ pub const MODEL_FIELD_IDS: &[&'static str] = &[
     "cgroup.[path:/<cgroup_path>/.]name",
     "cgroup.[path:/<cgroup_path>/.]full_path",
     "cgroup.[path:/<cgroup_path>/.]inode_number",
+    "cgroup.[path:/<cgroup_path>/.]reset",
+    "cgroup.[path:/<cgroup_path>/.]controllers",
+    "cgroup.[path:/<cgroup_path>/.]cpu_weight",
+    "cgroup.[path:/<cgroup_path>/.]io_weight",
+    "cgroup.[path:/<cgroup_path>/.]io_weight_devices",
+    "cgroup.[path:/<cgroup_path>/.]frozen",
+    "cgroup.[path:/<cgroup_path>/.]nr_descendants",
+    "cgroup.[path:/<cgroup_path>/.]nr_dying_descendants",
+    "cgroup.[path:/<cgroup_path>/.]device",
     "cgroup.[path:/<cgroup_path>/.]cpu.usage_pct",
     "cgroup.[path:/<cgroup_path>/.]cpu.user_pct",
     "cgroup.[path:/<cgroup_path>/.]cpu.system_pct",
     "cgroup.[path:/<cgroup_path>/.]cpu.nr_periods_per_sec",
     "cgroup.[path:/<cgroup_path>/.]cpu.nr_throttled_per_sec",
     "cgroup.[path:/<cgroup_path>/.]cpu.throttled_pct",
+    "cgroup.[path:/<cgroup_path>/.]cpu.usage_usec",
+    "cgroup.[path:/<cgroup_path>/.]cpu.user_usec",
+    "cgroup.[path:/<cgroup_path>/.]cpu.system_usec",
+    "cgroup.[path:/<cgroup_path>/.]cpu.nr_periods",
+    "cgroup.[path:/<cgroup_path>/.]cpu.nr_throttled",
+    "cgroup.[path:/<cgroup_path>/.]cpu.throttled_usec",
     "cgroup.[path:/<cgroup_path>/.]mem.total",
     "cgroup.[path:/<cgroup_path>/.]mem.swap",
+    "cgroup.[path:/<cgroup_path>/.]mem.zswap",
     "cgroup.[path:/<cgroup_path>/.]mem.anon",
     "cgroup.[path:/<cgroup_path>/.]mem.file",
     "cgroup.[path:/<cgroup_path>/.]mem.kernel_stack",
@@ -161,6 +184,10 @@ pub const MODEL_FIELD_IDS: &[&'static str] = &[
     "cgroup.[path:/<cgroup_path>/.]mem.pgrefill",
     "cgroup.[path:/<cgroup_path>/.]mem.pgscan",
     "cgroup.[path:/<cgroup_path>/.]mem.pgsteal",
+    "cgroup.[path:/<cgroup_path>/.]mem.pgscan_kswapd",
+    "cgroup.[path:/<cgroup_path>/.]mem.pgscan_direct",
+    "cgroup.[path:/<cgroup_path>/.]mem.pgsteal_kswapd",
+    "cgroup.[path:/<cgroup_path>/.]mem.pgsteal_direct",
     "cgroup.[path:/<cgroup_path>/.]mem.pgactivate",
     "cgroup.[path:/<cgroup_path>/.]mem.pgdeactivate",
     "cgroup.[path:/<cgroup_path>/.]mem.pglazyfree",
@@ -168,11 +195,18 @@ pub const MODEL_FIELD_IDS: &[&'static str] = &[
     "cgroup.[path:/<cgroup_path>/.]mem.thp_fault_alloc",
     "cgroup.[path:/<cgroup_path>/.]mem.thp_collapse_alloc",
     "cgroup.[path:/<cgroup_path>/.]mem.memory_high",
+    "cgroup.[path:/<cgroup_path>/.]mem.memory_max",
+    "cgroup.[path:/<cgroup_path>/.]mem.headroom",
     "cgroup.[path:/<cgroup_path>/.]mem.events_low",
     "cgroup.[path:/<cgroup_path>/.]mem.events_high",
     "cgroup.[path:/<cgroup_path>/.]mem.events_max",
     "cgroup.[path:/<cgroup_path>/.]mem.events_oom",
     "cgroup.[path:/<cgroup_path>/.]mem.events_oom_kill",
+    "cgroup.[path:/<cgroup_path>/.]mem.events_low_per_sec",
+    "cgroup.[path:/<cgroup_path>/.]mem.events_high_per_sec",
+    "cgroup.[path:/<cgroup_path>/.]mem.events_max_per_sec",
+    "cgroup.[path:/<cgroup_path>/.]mem.events_oom_per_sec",
+    "cgroup.[path:/<cgroup_path>/.]mem.events_oom_kill_per_sec",
     "cgroup.[path:/<cgroup_path>/.]io_details.<key>.rbytes_per_sec",
     "cgroup.[path:/<cgroup_path>/.]io_details.<key>.wbytes_per_sec",
     "cgroup.[path:/<cgroup_path>/.]io_details.<key>.rios_per_sec",
@@ -180,6 +214,9 @@ pub const MODEL_FIELD_IDS: &[&'static str] = &[
     "cgroup.[path:/<cgroup_path>/.]io_details.<key>.dbytes_per_sec",
     "cgroup.[path:/<cgroup_path>/.]io_details.<key>.dios_per_sec",
     "cgroup.[path:/<cgroup_path>/.]io_details.<key>.rwbytes_per_sec",
+    "cgroup.[path:/<cgroup_path>/.]io_details.<key>.cost_usage_pct",
+    "cgroup.[path:/<cgroup_path>/.]io_details.<key>.cost_vrate",
+    "cgroup.[path:/<cgroup_path>/.]io_details.<key>.cost_indebt_pct",
     "cgroup.[path:/<cgroup_path>/.]io.rbytes_per_sec",
     "cgroup.[path:/<cgroup_path>/.]io.wbytes_per_sec",
     "cgroup.[path:/<cgroup_path>/.]io.rios_per_sec",
@@ -187,17 +224,42 @@ pub const MODEL_FIELD_IDS: &[&'static str] = &[
     "cgroup.[path:/<cgroup_path>/.]io.dbytes_per_sec",
     "cgroup.[path:/<cgroup_path>/.]io.dios_per_sec",
     "cgroup.[path:/<cgroup_path>/.]io.rwbytes_per_sec",
+    "cgroup.[path:/<cgroup_path>/.]io.cost_usage_pct",
+    "cgroup.[path:/<cgroup_path>/.]io.cost_vrate",
+    "cgroup.[path:/<cgroup_path>/.]io.cost_indebt_pct",
     "cgroup.[path:/<cgroup_path>/.]pressure.cpu_some_pct",
     "cgroup.[path:/<cgroup_path>/.]pressure.io_some_pct",
     "cgroup.[path:/<cgroup_path>/.]pressure.io_full_pct",
     "cgroup.[path:/<cgroup_path>/.]pressure.memory_some_pct",
     "cgroup.[path:/<cgroup_path>/.]pressure.memory_full_pct",
+    "cgroup.[path:/<cgroup_path>/.]pressure.cpu_some_avg10",
+    "cgroup.[path:/<cgroup_path>/.]pressure.cpu_some_avg60",
+    "cgroup.[path:/<cgroup_path>/.]pressure.cpu_some_avg300",
+    "cgroup.[path:/<cgroup_path>/.]pressure.io_some_avg10",
+    "cgroup.[path:/<cgroup_path>/.]pressure.io_some_avg60",
+    "cgroup.[path:/<cgroup_path>/.]pressure.io_some_avg300",
+    "cgroup.[path:/<cgroup_path>/.]pressure.io_full_avg10",
+    "cgroup.[path:/<cgroup_path>/.]pressure.io_full_avg60",
+    "cgroup.[path:/<cgroup_path>/.]pressure.io_full_avg300",
+    "cgroup.[path:/<cgroup_path>/.]pressure.memory_some_avg10",
+    "cgroup.[path:/<cgroup_path>/.]pressure.memory_some_avg60",
+    "cgroup.[path:/<cgroup_path>/.]pressure.memory_some_avg300",
+    "cgroup.[path:/<cgroup_path>/.]pressure.memory_full_avg10",
+    "cgroup.[path:/<cgroup_path>/.]pressure.memory_full_avg60",
+    "cgroup.[path:/<cgroup_path>/.]pressure.memory_full_avg300",
     "process.processes.<key>.pid",
+    "process.processes.<key>.tid",
     "process.processes.<key>.ppid",
     "process.processes.<key>.comm",
     "process.processes.<key>.state",
+    "process.processes.<key>.wchan",
     "process.processes.<key>.uptime_secs",
+    "process.processes.<key>.age",
+    "process.processes.<key>.start_time",
+    "process.processes.<key>.reset",
     "process.processes.<key>.cgroup",
+    "process.processes.<key>.mem_some_pressure",
+    "process.processes.<key>.io_some_pressure",
     "process.processes.<key>.io.rbytes_per_sec",
     "process.processes.<key>.io.wbytes_per_sec",
     "process.processes.<key>.io.rwbytes_per_sec",
@@ -261,6 +323,10 @@ pub const MODEL_FIELD_IDS: &[&'static str] = &[
     "network.tcp.in_errs",
     "network.tcp.out_rsts_per_sec",
     "network.tcp.in_csum_errors",
+    "network.tcp.time_wait",
+    "network.tcp.close_wait",
+    "network.tcp.sockets_in_use",
+    "network.tcp.orphan_sockets",
     "network.ip.forwarding_pkts_per_sec",
     "network.ip.in_receives_pkts_per_sec",
     "network.ip.forw_datagrams_per_sec",
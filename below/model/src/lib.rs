@@ -27,6 +27,7 @@ pub mod collector_plugin;
 #[cfg(test)]
 mod field_ids;
 pub mod network;
+pub mod perf;
 pub mod process;
 pub mod sample;
 mod sample_model;
@@ -55,7 +56,9 @@ pub enum Field {
     F32(f32),
     F64(f64),
     Str(String),
+    Bool(bool),
     PidState(procfs::PidState),
+    StrList(Vec<String>),
 }
 
 impl From<Field> for i64 {
@@ -140,6 +143,18 @@ impl From<String> for Field {
     }
 }
 
+impl From<bool> for Field {
+    fn from(v: bool) -> Self {
+        Field::Bool(v)
+    }
+}
+
+impl From<Vec<String>> for Field {
+    fn from(v: Vec<String>) -> Self {
+        Field::StrList(v)
+    }
+}
+
 impl From<procfs::PidState> for Field {
     fn from(v: procfs::PidState) -> Self {
         Field::PidState(v)
@@ -177,7 +192,9 @@ impl PartialEq for Field {
             (Field::F32(s), Field::F32(o)) => s == o,
             (Field::F64(s), Field::F64(o)) => s == o,
             (Field::Str(s), Field::Str(o)) => s == o,
+            (Field::Bool(s), Field::Bool(o)) => s == o,
             (Field::PidState(s), Field::PidState(o)) => s == o,
+            (Field::StrList(s), Field::StrList(o)) => s == o,
             _ => false,
         }
     }
@@ -192,7 +209,9 @@ impl PartialOrd for Field {
             (Field::F32(s), Field::F32(o)) => s.partial_cmp(o),
             (Field::F64(s), Field::F64(o)) => s.partial_cmp(o),
             (Field::Str(s), Field::Str(o)) => s.partial_cmp(o),
+            (Field::Bool(s), Field::Bool(o)) => s.partial_cmp(o),
             (Field::PidState(s), Field::PidState(o)) => s.partial_cmp(o),
+            (Field::StrList(s), Field::StrList(o)) => s.partial_cmp(o),
             _ => None,
         }
     }
@@ -208,7 +227,9 @@ impl fmt::Display for Field {
             Field::F32(v) => v.fmt(f),
             Field::F64(v) => v.fmt(f),
             Field::Str(v) => v.fmt(f),
+            Field::Bool(v) => v.fmt(f),
             Field::PidState(v) => v.fmt(f),
+            Field::StrList(v) => v.join(",").fmt(f),
         }
     }
 }
@@ -406,19 +427,25 @@ impl Model {
     /// `CumulativeSample` as well as the `Duration` since it was
     /// collected.
     pub fn new(timestamp: SystemTime, sample: &Sample, last: Option<(&Sample, Duration)>) -> Self {
+        let cgroup = CgroupModel::new(
+            "<root>".to_string(),
+            String::new(),
+            0,
+            &sample.cgroup,
+            last.map(|(s, d)| (&s.cgroup, d)),
+        )
+        .aggr_top_level_val();
         Model {
             time_elapsed: last.map(|(_, d)| d).unwrap_or_default(),
             timestamp,
             system: SystemModel::new(&sample.system, last.map(|(s, d)| (&s.system, d))),
-            cgroup: CgroupModel::new(
-                "<root>".to_string(),
-                String::new(),
-                0,
-                &sample.cgroup,
-                last.map(|(s, d)| (&s.cgroup, d)),
-            )
-            .aggr_top_level_val(),
-            process: ProcessModel::new(&sample.processes, last.map(|(s, d)| (&s.processes, d))),
+            process: ProcessModel::new(
+                &sample.processes,
+                last.map(|(s, d)| (&s.processes, d)),
+                &cgroup,
+                sample.system.stat.boot_time_epoch_secs,
+            ),
+            cgroup,
             network: NetworkModel::new(&sample.netstats, last.map(|(s, d)| (&s.netstats, d))),
         }
     }
@@ -60,6 +60,8 @@ impl NetworkModel {
             tcp: TcpModel::new(
                 sample.tcp.as_ref().unwrap_or(&Default::default()),
                 last.and_then(|(n, d)| n.tcp.as_ref().map(|n| (n, d))),
+                sample.sockstat.as_ref().unwrap_or(&Default::default()),
+                sample.tcp_close_wait,
             ),
             ip: IpModel::new(
                 sample.ip.as_ref().unwrap_or(&Default::default()),
@@ -105,12 +107,33 @@ pub struct TcpModel {
     pub in_errs: Option<u64>,
     pub out_rsts_per_sec: Option<u64>,
     pub in_csum_errors: Option<u64>,
+    // Socket counts below cover IPv4 and IPv6. close_wait and sockets_in_use
+    // are summed from separate IPv4 and IPv6 counts, time_wait and
+    // orphan_sockets are already counted for both by the kernel. The
+    // established count is curr_estab_conn above.
+    pub time_wait: Option<u64>,
+    pub close_wait: Option<u64>,
+    pub sockets_in_use: Option<u64>,
+    pub orphan_sockets: Option<u64>,
     // Collected TcpExt stats, but not going to display. If we got feedback that user do need
     // those stats, we can add those here.
 }
 
+/// Sums IPv4 and IPv6 counts, treating a missing IPv6 count as zero.
+fn sum_v4_v6(v4: Option<u64>, v6: Option<u64>) -> Option<u64> {
+    match (v4, v6) {
+        (None, None) => None,
+        (v4, v6) => Some(v4.unwrap_or_default() + v6.unwrap_or_default()),
+    }
+}
+
 impl TcpModel {
-    pub fn new(sample: &procfs::TcpStat, last: Option<(&procfs::TcpStat, Duration)>) -> TcpModel {
+    pub fn new(
+        sample: &procfs::TcpStat,
+        last: Option<(&procfs::TcpStat, Duration)>,
+        sockstat: &procfs::SockStat,
+        close_wait: Option<u64>,
+    ) -> TcpModel {
         TcpModel {
             active_opens_per_sec: get_option_rate!(active_opens, sample, last),
             passive_opens_per_sec: get_option_rate!(passive_opens, sample, last),
@@ -124,6 +147,11 @@ impl TcpModel {
             in_errs: sample.in_errs.map(|s| s as u64),
             out_rsts_per_sec: get_option_rate!(out_rsts, sample, last),
             in_csum_errors: sample.in_csum_errors.map(|s| s as u64),
+            // TIME_WAIT and orphan counts in /proc/net/sockstat already cover both families
+            time_wait: sockstat.tcp_tw,
+            close_wait,
+            sockets_in_use: sum_v4_v6(sockstat.tcp_inuse, sockstat.tcp6_inuse),
+            orphan_sockets: sockstat.tcp_orphan,
         }
     }
 }
@@ -0,0 +1,216 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! System wide hardware counters read with perf_event_open(2).
+
+use std::fs::File;
+use std::io::Read;
+use std::os::unix::io::FromRawFd;
+
+use super::*;
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1;
+const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 2;
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 8;
+
+/// First published layout of struct perf_event_attr (PERF_ATTR_SIZE_VER0).
+/// The kernel zero fills fields added after it.
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+}
+
+/// Cumulative hardware counters summed over all CPUs. Counters the CPU or
+/// the permissions don't allow are None.
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PerfStat {
+    pub cycles: Option<u64>,
+    pub instructions: Option<u64>,
+    pub cache_misses: Option<u64>,
+    pub branch_misses: Option<u64>,
+}
+
+/// One hardware event counted on every online CPU.
+struct Counter {
+    files: Vec<File>,
+}
+
+impl Counter {
+    fn open(config: u64, cpus: &[i32]) -> std::io::Result<Self> {
+        let attr = PerfEventAttr {
+            type_: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+            ..Default::default()
+        };
+        let mut files = Vec::with_capacity(cpus.len());
+        for cpu in cpus {
+            // SAFETY: attr outlives the call and the kernel only reads `size`
+            // bytes of it. pid -1 with a cpu counts everything on that cpu.
+            let fd = unsafe {
+                libc::syscall(
+                    libc::SYS_perf_event_open,
+                    &attr as *const PerfEventAttr,
+                    -1 as libc::pid_t,
+                    *cpu as libc::c_int,
+                    -1 as libc::c_int,
+                    PERF_FLAG_FD_CLOEXEC,
+                )
+            };
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            // SAFETY: fd was just returned by perf_event_open and is owned
+            // by nothing else.
+            files.push(unsafe { File::from_raw_fd(fd as i32) });
+        }
+        Ok(Self { files })
+    }
+
+    /// Sum of the counts of all CPUs, scaled up for the time the event was
+    /// not scheduled on the PMU because of multiplexing.
+    fn read(&self) -> Option<u64> {
+        let mut total: u64 = 0;
+        for file in &self.files {
+            let mut buf = [0u8; 24];
+            (&*file).read_exact(&mut buf).ok()?;
+            let value = |idx: usize| {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&buf[idx * 8..idx * 8 + 8]);
+                u64::from_ne_bytes(bytes)
+            };
+            total = total.saturating_add(scale_count(value(0), value(1), value(2)));
+        }
+        Some(total)
+    }
+}
+
+/// Estimate the full count of an event that only ran for `running` out of
+/// `enabled` nanoseconds.
+pub fn scale_count(count: u64, enabled: u64, running: u64) -> u64 {
+    if running == 0 || running >= enabled {
+        return count;
+    }
+    (count as u128 * enabled as u128 / running as u128).min(u64::MAX as u128) as u64
+}
+
+/// Parse a cpu list such as /sys/devices/system/cpu/online, e.g. "0-3,8".
+pub fn parse_cpu_list(s: &str) -> Result<Vec<i32>> {
+    let mut cpus = Vec::new();
+    for range in s.trim().split(',').filter(|r| !r.is_empty()) {
+        let parse = |v: &str| {
+            v.parse::<i32>()
+                .with_context(|| format!("Invalid cpu list: {}", s.trim()))
+        };
+        match range.split_once('-') {
+            Some((first, last)) => cpus.extend(parse(first)?..=parse(last)?),
+            None => cpus.push(parse(range)?),
+        }
+    }
+    Ok(cpus)
+}
+
+/// Open hardware counters of all online CPUs. Needs CAP_PERFMON (or root)
+/// unless kernel.perf_event_paranoid allows system wide events.
+pub struct PerfCounters {
+    cycles: Option<Counter>,
+    instructions: Option<Counter>,
+    cache_misses: Option<Counter>,
+    branch_misses: Option<Counter>,
+}
+
+impl PerfCounters {
+    /// Fails if none of the counters could be opened, e.g. without
+    /// permission or in a VM without a virtual PMU. Otherwise the unsupported
+    /// ones are left out.
+    pub fn new() -> Result<Self> {
+        let cpus = parse_cpu_list(
+            &std::fs::read_to_string("/sys/devices/system/cpu/online")
+                .context("Failed to read online cpus")?,
+        )?;
+        let mut first_err = None;
+        let mut open = |config| match Counter::open(config, &cpus) {
+            Ok(counter) => Some(counter),
+            Err(e) => {
+                first_err.get_or_insert(e);
+                None
+            }
+        };
+        let counters = Self {
+            cycles: open(PERF_COUNT_HW_CPU_CYCLES),
+            instructions: open(PERF_COUNT_HW_INSTRUCTIONS),
+            cache_misses: open(PERF_COUNT_HW_CACHE_MISSES),
+            branch_misses: open(PERF_COUNT_HW_BRANCH_MISSES),
+        };
+        match first_err {
+            Some(e)
+                if counters.cycles.is_none()
+                    && counters.instructions.is_none()
+                    && counters.cache_misses.is_none()
+                    && counters.branch_misses.is_none() =>
+            {
+                Err(e).context("Failed to open perf counters")
+            }
+            _ => Ok(counters),
+        }
+    }
+
+    pub fn read(&self) -> PerfStat {
+        PerfStat {
+            cycles: self.cycles.as_ref().and_then(Counter::read),
+            instructions: self.instructions.as_ref().and_then(Counter::read),
+            cache_misses: self.cache_misses.as_ref().and_then(Counter::read),
+            branch_misses: self.branch_misses.as_ref().and_then(Counter::read),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cpu_list() {
+        assert_eq!(parse_cpu_list("0\n").unwrap(), vec![0]);
+        assert_eq!(
+            parse_cpu_list("0-3,8,10-11\n").unwrap(),
+            vec![0, 1, 2, 3, 8, 10, 11]
+        );
+        assert!(parse_cpu_list("0-x").is_err());
+    }
+
+    #[test]
+    fn scaled_count() {
+        assert_eq!(scale_count(100, 10, 10), 100);
+        assert_eq!(scale_count(100, 10, 5), 200);
+        // Never scheduled
+        assert_eq!(scale_count(0, 10, 0), 0);
+    }
+}
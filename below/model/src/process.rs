@@ -34,34 +34,77 @@ macro_rules! fold_optionals {
 pub struct ProcessModel {
     #[queriable(subquery)]
     pub processes: BTreeMap<i32, SingleProcessModel>,
+    /// Threads of the processes by tid, if recorded with `below record
+    /// --threads`. Only the cpu and per-thread stat fields are per thread,
+    /// the rest is copied from the process and io and mem are left out.
+    #[queriable(ignore)]
+    #[serde(default)]
+    pub threads: BTreeMap<i32, SingleProcessModel>,
 }
 
 impl ProcessModel {
-    pub fn new(sample: &procfs::PidMap, last: Option<(&procfs::PidMap, Duration)>) -> ProcessModel {
+    /// `cgroup` is the cgroup tree of the same sample, used to attribute
+    /// cgroup pressure to processes. `boot_time_epoch_secs` turns the start
+    /// time of processes into an absolute time.
+    pub fn new(
+        sample: &procfs::PidMap,
+        last: Option<(&procfs::PidMap, Duration)>,
+        cgroup: &CgroupModel,
+        boot_time_epoch_secs: Option<u64>,
+    ) -> ProcessModel {
         let mut processes: BTreeMap<i32, SingleProcessModel> = BTreeMap::new();
+        let mut threads: BTreeMap<i32, SingleProcessModel> = BTreeMap::new();
 
         for (pid, pidinfo) in sample.iter() {
-            processes.insert(
-                *pid,
-                SingleProcessModel::new(
-                    &pidinfo,
-                    last.and_then(|(p, d)| p.get(pid).map(|p| (p, d))),
-                ),
-            );
+            let last = last.and_then(|(p, d)| p.get(pid).map(|p| (p, d)));
+            let process = SingleProcessModel::new(pidinfo, last, cgroup, boot_time_epoch_secs);
+            for (tid, stat) in pidinfo.threads.iter().flatten() {
+                let last_stat = last
+                    .and_then(|(l, d)| l.threads.as_ref().and_then(|t| t.get(tid)).map(|s| (s, d)));
+                threads.insert(
+                    *tid,
+                    SingleProcessModel::new_thread(
+                        &process,
+                        *tid,
+                        stat,
+                        last_stat,
+                        boot_time_epoch_secs,
+                    ),
+                );
+            }
+            processes.insert(*pid, process);
         }
 
-        ProcessModel { processes }
+        ProcessModel { processes, threads }
     }
 }
 
 #[derive(Default, Clone, Serialize, Deserialize, below_derive::Queriable)]
 pub struct SingleProcessModel {
     pub pid: Option<i32>,
+    /// Thread id, only set for threads
+    pub tid: Option<i32>,
     pub ppid: Option<i32>,
     pub comm: Option<String>,
     pub state: Option<procfs::PidState>,
+    /// Kernel function the process sleeps in, if recorded with `below record
+    /// --wchan`. Empty while the process is running.
+    pub wchan: Option<String>,
+    /// Also queried as `age`, which is rendered as a duration.
+    #[queriable(alias = age)]
     pub uptime_secs: Option<u64>,
+    /// Epoch time the process started, from the boot time and the start time
+    /// in /proc/<pid>/stat.
+    pub start_time: Option<u64>,
+    /// Whether the counters were reset since the last sample, e.g. because
+    /// the pid was reused. Rates are empty then. None without a last sample.
+    pub reset: Option<bool>,
     pub cgroup: Option<String>,
+    /// Memory some pressure of the process's cgroup. Per-process PSI isn't
+    /// available, so this is the same for every process in the cgroup.
+    pub mem_some_pressure: Option<f64>,
+    /// I/O some pressure of the process's cgroup, see `mem_some_pressure`.
+    pub io_some_pressure: Option<f64>,
     #[queriable(subquery)]
     pub io: Option<ProcessIoModel>,
     #[queriable(subquery)]
@@ -72,18 +115,50 @@ pub struct SingleProcessModel {
     pub exe_path: Option<String>,
 }
 
+/// Whether the stat of a pid or tid can't be compared with the last one,
+/// because the id was reused by a new process or thread or its cpu time or
+/// fault counters went down.
+fn is_stat_reset(last: &procfs::PidStat, curr: &procfs::PidStat) -> bool {
+    matches!(
+        (last.start_secs_since_boot, curr.start_secs_since_boot),
+        (Some(last), Some(curr)) if last != curr
+    ) || counter_decreased(last.user_usecs, curr.user_usecs)
+        || counter_decreased(last.system_usecs, curr.system_usecs)
+        || counter_decreased(last.minflt, curr.minflt)
+        || counter_decreased(last.majflt, curr.majflt)
+}
+
 impl SingleProcessModel {
     fn new(
         sample: &procfs::PidInfo,
         last: Option<(&procfs::PidInfo, Duration)>,
+        cgroup: &CgroupModel,
+        boot_time_epoch_secs: Option<u64>,
     ) -> SingleProcessModel {
+        let pressure = cgroup
+            .get_by_path(&sample.cgroup)
+            .and_then(|cgroup| cgroup.data.pressure.as_ref());
+        let reset = last.map(|(l, _)| {
+            is_stat_reset(&l.stat, &sample.stat)
+                || counter_decreased(l.io.rbytes, sample.io.rbytes)
+                || counter_decreased(l.io.wbytes, sample.io.wbytes)
+        });
+        let last = last.filter(|_| reset != Some(true));
         SingleProcessModel {
             pid: sample.stat.pid,
+            tid: None,
             ppid: sample.stat.ppid,
             comm: sample.stat.comm.clone(),
             state: sample.stat.state.clone(),
+            wchan: sample.wchan.clone(),
             uptime_secs: sample.stat.running_secs.map(|s| s as u64),
+            start_time: boot_time_epoch_secs
+                .zip(sample.stat.start_secs_since_boot)
+                .map(|(boot, start)| boot + start),
+            reset,
             cgroup: Some(sample.cgroup.clone()),
+            mem_some_pressure: pressure.and_then(|p| p.memory_some_pct),
+            io_some_pressure: pressure.and_then(|p| p.io_some_pct),
             io: last.map(|(l, d)| ProcessIoModel::new(&l.io, &sample.io, d)),
             mem: last.map(|(l, d)| ProcessMemoryModel::new(&l, &sample, d)),
             cpu: last.map(|(l, d)| ProcessCpuModel::new(&l.stat, &sample.stat, d)),
@@ -96,17 +171,55 @@ impl SingleProcessModel {
         }
     }
 
+    /// Model of a thread of `process`. comm, state, uptime and cpu are read
+    /// from the thread's own stat, io, mem and wchan are per process only
+    /// and left out, everything else is the same as the process.
+    fn new_thread(
+        process: &SingleProcessModel,
+        tid: i32,
+        stat: &procfs::PidStat,
+        last: Option<(&procfs::PidStat, Duration)>,
+        boot_time_epoch_secs: Option<u64>,
+    ) -> SingleProcessModel {
+        let reset = last.map(|(l, _)| is_stat_reset(l, stat));
+        let last = last.filter(|_| reset != Some(true));
+        SingleProcessModel {
+            tid: Some(tid),
+            comm: stat.comm.clone(),
+            state: stat.state.clone(),
+            wchan: None,
+            uptime_secs: stat.running_secs,
+            start_time: boot_time_epoch_secs
+                .zip(stat.start_secs_since_boot)
+                .map(|(boot, start)| boot + start),
+            reset,
+            io: None,
+            mem: None,
+            cpu: last.map(|(l, d)| ProcessCpuModel::new(l, stat, d)),
+            ..process.clone()
+        }
+    }
+
     /// Sums stats between two process models together, None'ing out fields that semantically
     /// cannot be summed
     pub fn fold(left: &SingleProcessModel, right: &SingleProcessModel) -> SingleProcessModel {
         SingleProcessModel {
             pid: None,
+            tid: None,
             ppid: None,
             comm: None,
             state: None,
+            wchan: None,
             // 80% sure it should be None here. Don't know what someone can infer from summed uptime
             uptime_secs: None,
+            start_time: None,
+            reset: match (left.reset, right.reset) {
+                (Some(left), Some(right)) => Some(left || right),
+                (left, right) => left.or(right),
+            },
             cgroup: None,
+            mem_some_pressure: None,
+            io_some_pressure: None,
             io: fold_optionals!(&left.io, &right.io, ProcessIoModel::fold),
             mem: fold_optionals!(&left.mem, &right.mem, ProcessMemoryModel::fold),
             cpu: fold_optionals!(&left.cpu, &right.cpu, ProcessCpuModel::fold),
@@ -260,4 +373,197 @@ mod test {
             Some(Field::Str("systemd".to_owned()))
         );
     }
+
+    #[test]
+    fn attribute_cgroup_pressure() {
+        let cgroup_json = r#"
+        {
+            "data": { "name": "<root>", "full_path": "", "depth": 0 },
+            "count": 3,
+            "recreate_flag": false,
+            "children": [
+                {
+                    "data": { "name": "system.slice", "full_path": "/system.slice", "depth": 1 },
+                    "count": 2,
+                    "recreate_flag": false,
+                    "children": [
+                        {
+                            "data": {
+                                "name": "foo.service",
+                                "full_path": "/system.slice/foo.service",
+                                "depth": 2,
+                                "pressure": { "io_some_pct": 1.5, "memory_some_pct": 2.5 }
+                            },
+                            "count": 1,
+                            "recreate_flag": false,
+                            "children": []
+                        }
+                    ]
+                }
+            ]
+        }
+        "#;
+        let cgroup: CgroupModel = serde_json::from_str(cgroup_json).unwrap();
+        let mut sample = procfs::PidMap::new();
+        for (pid, cgroup_path) in [(1, "/system.slice/foo.service"), (2, "/no_such.slice")] {
+            sample.insert(
+                pid,
+                procfs::PidInfo {
+                    cgroup: cgroup_path.to_owned(),
+                    ..Default::default()
+                },
+            );
+        }
+        let model = ProcessModel::new(&sample, None, &cgroup, None);
+        assert_eq!(model.processes[&1].mem_some_pressure, Some(2.5));
+        assert_eq!(model.processes[&1].io_some_pressure, Some(1.5));
+        // Unknown cgroups have no pressure
+        assert_eq!(model.processes[&2].mem_some_pressure, None);
+        assert_eq!(model.processes[&2].io_some_pressure, None);
+    }
+
+    #[test]
+    fn process_start_time() {
+        let mut sample = procfs::PidMap::new();
+        sample.insert(
+            1,
+            procfs::PidInfo {
+                stat: procfs::PidStat {
+                    start_secs_since_boot: Some(100),
+                    running_secs: Some(50),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        let cgroup = CgroupModel::default();
+        let model = ProcessModel::new(&sample, None, &cgroup, Some(1_000_000_000));
+        assert_eq!(model.processes[&1].start_time, Some(1_000_000_100));
+        // The age is the uptime under another name
+        assert_eq!(
+            model.processes[&1]
+                .query(&SingleProcessModelFieldId::Age)
+                .map(|v| v.to_string()),
+            Some("50".to_owned())
+        );
+        // No absolute start time without the boot time
+        let model = ProcessModel::new(&sample, None, &cgroup, None);
+        assert_eq!(model.processes[&1].start_time, None);
+    }
+
+    #[test]
+    fn process_threads() {
+        let pidinfo = |user_usecs: u64, threads: &[(i32, &str, u64)]| procfs::PidInfo {
+            stat: procfs::PidStat {
+                pid: Some(1),
+                comm: Some("server".to_owned()),
+                user_usecs: Some(user_usecs),
+                ..Default::default()
+            },
+            cgroup: "/foo.service".to_owned(),
+            wchan: Some("futex_wait_queue_me".to_owned()),
+            threads: Some(
+                threads
+                    .iter()
+                    .map(|(tid, comm, user_usecs)| {
+                        (
+                            *tid,
+                            procfs::PidStat {
+                                pid: Some(*tid),
+                                comm: Some(comm.to_string()),
+                                user_usecs: Some(*user_usecs),
+                                ..Default::default()
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        let mut last = procfs::PidMap::new();
+        last.insert(1, pidinfo(0, &[(1, "server", 0), (2, "worker", 0)]));
+        let mut sample = procfs::PidMap::new();
+        sample.insert(
+            1,
+            pidinfo(
+                1_500_000,
+                &[
+                    (1, "server", 500_000),
+                    (2, "worker", 1_000_000),
+                    (3, "new", 0),
+                ],
+            ),
+        );
+        let cgroup = CgroupModel::default();
+        let model = ProcessModel::new(
+            &sample,
+            Some((&last, Duration::from_secs(1))),
+            &cgroup,
+            None,
+        );
+        assert_eq!(model.processes[&1].tid, None);
+        assert_eq!(
+            model.processes[&1].wchan,
+            Some("futex_wait_queue_me".to_owned())
+        );
+        assert_eq!(
+            model.threads.keys().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        let worker = &model.threads[&2];
+        assert_eq!(worker.pid, Some(1));
+        assert_eq!(worker.tid, Some(2));
+        assert_eq!(worker.comm, Some("worker".to_owned()));
+        assert_eq!(worker.cgroup, Some("/foo.service".to_owned()));
+        assert_eq!(worker.cpu.as_ref().and_then(|c| c.user_pct), Some(100.0));
+        assert!(worker.mem.is_none() && worker.io.is_none() && worker.wchan.is_none());
+        assert_eq!(
+            model.threads[&1].cpu.as_ref().and_then(|c| c.user_pct),
+            Some(50.0)
+        );
+        // No cpu for threads that didn't exist in the last sample
+        assert!(model.threads[&3].cpu.is_none());
+    }
+
+    #[test]
+    fn process_counter_reset() {
+        let pidinfo = |start_secs: u64, user_usecs: u64| procfs::PidInfo {
+            stat: procfs::PidStat {
+                pid: Some(1),
+                start_secs_since_boot: Some(start_secs),
+                user_usecs: Some(user_usecs),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let model = |last: procfs::PidInfo, sample: procfs::PidInfo| {
+            let last = vec![(1, last)].into_iter().collect::<procfs::PidMap>();
+            let sample = vec![(1, sample)].into_iter().collect::<procfs::PidMap>();
+            ProcessModel::new(
+                &sample,
+                Some((&last, Duration::from_secs(1))),
+                &CgroupModel::default(),
+                None,
+            )
+            .processes
+            .remove(&1)
+            .unwrap()
+        };
+
+        let spm = model(pidinfo(10, 0), pidinfo(10, 500_000));
+        assert_eq!(spm.reset, Some(false));
+        assert_eq!(spm.cpu.and_then(|cpu| cpu.user_pct), Some(50.0));
+        // Pid reused by a process that already used more cpu
+        let spm = model(pidinfo(10, 0), pidinfo(20, 500_000));
+        assert_eq!(spm.reset, Some(true));
+        assert!(spm.cpu.is_none());
+        let spm = model(pidinfo(10, 500_000), pidinfo(10, 0));
+        assert_eq!(spm.reset, Some(true));
+
+        let sample = vec![(1, pidinfo(10, 0))]
+            .into_iter()
+            .collect::<procfs::PidMap>();
+        let model = ProcessModel::new(&sample, None, &CgroupModel::default(), None);
+        assert_eq!(model.processes[&1].reset, None);
+    }
 }
@@ -32,8 +32,14 @@ pub struct CgroupSample {
     pub children: Option<BTreeMap<String, CgroupSample>>,
     pub memory_swap_current: Option<i64>,
     pub memory_high: Option<i64>,
+    pub memory_max: Option<i64>,
     pub memory_events: Option<cgroupfs::MemoryEvents>,
     pub inode_number: Option<i64>,
+    pub cgroup_controllers: Option<Vec<String>>,
+    pub cpu_weight: Option<u32>,
+    pub io_weight: Option<cgroupfs::IoWeight>,
+    pub frozen: Option<bool>,
+    pub cgroup_stat: Option<cgroupfs::CgroupStat>,
 }
 
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -45,4 +51,5 @@ pub struct SystemSample {
     pub disks: procfs::DiskMap,
     pub kernel_version: Option<String>,
     pub os_release: Option<String>,
+    pub perf: Option<perf::PerfStat>,
 }
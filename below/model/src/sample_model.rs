@@ -85,6 +85,7 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
             "total": 8000000000,
             "free": 4000000000,
             "available": 8000000000,
+            "headroom_pct": 100.0,
             "buffers": 20000,
             "cached": 2000000000,
             "swap_cached": 10000000,
@@ -96,6 +97,9 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
             "mlocked": 3000000,
             "swap_total": 2000000000,
             "swap_free": 1000000000,
+            "zswap_total": 400000000,
+            "zswap_compressed": 100000000,
+            "zswap_pool_pct": 1.25,
             "dirty": 500000,
             "writeback": 10000,
             "anon_pages": 2000000000,
@@ -131,6 +135,11 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
             "pgscan_direct": 0,
             "oom_kill": 0
         },
+        "perf": {
+            "ipc": 1.25,
+            "cache_misses_per_sec": 12345.0,
+            "branch_misses_per_sec": 6789.0
+        },
         "disks": {
             "vda": {
                 "name": "vda",
@@ -181,11 +190,21 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
             "name": "<root>",
             "full_path": "",
             "inode_number": 1,
+            "reset": false,
+            "controllers": ["cpuset", "cpu", "io", "memory", "pids"],
+            "cpu_weight": 100,
+            "io_weight": 100,
+            "io_weight_devices": [],
+            "frozen": null,
+            "nr_descendants": 4,
+            "nr_dying_descendants": 1,
+            "device": null,
             "depth": 0,
             "cpu": null,
             "memory": {
                 "total": 5000000000,
                 "swap": 1000000000,
+                "zswap": 0,
                 "anon": 2000000000,
                 "file": 2000000000,
                 "kernel_stack": 40000000,
@@ -211,6 +230,10 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                 "pgrefill": 0,
                 "pgscan": 0,
                 "pgsteal": 0,
+                "pgscan_kswapd": 0,
+                "pgscan_direct": 0,
+                "pgsteal_kswapd": 0,
+                "pgsteal_direct": 0,
                 "pgactivate": 0,
                 "pgdeactivate": 0,
                 "pglazyfree": 0,
@@ -218,11 +241,18 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                 "thp_fault_alloc": 0,
                 "thp_collapse_alloc": 0,
                 "memory_high": null,
+                "memory_max": null,
+                "headroom": null,
                 "events_low": 0,
                 "events_high": 300000,
                 "events_max": 300000,
                 "events_oom": 0,
-                "events_oom_kill": 0
+                "events_oom_kill": 0,
+                "events_low_per_sec": 0.0,
+                "events_high_per_sec": 12.5,
+                "events_max_per_sec": 12.5,
+                "events_oom_per_sec": 0.0,
+                "events_oom_kill_per_sec": 0.0
             },
             "io": null,
             "io_total": null,
@@ -231,7 +261,22 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                 "io_some_pct": 0.8,
                 "io_full_pct": 0.7,
                 "memory_some_pct": 0.6,
-                "memory_full_pct": 0.3
+                "memory_full_pct": 0.3,
+                "cpu_some_avg10": 2.05,
+                "cpu_some_avg60": 1.5,
+                "cpu_some_avg300": 1.1,
+                "io_some_avg10": 0.8,
+                "io_some_avg60": 0.5,
+                "io_some_avg300": 0.3,
+                "io_full_avg10": 0.7,
+                "io_full_avg60": 0.4,
+                "io_full_avg300": 0.2,
+                "memory_some_avg10": 0.6,
+                "memory_some_avg60": 0.35,
+                "memory_some_avg300": 0.15,
+                "memory_full_avg10": 0.3,
+                "memory_full_avg60": 0.2,
+                "memory_full_avg300": 0.1
             }
         },
         "children": [
@@ -247,7 +292,13 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                         "system_pct": 0.00400,
                         "nr_periods_per_sec": 0.0,
                         "nr_throttled_per_sec": 0.0,
-                        "throttled_pct": 0.0
+                        "throttled_pct": 0.0,
+                        "usage_usec": 12000000,
+                        "user_usec": 9000000,
+                        "system_usec": 3000000,
+                        "nr_periods": 0,
+                        "nr_throttled": 0,
+                        "throttled_usec": 0
                     },
                     "memory": {
                         "total": 20000000,
@@ -277,6 +328,10 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                         "pgrefill": 0,
                         "pgscan": 0,
                         "pgsteal": 0,
+                        "pgscan_kswapd": 0,
+                        "pgscan_direct": 0,
+                        "pgsteal_kswapd": 0,
+                        "pgsteal_direct": 0,
                         "pgactivate": 0,
                         "pgdeactivate": 0,
                         "pglazyfree": 0,
@@ -284,6 +339,8 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                         "thp_fault_alloc": 0,
                         "thp_collapse_alloc": 0,
                         "memory_high": -1,
+                        "memory_max": -1,
+                        "headroom": null,
                         "events_low": 0,
                         "events_high": 0,
                         "events_max": 0,
@@ -346,6 +403,10 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                         "pgrefill": 0,
                         "pgscan": 0,
                         "pgsteal": 0,
+                        "pgscan_kswapd": 0,
+                        "pgscan_direct": 0,
+                        "pgsteal_kswapd": 0,
+                        "pgsteal_direct": 0,
                         "pgactivate": 0,
                         "pgdeactivate": 0,
                         "pglazyfree": 0,
@@ -353,6 +414,8 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                         "thp_fault_alloc": 0,
                         "thp_collapse_alloc": 0,
                         "memory_high": -1,
+                        "memory_max": -1,
+                        "headroom": null,
                         "events_low": 0,
                         "events_high": 0,
                         "events_max": 0,
@@ -415,6 +478,10 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                         "pgrefill": 0,
                         "pgscan": 0,
                         "pgsteal": 0,
+                        "pgscan_kswapd": 0,
+                        "pgscan_direct": 0,
+                        "pgsteal_kswapd": 0,
+                        "pgsteal_direct": 0,
                         "pgactivate": 0,
                         "pgdeactivate": 0,
                         "pglazyfree": 0,
@@ -422,6 +489,8 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                         "thp_fault_alloc": 0,
                         "thp_collapse_alloc": 0,
                         "memory_high": -1,
+                        "memory_max": -1,
+                        "headroom": null,
                         "events_low": 0,
                         "events_high": 0,
                         "events_max": 0,
@@ -481,6 +550,10 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                                 "pgrefill": 0,
                                 "pgscan": 0,
                                 "pgsteal": 0,
+                                "pgscan_kswapd": 0,
+                                "pgscan_direct": 0,
+                                "pgsteal_kswapd": 0,
+                                "pgsteal_direct": 0,
                                 "pgactivate": 0,
                                 "pgdeactivate": 0,
                                 "pglazyfree": 0,
@@ -488,6 +561,8 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                                 "thp_fault_alloc": 0,
                                 "thp_collapse_alloc": 0,
                                 "memory_high": -1,
+                                "memory_max": -1,
+                                "headroom": null,
                                 "events_low": 0,
                                 "events_high": 0,
                                 "events_max": 0,
@@ -523,8 +598,13 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                 "ppid": 0,
                 "comm": "systemd",
                 "state": "Running",
+                "wchan": "",
                 "uptime_secs": 4000000,
+                "start_time": 1000001000,
+                "reset": false,
                 "cgroup": "/init.scope",
+                "mem_some_pressure": 0.0,
+                "io_some_pressure": 0.0,
                 "io": {
                     "rbytes_per_sec": 0.0,
                     "wbytes_per_sec": 0.0,
@@ -553,6 +633,29 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
                 "cmdline": "/usr/lib/systemd/systemd",
                 "exe_path": "/usr/lib/systemd/systemd"
             }
+        },
+        "threads": {
+            "1": {
+                "pid": 1,
+                "tid": 1,
+                "ppid": 0,
+                "comm": "systemd",
+                "state": "Running",
+                "uptime_secs": 4000000,
+                "start_time": 1000001000,
+                "reset": false,
+                "cgroup": "/init.scope",
+                "mem_some_pressure": 0.0,
+                "io_some_pressure": 0.0,
+                "cpu": {
+                    "usage_pct": 1.0,
+                    "user_pct": 1.0,
+                    "system_pct": 0.5,
+                    "num_threads": 1
+                },
+                "cmdline": "/usr/lib/systemd/systemd",
+                "exe_path": "/usr/lib/systemd/systemd"
+            }
         }
     },
     "network": {
@@ -634,7 +737,11 @@ pub const SAMPLE_MODEL_JSON: &str = r#"
             "retrans_segs": 70000000,
             "in_errs": 5000,
             "out_rsts_per_sec": 10,
-            "in_csum_errors": 100
+            "in_csum_errors": 100,
+            "time_wait": 300,
+            "close_wait": 50,
+            "sockets_in_use": 1020,
+            "orphan_sockets": 2
         },
         "ip": {
             "forwarding_pkts_per_sec": 0,
@@ -39,6 +39,8 @@ pub struct SystemModel {
     #[queriable(subquery)]
     pub vm: VmModel,
     #[queriable(subquery)]
+    pub perf: PerfModel,
+    #[queriable(subquery)]
     pub disks: BTreeMap<String, SingleDiskModel>,
 }
 
@@ -67,6 +69,13 @@ impl SystemModel {
         let vm = last
             .map(|(last, duration)| VmModel::new(&last.vmstat, &sample.vmstat, duration))
             .unwrap_or_default();
+        let perf = match (
+            last.and_then(|(last, duration)| last.perf.as_ref().map(|perf| (perf, duration))),
+            sample.perf.as_ref(),
+        ) {
+            (Some((prev, duration)), Some(curr)) => PerfModel::new(prev, curr, duration),
+            _ => Default::default(),
+        };
         let mut disks: BTreeMap<String, SingleDiskModel> = BTreeMap::new();
         sample.disks.iter().for_each(|(disk_name, end_disk_stat)| {
             disks.insert(
@@ -96,6 +105,7 @@ impl SystemModel {
             cpus,
             mem,
             vm,
+            perf,
             disks,
         }
     }
@@ -236,6 +246,8 @@ pub struct MemoryModel {
     pub total: Option<u64>,
     pub free: Option<u64>,
     pub available: Option<u64>,
+    /// available as a percentage of total memory
+    pub headroom_pct: Option<f64>,
     pub buffers: Option<u64>,
     pub cached: Option<u64>,
     pub swap_cached: Option<u64>,
@@ -247,6 +259,12 @@ pub struct MemoryModel {
     pub mlocked: Option<u64>,
     pub swap_total: Option<u64>,
     pub swap_free: Option<u64>,
+    /// Memory stored in zswap before compression
+    pub zswap_total: Option<u64>,
+    /// Size of the zswap pool, i.e. zswap_total after compression
+    pub zswap_compressed: Option<u64>,
+    /// zswap_compressed as a percentage of total memory
+    pub zswap_pool_pct: Option<f64>,
     pub dirty: Option<u64>,
     pub writeback: Option<u64>,
     pub anon_pages: Option<u64>,
@@ -278,6 +296,12 @@ impl MemoryModel {
             total: meminfo.total.map(|v| v as u64),
             free: meminfo.free.map(|v| v as u64),
             available: meminfo.available.map(|v| v as u64),
+            headroom_pct: match (meminfo.available, meminfo.total) {
+                (Some(available), Some(total)) if total != 0 => {
+                    Some(available as f64 / total as f64 * 100.0)
+                }
+                _ => None,
+            },
             buffers: meminfo.buffers.map(|v| v as u64),
             cached: meminfo.cached.map(|v| v as u64),
             swap_cached: meminfo.swap_cached.map(|v| v as u64),
@@ -289,6 +313,14 @@ impl MemoryModel {
             mlocked: meminfo.mlocked.map(|v| v as u64),
             swap_total: meminfo.swap_total.map(|v| v as u64),
             swap_free: meminfo.swap_free.map(|v| v as u64),
+            zswap_total: meminfo.zswapped.map(|v| v as u64),
+            zswap_compressed: meminfo.zswap.map(|v| v as u64),
+            zswap_pool_pct: match (meminfo.zswap, meminfo.total) {
+                (Some(zswap), Some(total)) if total != 0 => {
+                    Some(zswap as f64 / total as f64 * 100.0)
+                }
+                _ => None,
+            },
             dirty: meminfo.dirty.map(|v| v as u64),
             writeback: meminfo.writeback.map(|v| v as u64),
             anon_pages: meminfo.anon_pages.map(|v| v as u64),
@@ -353,6 +385,44 @@ impl VmModel {
     }
 }
 
+/// Hardware counters of all CPUs, only collected by `below record --perf`.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    below_derive::Queriable
+)]
+pub struct PerfModel {
+    pub ipc: Option<f64>,
+    pub cache_misses_per_sec: Option<f64>,
+    pub branch_misses_per_sec: Option<f64>,
+}
+
+impl PerfModel {
+    fn new(begin: &perf::PerfStat, end: &perf::PerfStat, duration: Duration) -> PerfModel {
+        let delta = |a: Option<u64>, b: Option<u64>| match (a, b) {
+            (Some(a), Some(b)) if a <= b => Some(b - a),
+            _ => None,
+        };
+        PerfModel {
+            ipc: match (
+                delta(begin.instructions, end.instructions),
+                delta(begin.cycles, end.cycles),
+            ) {
+                (Some(instructions), Some(cycles)) if cycles > 0 => {
+                    Some(instructions as f64 / cycles as f64)
+                }
+                _ => None,
+            },
+            cache_misses_per_sec: count_per_sec!(begin.cache_misses, end.cache_misses, duration),
+            branch_misses_per_sec: count_per_sec!(begin.branch_misses, end.branch_misses, duration),
+        }
+    }
+}
+
 #[derive(
     Clone,
     Debug,
@@ -451,6 +521,7 @@ mod test {
             "cpus": [],
             "mem": {},
             "vm": {},
+            "perf": {},
             "disks": {
                 "sda": {
                     "name": "sda",
@@ -465,4 +536,29 @@ mod test {
             Some(Field::F64(42.0))
         );
     }
+
+    #[test]
+    fn perf_model() {
+        let begin = perf::PerfStat {
+            cycles: Some(1000),
+            instructions: Some(2000),
+            cache_misses: Some(100),
+            branch_misses: None,
+        };
+        let end = perf::PerfStat {
+            cycles: Some(3000),
+            instructions: Some(5000),
+            cache_misses: Some(300),
+            branch_misses: Some(10),
+        };
+        let model = PerfModel::new(&begin, &end, Duration::from_secs(2));
+        assert_eq!(model.ipc, Some(1.5));
+        assert_eq!(model.cache_misses_per_sec, Some(100.0));
+        assert_eq!(model.branch_misses_per_sec, None);
+
+        // No cycles counted, e.g. all cpus idle in a deep C-state
+        let model = PerfModel::new(&begin, &begin, Duration::from_secs(2));
+        assert_eq!(model.ipc, None);
+        assert_eq!(model.cache_misses_per_sec, Some(0.0));
+    }
 }
@@ -286,6 +286,8 @@ impl ProcReader {
                     "Mlocked:" => meminfo.mlocked = parse_kb!(path, items.next(), line)?,
                     "SwapTotal:" => meminfo.swap_total = parse_kb!(path, items.next(), line)?,
                     "SwapFree:" => meminfo.swap_free = parse_kb!(path, items.next(), line)?,
+                    "Zswap:" => meminfo.zswap = parse_kb!(path, items.next(), line)?,
+                    "Zswapped:" => meminfo.zswapped = parse_kb!(path, items.next(), line)?,
                     "Dirty:" => meminfo.dirty = parse_kb!(path, items.next(), line)?,
                     "Writeback:" => meminfo.writeback = parse_kb!(path, items.next(), line)?,
                     "AnonPages:" => meminfo.anon_pages = parse_kb!(path, items.next(), line)?,
@@ -537,8 +539,10 @@ impl ProcReader {
         pidstat.num_threads = parse_item!(path, items.get(18), u64, line)?;
 
         let uptime = self.read_uptime_secs()?;
-        pidstat.running_secs = parse_sec!(path, items.get(20), line)?
-            .map(|running_secs_since_boot| (uptime - running_secs_since_boot) as u64);
+        pidstat.start_secs_since_boot = parse_sec!(path, items.get(20), line)?;
+        pidstat.running_secs = pidstat
+            .start_secs_since_boot
+            .map(|start_secs_since_boot| uptime - start_secs_since_boot);
 
         pidstat.rss_bytes =
             parse_item!(path, items.get(22), u64, line)?.map(|pages| pages * *PAGE_SIZE);
@@ -717,6 +721,47 @@ impl ProcReader {
         Self::read_pid_exe_path_from_path(self.path.join(pid.to_string()))
     }
 
+    fn read_pid_wchan_from_path<P: AsRef<Path>>(path: P) -> Result<String> {
+        let path = path.as_ref().join("wchan");
+        let wchan = std::fs::read_to_string(&path).map_err(|e| Error::IoError(path, e))?;
+        // "0" while the process is running, or if the kernel hides the
+        // symbol from the reader
+        match wchan.trim() {
+            "0" => Ok(String::new()),
+            wchan => Ok(wchan.to_owned()),
+        }
+    }
+
+    /// Read the kernel function a process sleeps in from /proc/<pid>/wchan,
+    /// empty while it's running.
+    pub fn read_pid_wchan(&self, pid: u32) -> Result<String> {
+        Self::read_pid_wchan_from_path(self.path.join(pid.to_string()))
+    }
+
+    /// Read the stat of every thread of a process from /proc/<pid>/task.
+    /// Threads that exit while being read are left out.
+    pub fn read_pid_threads(&self, pid: u32) -> Result<TidMap> {
+        let path = self.path.join(pid.to_string()).join("task");
+        let mut tidmap: TidMap = Default::default();
+        for entry in std::fs::read_dir(&path).map_err(|e| Error::IoError(path.clone(), e))? {
+            let entry = entry.map_err(|e| Error::IoError(path.clone(), e))?;
+            let tid = match entry.file_name().to_string_lossy().parse::<i32>() {
+                Ok(tid) => tid,
+                Err(_) => continue,
+            };
+            match self.read_pid_stat_from_path(entry.path()) {
+                Err(Error::IoError(_, ref e))
+                    if e.raw_os_error()
+                        .map_or(false, |ec| ec == 2 || ec == 3 /* ENOENT or ESRCH */) =>
+                {
+                    continue;
+                }
+                res => tidmap.insert(tid, res?),
+            };
+        }
+        Ok(tidmap)
+    }
+
     pub fn read_all_pids(&mut self) -> Result<PidMap> {
         let mut pidmap: PidMap = Default::default();
         for entry in
@@ -888,6 +933,15 @@ pub struct NetReader {
     proc_net_dir: Dir,
 }
 
+// IPv6 files are missing when IPv6 is disabled, which is not an error
+fn ok_if_not_found<T>(res: Result<T>) -> Result<Option<T>> {
+    match res {
+        Ok(v) => Ok(Some(v)),
+        Err(Error::IoError(_, e)) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
 impl NetReader {
     pub fn new() -> Result<NetReader> {
         Self::new_with_custom_path(NET_SYSFS.into(), NET_PROCFS.into())
@@ -1106,6 +1160,116 @@ impl NetReader {
         Ok(res)
     }
 
+    // format like /proc/net/sockstat. Key will be in "{title}_{field}" format
+    fn read_kv_paired_line(&self, stats_filename: &str) -> Result<BTreeMap<String, u64>> {
+        let cur_path = self
+            .proc_net_dir
+            .recover_path()
+            .unwrap_or_else(|_| NET_PROCFS.into())
+            .join(stats_filename);
+        let stats_file = self
+            .proc_net_dir
+            .open_file(stats_filename)
+            .map_err(|e| Error::IoError(cur_path.clone(), e))?;
+        let buf_reader = BufReader::new(stats_file);
+
+        let mut res = BTreeMap::new();
+        for line in buf_reader.lines() {
+            let line = match line {
+                Ok(l) => l,
+                _ => continue,
+            };
+
+            let (key_header, kvs) = match line.split_once(':') {
+                Some(header_and_kvs) => header_and_kvs,
+                None => return Err(Error::InvalidFileFormat(cur_path)),
+            };
+            let kvs: Vec<&str> = kvs.split_whitespace().collect();
+            for kv in kvs.chunks(2) {
+                if kv.len() != 2 {
+                    return Err(Error::InvalidFileFormat(cur_path));
+                }
+                res.insert(
+                    format!("{}_{}", key_header, kv[0]),
+                    kv[1].parse::<u64>().map_err(|_| Error::ParseError {
+                        line: line.clone(),
+                        item: kv[1].into(),
+                        type_name: "u64".into(),
+                        path: cur_path.clone(),
+                    })?,
+                );
+            }
+        }
+
+        Ok(res)
+    }
+
+    // format like /proc/net/tcp. Returns the number of sockets in `state`,
+    // from include/net/tcp_states.h.
+    fn read_tcp_state_count(&self, stats_filename: &str, state: u8) -> Result<u64> {
+        let cur_path = self
+            .proc_net_dir
+            .recover_path()
+            .unwrap_or_else(|_| NET_PROCFS.into())
+            .join(stats_filename);
+        let stats_file = self
+            .proc_net_dir
+            .open_file(stats_filename)
+            .map_err(|e| Error::IoError(cur_path.clone(), e))?;
+        let buf_reader = BufReader::new(stats_file);
+
+        let mut count = 0;
+        // First line is the header
+        for line in buf_reader.lines().skip(1) {
+            let line = match line {
+                Ok(l) => l,
+                _ => continue,
+            };
+
+            let st = match line.split_whitespace().nth(3) {
+                Some(st) => st,
+                None => return Err(Error::InvalidFileFormat(cur_path)),
+            };
+            match u8::from_str_radix(st, 16) {
+                Ok(st) if st == state => count += 1,
+                Ok(_) => {}
+                Err(_) => {
+                    return Err(Error::ParseError {
+                        line: line.clone(),
+                        item: st.into(),
+                        type_name: "u8".into(),
+                        path: cur_path,
+                    });
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Count the TCP sockets in CLOSE_WAIT state, IPv4 and IPv6 summed
+    /// together. Unlike the rest of the netstat, this walks every connection
+    /// in /proc/net/tcp{,6}, so it's not part of `read_netstat`.
+    pub fn read_tcp_close_wait(&self) -> Result<u64> {
+        const TCP_CLOSE_WAIT: u8 = 0x08;
+
+        let tcp = self.read_tcp_state_count("tcp", TCP_CLOSE_WAIT)?;
+        let tcp6 = ok_if_not_found(self.read_tcp_state_count("tcp6", TCP_CLOSE_WAIT))?;
+        Ok(tcp + tcp6.unwrap_or_default())
+    }
+
+    fn read_sock_stat(&self) -> Result<SockStat> {
+        let sockstat_map = self.read_kv_paired_line("sockstat")?;
+        let sockstat6_map = ok_if_not_found(self.read_kv_paired_line("sockstat6"))?;
+
+        Ok(SockStat {
+            tcp_inuse: sockstat_map.get("TCP_inuse").copied(),
+            tcp_orphan: sockstat_map.get("TCP_orphan").copied(),
+            tcp_tw: sockstat_map.get("TCP_tw").copied(),
+            tcp6_inuse: sockstat6_map.and_then(|m| m.get("TCP6_inuse").copied()),
+        })
+    }
+
     fn read_tcp_stat(snmp_map: &BTreeMap<String, u64>) -> TcpStat {
         get_val_from_stats_map!(
             snmp_map,
@@ -1296,6 +1460,10 @@ impl NetReader {
             icmp6: Some(Self::read_icmp6_stat(&snmp6_map)),
             udp: Some(Self::read_udp_stat(&snmp_map)),
             udp6: Some(Self::read_udp6_stat(&snmp6_map)),
+            // Socket counts are optional, so failing to read them doesn't lose
+            // the rest of the sample
+            sockstat: self.read_sock_stat().ok(),
+            tcp_close_wait: None,
         })
     }
 }
@@ -303,6 +303,26 @@ DirectMap1G:     2097152 kB
     assert_eq!(meminfo.direct_map_4k, Some(19_445_616 * 1024));
     assert_eq!(meminfo.direct_map_2m, Some(40_323_072 * 1024));
     assert_eq!(meminfo.direct_map_1g, Some(2_097_152 * 1024));
+    // No zswap entries on this kernel
+    assert_eq!(meminfo.zswap, None);
+    assert_eq!(meminfo.zswapped, None);
+}
+
+#[test]
+fn test_meminfo_zswap() {
+    let meminfo = b"MemTotal:       58603192 kB
+SwapFree:       18537912 kB
+Zswap:            262144 kB
+Zswapped:        1048576 kB
+Dirty:             40896 kB
+";
+    let procfs = TestProcfs::new();
+    procfs.create_file_with_content("meminfo", meminfo);
+    let reader = procfs.get_reader();
+
+    let meminfo = reader.read_meminfo().expect("Failed to read meminfo");
+    assert_eq!(meminfo.zswap, Some(262144 * 1024));
+    assert_eq!(meminfo.zswapped, Some(1048576 * 1024));
 }
 
 #[test]
@@ -700,10 +720,50 @@ fn test_pid_stat() {
     assert_eq!(pidstat.system_usecs, Some(108870000));
     assert_eq!(pidstat.num_threads, Some(1));
     assert_eq!(pidstat.running_secs, Some(1631827 /* rounded up */ - 1028));
+    assert_eq!(pidstat.start_secs_since_boot, Some(1028));
     assert_eq!(pidstat.rss_bytes, Some(12725 * *PAGE_SIZE));
     assert_eq!(pidstat.processor, Some(12));
 }
 
+#[test]
+fn test_pid_threads() {
+    let uptime = b"1631826.55 37530838.66";
+    let stat = |tid: u32, comm: &str, utime: u64| {
+        format!(
+            "{} ({}) S 1 74718 74718 0 -1 4194304 100 0 0 0 {} 5 0 0 20 0 2 0 102803 224440320 12725 18446744073709551615 0 0 0 0 0 0 0 0 0 0 0 0 17 3 0 0 0 0 0",
+            tid, comm, utime
+        )
+    };
+
+    let procfs = TestProcfs::new();
+    procfs.create_file_with_content("uptime", uptime);
+    procfs.create_pid_file_with_content(74718, "stat", stat(74718, "server", 30).as_bytes());
+    procfs.create_dir("74718/task/74718");
+    procfs.create_dir("74718/task/74720");
+    procfs.create_file_with_content(
+        "74718/task/74718/stat",
+        stat(74718, "server", 10).as_bytes(),
+    );
+    procfs.create_file_with_content(
+        "74718/task/74720/stat",
+        stat(74720, "worker", 20).as_bytes(),
+    );
+    let reader = procfs.get_reader();
+    let threads = reader
+        .read_pid_threads(74718)
+        .expect("Failed to read pid threads");
+
+    assert_eq!(
+        threads.keys().copied().collect::<Vec<_>>(),
+        vec![74718, 74720]
+    );
+    assert_eq!(threads[&74720].pid, Some(74720));
+    assert_eq!(threads[&74720].comm, Some("worker".to_string()));
+    assert_eq!(threads[&74720].user_usecs, Some(200000));
+    assert_eq!(threads[&74718].user_usecs, Some(100000));
+    assert!(reader.read_pid_threads(1).is_err());
+}
+
 #[test]
 fn test_pid_mem() {
     let status = b"Name:	below
@@ -1167,12 +1227,47 @@ IpExt: 0 0 72982 72982 26227 6841 3021953584043 3021942373821 11953543 11953543
     netsysfs.create_file_with_content("netstat", netstat);
 }
 
+fn write_net_sockstat(netsysfs: &TestProcfs) {
+    let sockstat = b"sockets: used 1411
+TCP: inuse 62 orphan 3 tw 1127 alloc 95 mem 27
+UDP: inuse 9 mem 10
+UDPLITE: inuse 0
+RAW: inuse 1
+FRAG: inuse 0 memory 0
+";
+    let sockstat6 = b"TCP6: inuse 33
+UDP6: inuse 6
+UDPLITE6: inuse 0
+RAW6: inuse 1
+FRAG6: inuse 0 memory 0
+";
+    netsysfs.create_file_with_content("sockstat", sockstat);
+    netsysfs.create_file_with_content("sockstat6", sockstat6);
+}
+
+fn write_net_tcp(netsysfs: &TestProcfs) {
+    let tcp = b"  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 21941 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:0CEA 0100007F:9A2E 01 00000000:00000000 00:00000000 00000000  1000        0 83121 1 0000000000000000 20 4 30 10 -1
+   2: 0100007F:9A2E 0100007F:0CEA 08 00000000:00000000 00:00000000 00000000  1000        0 83120 1 0000000000000000 20 4 33 10 -1
+   3: 0100007F:9A30 0100007F:0CEA 08 00000000:00000000 00:00000000 00000000  1000        0 83122 1 0000000000000000 20 4 33 10 -1
+   4: 0100007F:9A32 0100007F:0CEA 06 00000000:00000000 03:00000D21 00000000     0        0 0 3 0000000000000000
+";
+    let tcp6 = b"  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:0016 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 21943 1 0000000000000000 100 0 0 10 0
+   1: 00000000000000000000000001000000:1F90 00000000000000000000000001000000:C350 08 00000000:00000000 00:00000000 00000000  1000        0 93121 1 0000000000000000 20 4 30 10 -1
+";
+    netsysfs.create_file_with_content("tcp", tcp);
+    netsysfs.create_file_with_content("tcp6", tcp6);
+}
+
 #[test]
 fn test_read_net_stat() {
     let netsysfs = TestProcfs::new();
     write_net_snmp(&netsysfs);
     write_net_snmp6(&netsysfs);
     write_net_netstat(&netsysfs);
+    write_net_sockstat(&netsysfs);
     write_net_map(&netsysfs);
     let netstat = netsysfs
         .get_net_reader()
@@ -1187,6 +1282,9 @@ fn test_read_net_stat() {
     verify_icmp6(&netstat);
     verify_udp(&netstat);
     verify_udp6(&netstat);
+    verify_sockstat(&netstat);
+    // Only read with read_tcp_close_wait
+    assert_eq!(netstat.tcp_close_wait, None);
     verify_interfaces(&netstat);
 }
 
@@ -1334,6 +1432,77 @@ fn verify_udp6(netstat: &NetStat) {
     assert_eq!(udp6.ignored_multi, Some(0));
 }
 
+fn verify_sockstat(netstat: &NetStat) {
+    let sockstat = netstat
+        .sockstat
+        .as_ref()
+        .expect("Fail to collect sockstat stats");
+    assert_eq!(sockstat.tcp_inuse, Some(62));
+    assert_eq!(sockstat.tcp_orphan, Some(3));
+    assert_eq!(sockstat.tcp_tw, Some(1127));
+    assert_eq!(sockstat.tcp6_inuse, Some(33));
+}
+
+#[test]
+fn test_read_net_stat_without_ipv6_sockstat() {
+    let netsysfs = TestProcfs::new();
+    write_net_snmp(&netsysfs);
+    write_net_snmp6(&netsysfs);
+    write_net_netstat(&netsysfs);
+    write_net_sockstat(&netsysfs);
+    write_net_map(&netsysfs);
+    std::fs::remove_file(netsysfs.path().join("sockstat6")).expect("Fail to remove sockstat6");
+    let netstat = netsysfs
+        .get_net_reader()
+        .read_netstat()
+        .expect("Fail to get NetStat");
+    let sockstat = netstat
+        .sockstat
+        .as_ref()
+        .expect("Fail to collect sockstat stats");
+    assert_eq!(sockstat.tcp_inuse, Some(62));
+    assert_eq!(sockstat.tcp6_inuse, None);
+}
+
+#[test]
+fn test_read_tcp_close_wait() {
+    let netsysfs = TestProcfs::new();
+    write_net_tcp(&netsysfs);
+    let reader = netsysfs.get_net_reader();
+    assert_eq!(
+        reader
+            .read_tcp_close_wait()
+            .expect("Fail to count CLOSE_WAIT sockets"),
+        3
+    );
+
+    std::fs::remove_file(netsysfs.path().join("tcp6")).expect("Fail to remove tcp6");
+    assert_eq!(
+        reader
+            .read_tcp_close_wait()
+            .expect("Fail to count CLOSE_WAIT sockets"),
+        2
+    );
+
+    std::fs::remove_file(netsysfs.path().join("tcp")).expect("Fail to remove tcp");
+    assert!(reader.read_tcp_close_wait().is_err());
+}
+
+#[test]
+fn test_read_net_stat_without_sockstat() {
+    let netsysfs = TestProcfs::new();
+    write_net_snmp(&netsysfs);
+    write_net_snmp6(&netsysfs);
+    write_net_netstat(&netsysfs);
+    write_net_map(&netsysfs);
+    let netstat = netsysfs
+        .get_net_reader()
+        .read_netstat()
+        .expect("Fail to get NetStat");
+    assert_eq!(netstat.sockstat, None);
+    verify_tcp(&netstat);
+}
+
 fn verify_interfaces(netstat: &NetStat) {
     let netmap = netstat
         .interfaces
@@ -1380,6 +1549,28 @@ fn test_read_pid_exec() {
     assert_eq!(exe_path, res);
 }
 
+#[test]
+fn test_read_pid_wchan() {
+    let procfs = TestProcfs::new();
+    procfs.create_pid_file_with_content(1234, "wchan", b"do_sys_poll");
+    procfs.create_pid_file_with_content(1235, "wchan", b"0");
+    let reader = procfs.get_reader();
+
+    assert_eq!(
+        reader
+            .read_pid_wchan(1234)
+            .expect("Failed to read pid wchan file"),
+        "do_sys_poll"
+    );
+    assert_eq!(
+        reader
+            .read_pid_wchan(1235)
+            .expect("Failed to read pid wchan file"),
+        ""
+    );
+    assert!(reader.read_pid_wchan(1236).is_err());
+}
+
 #[test]
 fn test_read_mountinfo() {
     let mountinfo = b"
@@ -61,6 +61,9 @@ pub struct MemInfo {
     pub mlocked: Option<u64>,
     pub swap_total: Option<u64>,
     pub swap_free: Option<u64>,
+    // Only present with zswap enabled kernels
+    pub zswap: Option<u64>,
+    pub zswapped: Option<u64>,
     pub dirty: Option<u64>,
     pub writeback: Option<u64>,
     pub anon_pages: Option<u64>,
@@ -134,6 +137,16 @@ pub struct TcpStat {
     pub in_csum_errors: Option<u64>,
 }
 
+/// TCP socket counts from /proc/net/sockstat{,6}. IPv4 and IPv6 are kept
+/// separate here.
+#[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SockStat {
+    pub tcp_inuse: Option<u64>,
+    pub tcp_orphan: Option<u64>,
+    pub tcp_tw: Option<u64>,
+    pub tcp6_inuse: Option<u64>,
+}
+
 #[derive(Default, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct TcpExtStat {
     pub syncookies_sent: Option<u64>,
@@ -331,6 +344,7 @@ pub struct PidStat {
     pub system_usecs: Option<u64>,
     pub num_threads: Option<u64>,
     pub running_secs: Option<u64>,
+    pub start_secs_since_boot: Option<u64>,
     pub rss_bytes: Option<u64>,
     pub processor: Option<i32>,
 }
@@ -363,9 +377,15 @@ pub struct PidInfo {
     pub cmdline_vec: Option<Vec<String>>,
     pub exe_path: Option<String>,
     pub mem: PidMem,
+    /// Stats of each thread from /proc/<pid>/task, only read when asked for
+    pub threads: Option<TidMap>,
+    /// Kernel function the process sleeps in from /proc/<pid>/wchan, only
+    /// read when asked for
+    pub wchan: Option<String>,
 }
 
 pub type PidMap = BTreeMap<i32, PidInfo>;
+pub type TidMap = BTreeMap<i32, PidStat>;
 pub type NetMap = BTreeMap<String, InterfaceStat>;
 pub type DiskMap = BTreeMap<String, DiskStat>;
 
@@ -381,6 +401,10 @@ pub struct NetStat {
     pub icmp6: Option<Icmp6Stat>,
     pub udp: Option<UdpStat>,
     pub udp6: Option<Udp6Stat>,
+    pub sockstat: Option<SockStat>,
+    /// TCP sockets in CLOSE_WAIT state from /proc/net/tcp{,6}, only read when
+    /// asked for
+    pub tcp_close_wait: Option<u64>,
 }
 
 impl fmt::Display for PidState {
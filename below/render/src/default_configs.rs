@@ -15,7 +15,8 @@
 use super::*;
 
 use RenderFormat::{
-    MaxOrReadableSize, PageReadableSize, Precision, ReadableSize, SectorReadableSize,
+    Datetime, Duration, MaxOrReadableSize, PageReadableSize, Precision, ReadableSize,
+    SectorReadableSize, YesNo,
 };
 
 impl HasRenderConfig for model::SingleCgroupModel {
@@ -26,6 +27,15 @@ impl HasRenderConfig for model::SingleCgroupModel {
             Name => rc.title("Name").width(50),
             FullPath => rc.title("Full Path").width(50),
             InodeNumber => rc.title("Inode Number"),
+            Reset => rc.title("Reset").format(YesNo),
+            Controllers => rc.title("Controllers").width(30),
+            CpuWeight => rc.title("CPU Weight"),
+            IoWeight => rc.title("IO Weight"),
+            IoWeightDevices => rc.title("IO Weight Devices").width(30),
+            Frozen => rc.title("Frozen").format(YesNo),
+            NrDescendants => rc.title("Nr Descendants"),
+            NrDyingDescendants => rc.title("Nr Dying Descendants"),
+            Device => rc.title("Device").width(15),
             Cpu(field_id) => model::CgroupCpuModel::get_render_config_builder(field_id),
             Io(field_id) => model::CgroupIoModel::get_render_config_builder(field_id),
             IoDetails(field_id) => {
@@ -48,6 +58,12 @@ impl HasRenderConfig for model::CgroupCpuModel {
             NrPeriodsPerSec => rc.title("Nr Period").suffix("/s").format(Precision(2)),
             NrThrottledPerSec => rc.title("Nr Throttled").suffix("/s").format(Precision(2)),
             ThrottledPct => rc.title("Throttled").suffix("%").format(Precision(2)),
+            UsageUsec => rc.title("CPU Usage(usec)"),
+            UserUsec => rc.title("CPU User(usec)"),
+            SystemUsec => rc.title("CPU Sys(usec)"),
+            NrPeriods => rc.title("Nr Periods"),
+            NrThrottled => rc.title("Nr Throttled(total)"),
+            ThrottledUsec => rc.title("Throttled(usec)"),
         }
     }
 }
@@ -64,6 +80,9 @@ impl HasRenderConfig for model::CgroupIoModel {
             DbytesPerSec => rc.title("Discards").suffix("/s").format(ReadableSize),
             DiosPerSec => rc.title("Discard IOPS").format(Precision(1)),
             RwbytesPerSec => rc.title("RW Total").suffix("/s").format(ReadableSize),
+            CostUsagePct => rc.title("Cost Usage").suffix("%").format(Precision(2)),
+            CostVrate => rc.title("Cost Vrate").suffix("%").format(Precision(2)),
+            CostIndebtPct => rc.title("Cost Debt").suffix("%").format(Precision(2)),
         }
     }
 }
@@ -75,12 +94,20 @@ impl HasRenderConfig for model::CgroupMemoryModel {
         match field_id {
             Total => rc.title("Memory").format(ReadableSize),
             Swap => rc.title("Memory Swap").format(ReadableSize),
+            Zswap => rc.title("Memory Zswap").format(ReadableSize),
             MemoryHigh => rc.title("Memory High").format(MaxOrReadableSize),
+            MemoryMax => rc.title("Memory Max").format(MaxOrReadableSize),
+            Headroom => rc.title("Memory Headroom").format(ReadableSize),
             EventsLow => rc.title("Events Low"),
             EventsHigh => rc.title("Events High"),
             EventsMax => rc.title("Events Max"),
             EventsOom => rc.title("Events OOM"),
             EventsOomKill => rc.title("Events Kill"),
+            EventsLowPerSec => rc.title("Events Low/s").format(Precision(2)),
+            EventsHighPerSec => rc.title("Events High/s").format(Precision(2)),
+            EventsMaxPerSec => rc.title("Events Max/s").format(Precision(2)),
+            EventsOomPerSec => rc.title("Events OOM/s").format(Precision(2)),
+            EventsOomKillPerSec => rc.title("Events Kill/s").format(Precision(2)),
             Anon => rc.title("Anon").format(ReadableSize),
             File => rc.title("File").format(ReadableSize),
             KernelStack => rc.title("Kernel Stack").format(ReadableSize),
@@ -106,6 +133,10 @@ impl HasRenderConfig for model::CgroupMemoryModel {
             Pgrefill => rc.title("Pgrefill/s"),
             Pgscan => rc.title("Pgscan/s"),
             Pgsteal => rc.title("Pgsteal/s"),
+            PgscanKswapd => rc.title("Pgscan Kswapd/s"),
+            PgscanDirect => rc.title("Pgscan Direct/s"),
+            PgstealKswapd => rc.title("Pgsteal Kswapd/s"),
+            PgstealDirect => rc.title("Pgsteal Direct/s"),
             Pgactivate => rc.title("Pgactivate/s"),
             Pgdeactivate => rc.title("Pgdeactivate/s"),
             Pglazyfree => rc.title("Pglazyfree/s"),
@@ -132,6 +163,66 @@ impl HasRenderConfig for model::CgroupPressureModel {
                 .suffix("%")
                 .format(Precision(2)),
             MemoryFullPct => rc.title("Mem Pressure").suffix("%").format(Precision(2)),
+            CpuSomeAvg10 => rc
+                .title("CPU Some Pressure Avg10")
+                .suffix("%")
+                .format(Precision(2)),
+            CpuSomeAvg60 => rc
+                .title("CPU Some Pressure Avg60")
+                .suffix("%")
+                .format(Precision(2)),
+            CpuSomeAvg300 => rc
+                .title("CPU Some Pressure Avg300")
+                .suffix("%")
+                .format(Precision(2)),
+            IoSomeAvg10 => rc
+                .title("I/O Some Pressure Avg10")
+                .suffix("%")
+                .format(Precision(2)),
+            IoSomeAvg60 => rc
+                .title("I/O Some Pressure Avg60")
+                .suffix("%")
+                .format(Precision(2)),
+            IoSomeAvg300 => rc
+                .title("I/O Some Pressure Avg300")
+                .suffix("%")
+                .format(Precision(2)),
+            IoFullAvg10 => rc
+                .title("I/O Full Pressure Avg10")
+                .suffix("%")
+                .format(Precision(2)),
+            IoFullAvg60 => rc
+                .title("I/O Full Pressure Avg60")
+                .suffix("%")
+                .format(Precision(2)),
+            IoFullAvg300 => rc
+                .title("I/O Full Pressure Avg300")
+                .suffix("%")
+                .format(Precision(2)),
+            MemorySomeAvg10 => rc
+                .title("Mem Some Pressure Avg10")
+                .suffix("%")
+                .format(Precision(2)),
+            MemorySomeAvg60 => rc
+                .title("Mem Some Pressure Avg60")
+                .suffix("%")
+                .format(Precision(2)),
+            MemorySomeAvg300 => rc
+                .title("Mem Some Pressure Avg300")
+                .suffix("%")
+                .format(Precision(2)),
+            MemoryFullAvg10 => rc
+                .title("Mem Full Pressure Avg10")
+                .suffix("%")
+                .format(Precision(2)),
+            MemoryFullAvg60 => rc
+                .title("Mem Full Pressure Avg60")
+                .suffix("%")
+                .format(Precision(2)),
+            MemoryFullAvg300 => rc
+                .title("Mem Full Pressure Avg300")
+                .suffix("%")
+                .format(Precision(2)),
         }
     }
 }
@@ -171,6 +262,10 @@ impl HasRenderConfig for model::TcpModel {
             InErrs => rc.title("TcpInErrors"),
             OutRstsPerSec => rc.title("TcpOutRsts/s"),
             InCsumErrors => rc.title("TcpInCsumErrors"),
+            TimeWait => rc.title("TcpTimeWait"),
+            CloseWait => rc.title("TcpCloseWait"),
+            SocketsInUse => rc.title("TcpSocketsInUse"),
+            OrphanSockets => rc.title("TcpOrphanSockets"),
         }
     }
 }
@@ -338,11 +433,24 @@ impl HasRenderConfig for model::SingleProcessModel {
         let rc = RenderConfigBuilder::new();
         match field_id {
             Pid => rc.title("Pid"),
+            Tid => rc.title("Tid"),
             Ppid => rc.title("Ppid"),
             Comm => rc.title("Comm").width(30),
             State => rc.title("State"),
+            Wchan => rc.title("Wchan").width(20),
             UptimeSecs => rc.title("Uptime(sec)"),
+            Age => rc.title("Age").format(Duration),
+            StartTime => rc.title("Start Time").width(19).format(Datetime),
+            Reset => rc.title("Reset").format(YesNo),
             Cgroup => rc.title("Cgroup").width(50).fold(FoldOption::Name),
+            MemSomePressure => rc
+                .title("Cgroup Mem Some Pressure")
+                .suffix("%")
+                .format(Precision(2)),
+            IoSomePressure => rc
+                .title("Cgroup I/O Some Pressure")
+                .suffix("%")
+                .format(Precision(2)),
             Io(field_id) => model::ProcessIoModel::get_render_config_builder(field_id),
             Mem(field_id) => model::ProcessMemoryModel::get_render_config_builder(field_id),
             Cpu(field_id) => model::ProcessCpuModel::get_render_config_builder(field_id),
@@ -411,6 +519,7 @@ impl HasRenderConfig for model::SystemModel {
             Cpus(field_id) => Vec::<model::SingleCpuModel>::get_render_config_builder(field_id),
             Mem(field_id) => model::MemoryModel::get_render_config_builder(field_id),
             Vm(field_id) => model::VmModel::get_render_config_builder(field_id),
+            Perf(field_id) => model::PerfModel::get_render_config_builder(field_id),
             Disks(field_id) => {
                 model::SingleDiskModel::get_render_config_builder(&field_id.subquery_id)
             }
@@ -478,6 +587,7 @@ impl HasRenderConfig for model::MemoryModel {
             Total => rc.title("Total").format(ReadableSize),
             Free => rc.title("Free").format(ReadableSize),
             Available => rc.title("Available").format(ReadableSize),
+            HeadroomPct => rc.title("Headroom").suffix("%").format(Precision(2)),
             Buffers => rc.title("Buffers").format(ReadableSize),
             Cached => rc.title("Cached").format(ReadableSize),
             SwapCached => rc.title("Swap Cached").format(ReadableSize),
@@ -489,6 +599,9 @@ impl HasRenderConfig for model::MemoryModel {
             Mlocked => rc.title("Mlocked").format(ReadableSize),
             SwapTotal => rc.title("Swap Total").format(ReadableSize),
             SwapFree => rc.title("Swap Free").format(ReadableSize),
+            ZswapTotal => rc.title("Zswap Total").format(ReadableSize),
+            ZswapCompressed => rc.title("Zswap Compressed").format(ReadableSize),
+            ZswapPoolPct => rc.title("Zswap Pool").suffix("%").format(Precision(2)),
             Dirty => rc.title("Dirty").format(ReadableSize),
             Writeback => rc.title("Writeback").format(ReadableSize),
             AnonPages => rc.title("Anon Pages").format(ReadableSize),
@@ -534,6 +647,18 @@ impl HasRenderConfig for model::VmModel {
     }
 }
 
+impl HasRenderConfig for model::PerfModel {
+    fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
+        use model::PerfModelFieldId::*;
+        let rc = RenderConfigBuilder::new();
+        match field_id {
+            Ipc => rc.title("IPC").format(Precision(2)),
+            CacheMissesPerSec => rc.title("Cache Misses").suffix("/s").format(Precision(1)),
+            BranchMissesPerSec => rc.title("Branch Misses").suffix("/s").format(Precision(1)),
+        }
+    }
+}
+
 impl HasRenderConfig for model::SingleDiskModel {
     fn get_render_config_builder(field_id: &Self::FieldId) -> RenderConfigBuilder {
         use model::SingleDiskModelFieldId::*;
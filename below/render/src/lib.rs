@@ -14,7 +14,7 @@
 
 mod default_configs;
 
-use common::util::{convert_bytes, fold_string};
+use common::util::{convert_bytes, convert_duration, fold_string, timestamp_to_datetime};
 use model::{Field, Queriable};
 
 /// Specifies how to format a Field into String
@@ -34,6 +34,14 @@ pub enum RenderFormat {
     /// Only works on int Fields. Same as ReadableSize except when Field is -1,
     /// in which case "max" is returned.
     MaxOrReadableSize,
+    /// Only works on int Fields. Format seconds since epoch as local
+    /// datetime.
+    Datetime,
+    /// Only works on int Fields. Format number of seconds as human-readable
+    /// duration (e.g. 3d 4h).
+    Duration,
+    /// Only works on bool Fields. Format as yes or no.
+    YesNo,
 }
 
 /// Specifies how a long string is folded to fit into a shorter width.
@@ -43,6 +51,8 @@ pub enum FoldOption {
     Name,
     /// Starts elision from first subdirectory (second '/' as we skip root).
     Path,
+    /// Omits the beginning of the string, keeping as much of its end as fits.
+    KeepEnd,
 }
 
 /// Config object for specifying how to render a Field. Options are ordered
@@ -141,7 +151,7 @@ impl RenderConfig {
 
     /// Value for fixed-width rendering, with default as title width + 2 and
     /// minimum width 10.
-    fn get_width(&self) -> usize {
+    pub fn get_width(&self) -> usize {
         const MIN_WIDTH: usize = 10;
         std::cmp::max(MIN_WIDTH, self.width.unwrap_or(self.get_title().len() + 2))
     }
@@ -171,6 +181,13 @@ impl RenderConfig {
                         convert_bytes(field as f64)
                     }
                 }
+                Datetime => timestamp_to_datetime(&(f64::from(field) as i64)),
+                Duration => convert_duration(f64::from(field) as u64),
+                YesNo => match field {
+                    Field::Bool(true) => "yes".to_owned(),
+                    Field::Bool(false) => "no".to_owned(),
+                    field => field.to_string(),
+                },
             },
             None => field.to_string(),
         }
@@ -180,6 +197,10 @@ impl RenderConfig {
         match self.fold {
             Some(FoldOption::Name) => fold_string(val, width, 0, |c: char| !c.is_alphanumeric()),
             Some(FoldOption::Path) => fold_string(val, width, 1, |c: char| c == '/'),
+            Some(FoldOption::KeepEnd) => {
+                let len = val.chars().count();
+                val.chars().skip(len.saturating_sub(width)).collect()
+            }
             None => val.to_owned(),
         }
     }
@@ -178,6 +178,27 @@ enum Command {
         /// Flag to disable eBPF-based exitstats
         #[clap(long)]
         disable_exitstats: bool,
+        /// Collect system wide IPC, cache misses and branch misses from
+        /// hardware perf counters. Needs CAP_PERFMON or root, fields are left
+        /// empty if the counters are unavailable.
+        #[clap(long)]
+        perf: bool,
+        /// Also collect the cpu time of each thread from /proc/<pid>/task, for
+        /// `below dump process --threads`. Costs more time and space for
+        /// processes with many threads.
+        #[clap(long)]
+        threads: bool,
+        /// Also collect the kernel function each process sleeps in from
+        /// /proc/<pid>/wchan, for the process field `wchan`. Missing for
+        /// processes whose wchan isn't readable, e.g. without ptrace access.
+        #[clap(long)]
+        wchan: bool,
+        /// Also count the TCP sockets in CLOSE_WAIT state from
+        /// /proc/net/tcp{,6}, for the transport field `tcp.close_wait`. Walks
+        /// every connection on each sample, which costs time on hosts with
+        /// many connections.
+        #[clap(long)]
+        tcp_close_wait: bool,
         /// Options for compression
         #[clap(flatten)]
         compress_opts: CompressOpts,
@@ -189,7 +210,7 @@ enum Command {
         /// Relative: {humantime} ago, e.g. "2 days 3 hr 15m 10sec ago"{n}
         /// Relative short: Mixed {time_digit}{time_unit_char} E.g. 10m, 3d2h, 5h30s. Case insensitive.{n}
         /// Absolute: "Jan 01 23:59", "01/01/1970 11:59PM", "1970-01-01 23:59:59"{n}
-        /// Unix Epoch: 1589808367
+        /// Unix Epoch: 1589808367, in milliseconds 1589808367000 or ms:1589808367000
         /// _
         #[clap(short, long, verbatim_doc_comment)]
         time: String,
@@ -502,6 +523,11 @@ where
             error!(logger, "{:#}", e);
             0
         }
+        // Not a failure, but scripts need to tell it apart from dumped rows
+        Err(e) if e.is::<dump::EmptyResult>() => {
+            warn!(logger, "{:#}", e);
+            dump::EMPTY_RESULT_EXIT_CODE
+        }
         Err(e) => {
             if logutil::get_current_log_target() == logutil::TargetLog::File {
                 logutil::set_current_log_target(logutil::TargetLog::All);
@@ -589,6 +615,10 @@ fn real_main(init: init::InitToken) {
             ref skew_detection_threshold_ms,
             ref disable_disk_stat,
             ref disable_exitstats,
+            ref perf,
+            ref threads,
+            ref wchan,
+            ref tcp_close_wait,
             ref compress_opts,
         } => {
             logutil::set_current_log_target(logutil::TargetLog::Term);
@@ -611,6 +641,10 @@ fn real_main(init: init::InitToken) {
                         debug,
                         *disable_disk_stat,
                         *disable_exitstats,
+                        *perf,
+                        *threads,
+                        *wchan,
+                        *tcp_close_wait,
                         compress_opts,
                     )
                 },
@@ -836,6 +870,10 @@ fn record(
     debug: bool,
     disable_disk_stat: bool,
     disable_exitstats: bool,
+    perf: bool,
+    threads: bool,
+    wchan: bool,
+    tcp_close_wait: bool,
     compress_opts: &CompressOpts,
 ) -> Result<()> {
     debug!(logger, "Starting up!");
@@ -877,6 +915,10 @@ fn record(
             collect_io_stat,
             disable_disk_stat,
             cgroup_re,
+            collect_perf: perf,
+            collect_threads: threads,
+            collect_wchan: wchan,
+            collect_tcp_close_wait: tcp_close_wait,
         },
     );
 
@@ -21,7 +21,7 @@ use slog::{self, error};
 use common::util;
 use model::{self, Model};
 
-use crate::{DataFrame, Direction, LocalStore, RemoteStore, Store};
+use crate::{DataFrame, Direction, LocalStore, MergedStore, RemoteStore, Store};
 
 /// A SamplePackage consists of enough information to construct a Model.
 // A SamplePackage consists of the sample(newer_sample) at target timestamp
@@ -151,6 +151,14 @@ impl ModelStore for RemoteStore {
     }
 }
 
+impl ModelStore for MergedStore<LocalStore> {
+    type ModelType = Model;
+
+    fn to_model(&self, sample_package: &SamplePackage<DataFrame>) -> Option<Model> {
+        Some(sample_package.to_model())
+    }
+}
+
 /// The Advance data structure will be used as an operational
 /// bridge between controller and store.
 pub struct Advance<FrameType, MType> {
@@ -359,6 +367,22 @@ pub fn new_advance_local(
     }
 }
 
+/// Construct a new Advance object reading several local stores as one
+pub fn new_advance_merged(
+    logger: slog::Logger,
+    store_dirs: Vec<PathBuf>,
+    timestamp: SystemTime,
+) -> Advance<DataFrame, Model> {
+    let store = Box::new(MergedStore::new_local(logger.clone(), store_dirs));
+    Advance {
+        logger,
+        store,
+        cached_sample: None,
+        target_timestamp: timestamp,
+        current_direction: Direction::Forward,
+    }
+}
+
 /// Construct a new Advance object with remote store
 pub fn new_advance_remote(
     logger: slog::Logger,
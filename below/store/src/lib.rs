@@ -796,6 +796,84 @@ impl Store for RemoteStore {
     }
 }
 
+/// Several stores read as one, e.g. store directories of different hosts or
+/// of adjacent and overlapping time ranges. Samples are ordered by timestamp
+/// across stores. If more than one store has a sample at a timestamp, the
+/// one of the first store wins and a warning is logged if they differ.
+pub struct MergedStore<S> {
+    logger: slog::Logger,
+    // (name, store) in order of preference. The name is used in warnings.
+    stores: Vec<(String, S)>,
+}
+
+impl<S> MergedStore<S> {
+    pub fn new(logger: slog::Logger, stores: Vec<(String, S)>) -> Self {
+        Self { logger, stores }
+    }
+}
+
+impl MergedStore<LocalStore> {
+    pub fn new_local(logger: slog::Logger, dirs: Vec<PathBuf>) -> Self {
+        let stores = dirs
+            .into_iter()
+            .map(|dir| {
+                (
+                    dir.display().to_string(),
+                    LocalStore::new(logger.clone(), dir),
+                )
+            })
+            .collect();
+        Self::new(logger, stores)
+    }
+}
+
+impl<S> Store for MergedStore<S>
+where
+    S: Store,
+    S::SampleType: PartialEq,
+{
+    type SampleType = S::SampleType;
+
+    /// The nearest sample in `direction` over all stores.
+    fn get_sample_at_timestamp(
+        &mut self,
+        timestamp: SystemTime,
+        direction: Direction,
+    ) -> Result<Option<(SystemTime, Self::SampleType)>> {
+        // (timestamp, sample, index of store)
+        let mut res: Option<(SystemTime, Self::SampleType, usize)> = None;
+        for idx in 0..self.stores.len() {
+            let (name, store) = &mut self.stores[idx];
+            let (ts, sample) = match store
+                .get_sample_at_timestamp(timestamp, direction)
+                .with_context(|| format!("Failed to read store {}", name))?
+            {
+                Some(found) => found,
+                None => continue,
+            };
+            match &res {
+                Some((res_ts, res_sample, res_idx)) if *res_ts == ts => {
+                    if *res_sample != sample {
+                        warn!(
+                            self.logger,
+                            "Stores {} and {} have different samples at {}, using the one of {}",
+                            self.stores[*res_idx].0,
+                            self.stores[idx].0,
+                            get_unix_timestamp(ts),
+                            self.stores[*res_idx].0,
+                        );
+                    }
+                }
+                Some((res_ts, _, _))
+                    if (direction == Direction::Forward && *res_ts < ts)
+                        || (direction == Direction::Reverse && *res_ts > ts) => {}
+                _ => res = Some((ts, sample, idx)),
+            }
+        }
+        Ok(res.map(|(ts, sample, _)| (ts, sample)))
+    }
+}
+
 trait Crc32 {
     fn crc32(&self) -> u32;
 }
@@ -980,6 +1058,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn merged_store() {
+        let ts = std::time::UNIX_EPOCH + Duration::from_secs(SHARD_TIME);
+        let write = |dir: &TempDir, samples: &[(u64, i64)]| {
+            let mut writer =
+                StoreWriter::new(get_logger(), dir, CompressionMode::None, Format::Cbor)
+                    .expect("Failed to create store");
+            for (offset, value) in samples {
+                let mut frame = DataFrame::default();
+                frame.sample.cgroup.memory_current = Some(*value);
+                writer
+                    .put(ts + Duration::from_secs(*offset), &frame)
+                    .expect("Failed to store data");
+            }
+        };
+        let dir_a = TempDir::new("below_store_test").expect("tempdir failed");
+        let dir_b = TempDir::new("below_store_test").expect("tempdir failed");
+        write(&dir_a, &[(0, 0), (2, 2), (3, 3)]);
+        // Overlaps at 3 with different data
+        write(&dir_b, &[(1, 1), (3, 30), (4, 4)]);
+
+        let mut store = MergedStore::new_local(
+            get_logger(),
+            vec![dir_a.path().to_path_buf(), dir_b.path().to_path_buf()],
+        );
+        let mut next = ts;
+        let mut values = Vec::new();
+        while let Some((sample_ts, frame)) = store
+            .get_sample_at_timestamp(next, Direction::Forward)
+            .expect("Failed to read sample")
+        {
+            values.push(frame.sample.cgroup.memory_current.unwrap());
+            next = sample_ts + Duration::from_secs(1);
+        }
+        // Timestamp 3 is only dumped once, from the first store
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+
+        let (sample_ts, frame) = store
+            .get_sample_at_timestamp(ts + Duration::from_secs(10), Direction::Reverse)
+            .expect("Failed to read sample")
+            .expect("Did not find stored sample");
+        assert_ts!(sample_ts, ts + Duration::from_secs(4));
+        assert_eq!(frame.sample.cgroup.memory_current, Some(4));
+    }
+
     #[test]
     fn write_index_padding() {
         let dir = TempDir::new("below_store_test").expect("tempdir failed");
@@ -200,15 +200,17 @@ pub mod default_tabs {
         NrPeriodsPerSec, NrThrottledPerSec, SystemPct, ThrottledPct, UsagePct, UserPct,
     };
     use model::CgroupIoModelFieldId::{
-        DbytesPerSec, DiosPerSec, RbytesPerSec, RiosPerSec, RwbytesPerSec, WbytesPerSec, WiosPerSec,
+        CostIndebtPct, CostUsagePct, CostVrate, DbytesPerSec, DiosPerSec, RbytesPerSec, RiosPerSec,
+        RwbytesPerSec, WbytesPerSec, WiosPerSec,
     };
     use model::CgroupMemoryModelFieldId::{
         ActiveAnon, ActiveFile, Anon, AnonThp, EventsHigh, EventsLow, EventsMax, EventsOom,
-        EventsOomKill, File, FileDirty, FileMapped, FileWriteback, InactiveAnon, InactiveFile,
-        KernelStack, Pgactivate, Pgdeactivate, Pgfault, Pglazyfree, Pglazyfreed, Pgmajfault,
-        Pgrefill, Pgscan, Pgsteal, Shmem, Slab, SlabReclaimable, SlabUnreclaimable, Sock, Swap,
+        EventsOomKill, File, FileDirty, FileMapped, FileWriteback, Headroom, InactiveAnon,
+        InactiveFile, KernelStack, Pgactivate, Pgdeactivate, Pgfault, Pglazyfree, Pglazyfreed,
+        Pgmajfault, Pgrefill, Pgscan, PgscanDirect, PgscanKswapd, Pgsteal, PgstealDirect,
+        PgstealKswapd, Shmem, Slab, SlabReclaimable, SlabUnreclaimable, Sock, Swap,
         ThpCollapseAlloc, ThpFaultAlloc, Total, Unevictable, WorkingsetActivate,
-        WorkingsetNodereclaim, WorkingsetRefault,
+        WorkingsetNodereclaim, WorkingsetRefault, Zswap,
     };
     use model::CgroupPressureModelFieldId::{
         CpuSomePct, IoFullPct, IoSomePct, MemoryFullPct, MemorySomePct,
@@ -252,6 +254,8 @@ pub mod default_tabs {
         CgroupTab::new(vec![
             ViewItem::from_default(Mem(Total)),
             ViewItem::from_default(Mem(Swap)),
+            ViewItem::from_default(Mem(Zswap)),
+            ViewItem::from_default(Mem(Headroom)),
             ViewItem::from_default(Mem(Anon)),
             ViewItem::from_default(Mem(File)),
             ViewItem::from_default(Mem(KernelStack)),
@@ -277,6 +281,10 @@ pub mod default_tabs {
             ViewItem::from_default(Mem(Pgrefill)),
             ViewItem::from_default(Mem(Pgscan)),
             ViewItem::from_default(Mem(Pgsteal)),
+            ViewItem::from_default(Mem(PgscanKswapd)),
+            ViewItem::from_default(Mem(PgscanDirect)),
+            ViewItem::from_default(Mem(PgstealKswapd)),
+            ViewItem::from_default(Mem(PgstealDirect)),
             ViewItem::from_default(Mem(Pgactivate)),
             ViewItem::from_default(Mem(Pgdeactivate)),
             ViewItem::from_default(Mem(Pglazyfree)),
@@ -300,6 +308,9 @@ pub mod default_tabs {
             ViewItem::from_default(Io(WiosPerSec)),
             ViewItem::from_default(Io(DiosPerSec)),
             ViewItem::from_default(Io(RwbytesPerSec)),
+            ViewItem::from_default(Io(CostUsagePct)),
+            ViewItem::from_default(Io(CostVrate)),
+            ViewItem::from_default(Io(CostIndebtPct)),
         ])
     });
 